@@ -128,10 +128,10 @@ impl PKI {
             PKI::RSA(rsa_keypair) => rsa_keypair.private_key_raw_bytes(),
     
             #[cfg(feature = "dilithium")]
-            PKI::Dilithium(dilithium_keypair) => dilithium_keypair.private_key_raw_bytes(),
+            PKI::Dilithium(dilithium_keypair) => dilithium_keypair.private_key_raw_bytes().to_vec(),
     
             #[cfg(feature = "falcon")]
-            PKI::Falcon(falcon_keypair) => falcon_keypair.private_key_raw_bytes(),
+            PKI::Falcon(falcon_keypair) => falcon_keypair.private_key_raw_bytes().to_vec(),
     
             #[cfg(feature = "ed25519")]
             PKI::Ed25519(ed25519_keypair) => ed25519_keypair.private_key_raw_bytes(),
@@ -0,0 +1,51 @@
+// identity\src\algorithm_info.rs
+use serde::Serialize;
+
+/// Describes a key pair algorithm compiled into this build.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct AlgorithmInfo {
+  /// The algorithm's name, as returned by `PKITraits::key_type` and `peek_algorithm`.
+  pub name: &'static str,
+  /// The cargo feature that enables it.
+  pub feature: &'static str,
+  /// Whether the key pair can sign and verify.
+  pub signing: bool,
+  /// Whether the key pair implements `KeyExchange`.
+  pub key_exchange: bool,
+  /// Whether the scheme is designed to resist quantum attacks.
+  pub post_quantum: bool,
+}
+
+/// Lists the algorithms enabled in this build, in a stable order.
+// Each push is behind its own feature, so this cannot be a single `vec![]`.
+#[allow(clippy::vec_init_then_push)]
+pub fn supported_algorithms() -> Vec<AlgorithmInfo> {
+  #[allow(unused_mut)]
+  let mut algorithms = vec![];
+
+  #[cfg(feature = "pki_rsa")]
+  algorithms.push(AlgorithmInfo { name: "RSA", feature: "pki_rsa", signing: true, key_exchange: true, post_quantum: false });
+
+  #[cfg(feature = "secp256k1")]
+  algorithms.push(AlgorithmInfo { name: "SECP256K1", feature: "secp256k1", signing: true, key_exchange: true, post_quantum: false });
+
+  #[cfg(feature = "ecdsa")]
+  algorithms.push(AlgorithmInfo { name: "ECDSA", feature: "ecdsa", signing: true, key_exchange: true, post_quantum: false });
+
+  #[cfg(feature = "ed25519")]
+  algorithms.push(AlgorithmInfo { name: "ED25519", feature: "ed25519", signing: true, key_exchange: true, post_quantum: false });
+
+  #[cfg(feature = "dilithium")]
+  algorithms.push(AlgorithmInfo { name: "Dilithium", feature: "dilithium", signing: true, key_exchange: false, post_quantum: true });
+
+  #[cfg(feature = "spincs")]
+  algorithms.push(AlgorithmInfo { name: "SPHINCS+", feature: "spincs", signing: true, key_exchange: false, post_quantum: true });
+
+  #[cfg(feature = "falcon")]
+  algorithms.push(AlgorithmInfo { name: "Falcon", feature: "falcon", signing: true, key_exchange: false, post_quantum: true });
+
+  #[cfg(feature = "kyber")]
+  algorithms.push(AlgorithmInfo { name: "Kyber", feature: "kyber", signing: false, key_exchange: true, post_quantum: true });
+
+  algorithms
+}
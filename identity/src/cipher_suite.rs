@@ -126,3 +126,41 @@ impl CipherSuite {
         schemes
     }
 }
+
+/// Local preference order used to pick one cipher suite during negotiation.
+///
+/// Suites are compared by `name()`, so the `priority` fields carried by each side
+/// do not affect the outcome. The side applying the policy decides (server
+/// preference): its order wins over the order the peer listed its suites in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CipherSuitePolicy {
+    preferences: Vec<String>,
+}
+
+impl CipherSuitePolicy {
+    /// Creates a policy preferring the given suites, most preferred first.
+    pub fn new(preferences: &[CipherSuite]) -> Self {
+        Self {
+            preferences: preferences.iter().map(CipherSuite::name).collect(),
+        }
+    }
+
+    /// Returns the preferred suite names, most preferred first.
+    pub fn preferences(&self) -> &[String] {
+        &self.preferences
+    }
+
+    /// Picks the suite both sides support that ranks highest locally.
+    ///
+    /// Suites named in the policy are tried in policy order; any other suite both
+    /// sides support comes after them, in `local` order. Returns the local entry, or
+    /// `None` if the two lists have nothing in common.
+    pub fn select(&self, local: &[CipherSuite], remote: &[CipherSuite]) -> Option<CipherSuite> {
+        let shared = |suite: &&CipherSuite| remote.iter().any(|theirs| theirs.name() == suite.name());
+        self.preferences
+            .iter()
+            .find_map(|name| local.iter().filter(shared).find(|suite| suite.name() == *name))
+            .or_else(|| local.iter().find(shared))
+            .cloned()
+    }
+}
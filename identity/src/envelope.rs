@@ -0,0 +1,91 @@
+// identity\src\envelope.rs
+use crate::rng::KeyRng;
+use crate::{KeyExchange, KyberKeyPair, PKIError};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use fips203::ml_kem_1024::{DecapsKey, EncapsKey};
+use rand_core::RngCore;
+
+/// AES-GCM nonce size in bytes.
+const NONCE_LEN: usize = 12;
+/// Size of the random AES-256 content key.
+const CONTENT_KEY_LEN: usize = 32;
+
+/// The content key of a `MultiRecipientEnvelope`, wrapped for one recipient.
+#[derive(Debug, Clone)]
+pub struct WrappedKey {
+  /// Kyber ciphertext (with validation tag) as produced by `KyberKeyPair::encapsulate`.
+  pub kem_ciphertext: Vec<u8>,
+  pub nonce: [u8; NONCE_LEN],
+  /// The content key, AES-256-GCM encrypted under the KEM shared secret.
+  pub encrypted_key: Vec<u8>,
+}
+
+/// A payload encrypted once under a random content key, with that key wrapped for
+/// each recipient's Kyber public key.
+#[derive(Debug, Clone)]
+pub struct MultiRecipientEnvelope {
+  pub nonce: [u8; NONCE_LEN],
+  pub ciphertext: Vec<u8>,
+  pub recipients: Vec<WrappedKey>,
+}
+
+impl MultiRecipientEnvelope {
+  /// Encrypts `plaintext` once and wraps the content key for every recipient.
+  pub fn seal_for_recipients(plaintext: &[u8], recipients: &[EncapsKey]) -> Result<Self, PKIError> {
+    if recipients.is_empty() {
+      return Err(PKIError::GenericError("An envelope needs at least one recipient".to_string()));
+    }
+
+    let mut content_key = [0u8; CONTENT_KEY_LEN];
+    KeyRng.fill_bytes(&mut content_key);
+    let (nonce, ciphertext) = aes_encrypt(&content_key, plaintext)?;
+
+    let recipients = recipients
+      .iter()
+      .map(|public_key| {
+        let (shared_secret, kem_ciphertext) = KyberKeyPair::encapsulate(public_key, None)?;
+        let (nonce, encrypted_key) = aes_encrypt(&shared_secret, &content_key)?;
+        Ok(WrappedKey { kem_ciphertext, nonce, encrypted_key })
+      })
+      .collect::<Result<Vec<_>, PKIError>>()?;
+
+    Ok(Self { nonce, ciphertext, recipients })
+  }
+
+  /// Finds the content key wrapped for `private_key` and decrypts the payload.
+  ///
+  /// Wrapped keys addressed to other recipients fail Kyber's validation tag and
+  /// are skipped.
+  pub fn open(&self, private_key: &DecapsKey) -> Result<Vec<u8>, PKIError> {
+    for wrapped in &self.recipients {
+      let Ok(shared_secret) = KyberKeyPair::decapsulate(private_key, &wrapped.kem_ciphertext, None) else {
+        continue;
+      };
+      let Ok(content_key) = aes_decrypt(&shared_secret, &wrapped.nonce, &wrapped.encrypted_key) else {
+        continue;
+      };
+      return aes_decrypt(&content_key, &self.nonce, &self.ciphertext);
+    }
+    Err(PKIError::KeyExchangeError("No wrapped key in the envelope matches this private key".to_string()))
+  }
+}
+
+fn cipher(key: &[u8]) -> Result<Aes256Gcm, PKIError> {
+  Aes256Gcm::new_from_slice(key).map_err(|_| PKIError::InvalidKey(format!("Expected a 32-byte key, got {} bytes", key.len())))
+}
+
+fn aes_encrypt(key: &[u8], plaintext: &[u8]) -> Result<([u8; NONCE_LEN], Vec<u8>), PKIError> {
+  let mut nonce = [0u8; NONCE_LEN];
+  KeyRng.fill_bytes(&mut nonce);
+  let ciphertext = cipher(key)?
+    .encrypt(Nonce::from_slice(&nonce), plaintext)
+    .map_err(|_| PKIError::EncodingError("AES-GCM encryption failed".to_string()))?;
+  Ok((nonce, ciphertext))
+}
+
+fn aes_decrypt(key: &[u8], nonce: &[u8; NONCE_LEN], ciphertext: &[u8]) -> Result<Vec<u8>, PKIError> {
+  cipher(key)?
+    .decrypt(Nonce::from_slice(nonce), ciphertext)
+    .map_err(|_| PKIError::DecodingError("AES-GCM authentication failed".to_string()))
+}
@@ -1,11 +1,130 @@
 // identity\src\key_serde_trait.rs
 use crate::PKIError;
+use zeroize::Zeroizing;
+
+/// Magic bytes opening every serialized key ("Nautilus Key").
+pub const KEY_FORMAT_MAGIC: [u8; 2] = *b"NK";
+/// Current version of the serialized key layout.
+pub const KEY_FORMAT_VERSION: u8 = 2;
+/// Version 1 layout: magic + version, with no algorithm tag.
+const KEY_FORMAT_VERSION_UNTAGGED: u8 = 1;
+/// Offset of the algorithm tag in a version 2 header.
+const ALGORITHM_TAG_OFFSET: usize = KEY_FORMAT_MAGIC.len() + 1;
+/// Length of the version 2 header (magic + version + algorithm tag).
+const KEY_FORMAT_HEADER_LEN: usize = ALGORITHM_TAG_OFFSET + 1;
+
+pub(crate) const ALGORITHM_TAG_RSA: u8 = 1;
+pub(crate) const ALGORITHM_TAG_ECDSA: u8 = 2;
+pub(crate) const ALGORITHM_TAG_ED25519: u8 = 3;
+pub(crate) const ALGORITHM_TAG_SECP256K1: u8 = 4;
+pub(crate) const ALGORITHM_TAG_DILITHIUM: u8 = 5;
+pub(crate) const ALGORITHM_TAG_SPHINCS: u8 = 6;
+pub(crate) const ALGORITHM_TAG_FALCON: u8 = 7;
+pub(crate) const ALGORITHM_TAG_KYBER: u8 = 8;
+
+/// Algorithm tags and the `PKITraits::key_type` name each one stands for.
+const ALGORITHM_TAGS: [(u8, &str); 8] = [
+  (ALGORITHM_TAG_RSA, "RSA"),
+  (ALGORITHM_TAG_ECDSA, "ECDSA"),
+  (ALGORITHM_TAG_ED25519, "ED25519"),
+  (ALGORITHM_TAG_SECP256K1, "SECP256K1"),
+  (ALGORITHM_TAG_DILITHIUM, "Dilithium"),
+  (ALGORITHM_TAG_SPHINCS, "SPHINCS+"),
+  (ALGORITHM_TAG_FALCON, "Falcon"),
+  (ALGORITHM_TAG_KYBER, "Kyber"),
+];
+
+/// Reads the algorithm of a serialized key from its header without parsing the key.
+///
+/// Returns the algorithm's `PKITraits::key_type` name, or `None` for bytes that are
+/// not a tagged (version 2) key or carry an unknown tag. Works whether or not the
+/// algorithm's feature is enabled.
+pub fn peek_algorithm(bytes: &[u8]) -> Option<&'static str> {
+  if !bytes.starts_with(&KEY_FORMAT_MAGIC) || bytes.get(KEY_FORMAT_MAGIC.len()) != Some(&KEY_FORMAT_VERSION) {
+    return None;
+  }
+  let tag = *bytes.get(ALGORITHM_TAG_OFFSET)?;
+  ALGORITHM_TAGS.iter().find(|(known, _)| *known == tag).map(|(_, name)| *name)
+}
+
+/// Serialization of key pairs to and from bytes.
+///
+/// `to_bytes` writes a 4-byte header (`KEY_FORMAT_MAGIC`, `KEY_FORMAT_VERSION` and
+/// the type's `ALGORITHM_TAG`) in front of the algorithm-specific layout produced by
+/// `to_raw_bytes`; `from_bytes` checks it before handing the rest to
+/// `from_raw_bytes`. Version 1 keys, which have no tag, are still accepted. With the `legacy_key_format` feature, input that does not start
+/// with the magic is parsed as the old headerless layout.
 pub trait KeySerialization {
-  /// Serialize the key into bytes.
-  fn to_bytes(&self) -> Vec<u8>;
+  /// One-byte tag identifying the algorithm in the serialized header.
+  const ALGORITHM_TAG: u8;
 
-  /// Deserialize the key from bytes.
-  fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError>
+  /// Serialize the key into the algorithm-specific layout, without the header.
+  fn to_raw_bytes(&self) -> Vec<u8>;
+
+  /// Deserialize the key from the algorithm-specific layout, without the header.
+  fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError>
   where
       Self: Sized;
-}
\ No newline at end of file
+
+  /// Serialize the key into versioned bytes.
+  fn to_bytes(&self) -> Vec<u8> {
+    // The raw layout holds the private key; wipe it once it has been copied.
+    let raw = Zeroizing::new(self.to_raw_bytes());
+    let mut bytes = Vec::with_capacity(KEY_FORMAT_HEADER_LEN + raw.len());
+    bytes.extend_from_slice(&KEY_FORMAT_MAGIC);
+    bytes.push(KEY_FORMAT_VERSION);
+    bytes.push(Self::ALGORITHM_TAG);
+    bytes.extend_from_slice(&raw);
+    bytes
+  }
+
+  /// Deserialize the key from versioned bytes.
+  fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError>
+  where
+      Self: Sized,
+  {
+    if !bytes.starts_with(&KEY_FORMAT_MAGIC) {
+      if cfg!(feature = "legacy_key_format") {
+        return Self::from_raw_bytes(bytes);
+      }
+      return Err(PKIError::InvalidKey("missing key format header".to_string()));
+    }
+
+    match bytes.get(KEY_FORMAT_MAGIC.len()) {
+      Some(&KEY_FORMAT_VERSION) => match bytes.get(ALGORITHM_TAG_OFFSET) {
+        Some(&tag) if tag == Self::ALGORITHM_TAG => Self::from_raw_bytes(&bytes[KEY_FORMAT_HEADER_LEN..]),
+        Some(_) => Err(PKIError::InvalidKey(format!(
+          "key is {}, not the requested algorithm",
+          peek_algorithm(bytes).unwrap_or("an unknown algorithm")
+        ))),
+        None => Err(PKIError::InvalidKey("truncated key format header".to_string())),
+      },
+      Some(&KEY_FORMAT_VERSION_UNTAGGED) => Self::from_raw_bytes(&bytes[ALGORITHM_TAG_OFFSET..]),
+      Some(_) => Err(PKIError::InvalidKey("unsupported key format version".to_string())),
+      None => Err(PKIError::InvalidKey("truncated key format header".to_string())),
+    }
+  }
+
+  /// Serialize the key with `to_bytes` and encrypt it under `passphrase`.
+  ///
+  /// The passphrase is stretched with Argon2id using a random salt, and the key
+  /// bytes are sealed with AES-256-GCM; the salt, nonce and Argon2 costs are stored
+  /// in the header so `from_encrypted_bytes` needs only the passphrase.
+  #[cfg(feature = "sealed_keys")]
+  fn to_encrypted_bytes(&self, passphrase: &str) -> Result<Vec<u8>, PKIError> {
+    let bytes = Zeroizing::new(self.to_bytes());
+    crate::sealed_key::seal(&bytes, passphrase)
+  }
+
+  /// Decrypt bytes produced by `to_encrypted_bytes` and deserialize the key.
+  ///
+  /// A wrong passphrase is reported as `PKIError::DecryptionFailed`.
+  #[cfg(feature = "sealed_keys")]
+  fn from_encrypted_bytes(data: &[u8], passphrase: &str) -> Result<Self, PKIError>
+  where
+      Self: Sized,
+  {
+    let bytes = crate::sealed_key::open(data, passphrase)?;
+    Self::from_bytes(&bytes)
+  }
+}
@@ -0,0 +1,146 @@
+// identity\src\keyset.rs
+use crate::{supported_algorithms, PKIError};
+#[allow(unused_imports)]
+use crate::PKITraits;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// A key pair of any algorithm enabled in this build. Key pairs are boxed since
+/// their sizes range from tens of bytes to several kilobytes.
+pub enum AnyKeyPair {
+  #[cfg(feature = "pki_rsa")]
+  Rsa(Box<crate::RSAkeyPair>),
+  #[cfg(feature = "secp256k1")]
+  Secp256k1(Box<crate::SECP256K1KeyPair>),
+  #[cfg(feature = "ecdsa")]
+  Ecdsa(Box<crate::ECDSAKeyPair>),
+  #[cfg(feature = "ed25519")]
+  Ed25519(Box<crate::Ed25519KeyPair>),
+  #[cfg(feature = "dilithium")]
+  Dilithium(Box<crate::DilithiumKeyPair>),
+  #[cfg(feature = "spincs")]
+  Sphincs(Box<crate::SPHINCSKeyPair>),
+  #[cfg(feature = "falcon")]
+  Falcon(Box<crate::FalconKeyPair>),
+  #[cfg(feature = "kyber")]
+  Kyber(Box<crate::KyberKeyPair>),
+}
+
+/// Runs `$body` with `$key` bound to the key pair inside any variant. Matching on
+/// `*$any` keeps this valid when no algorithm feature is enabled.
+macro_rules! with_key_pair {
+  ($any:expr, $key:ident => $body:expr) => {
+    match *$any {
+      #[cfg(feature = "pki_rsa")]
+      AnyKeyPair::Rsa(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "secp256k1")]
+      AnyKeyPair::Secp256k1(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "ecdsa")]
+      AnyKeyPair::Ecdsa(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "ed25519")]
+      AnyKeyPair::Ed25519(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "dilithium")]
+      AnyKeyPair::Dilithium(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "spincs")]
+      AnyKeyPair::Sphincs(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "falcon")]
+      AnyKeyPair::Falcon(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+      #[cfg(feature = "kyber")]
+      AnyKeyPair::Kyber(ref boxed) => {
+        let $key = &**boxed;
+        $body
+      }
+    }
+  };
+}
+
+impl AnyKeyPair {
+  /// Generates a key pair for `algorithm`, matched case-insensitively against the
+  /// names listed by `supported_algorithms`.
+  pub fn generate(algorithm: &str) -> Result<Self, PKIError> {
+    let info = supported_algorithms()
+      .into_iter()
+      .find(|info| info.name.eq_ignore_ascii_case(algorithm))
+      .ok_or_else(|| {
+        PKIError::UnsupportedOperation(format!("Algorithm '{}' is not enabled in this build", algorithm))
+      })?;
+
+    match info.feature {
+      #[cfg(feature = "pki_rsa")]
+      "pki_rsa" => crate::RSAkeyPair::generate_key_pair().map(|key| AnyKeyPair::Rsa(Box::new(key))),
+      #[cfg(feature = "secp256k1")]
+      "secp256k1" => crate::SECP256K1KeyPair::generate_key_pair().map(|key| AnyKeyPair::Secp256k1(Box::new(key))),
+      #[cfg(feature = "ecdsa")]
+      "ecdsa" => crate::ECDSAKeyPair::generate_key_pair().map(|key| AnyKeyPair::Ecdsa(Box::new(key))),
+      #[cfg(feature = "ed25519")]
+      "ed25519" => crate::Ed25519KeyPair::generate_key_pair().map(|key| AnyKeyPair::Ed25519(Box::new(key))),
+      #[cfg(feature = "dilithium")]
+      "dilithium" => crate::DilithiumKeyPair::generate_key_pair().map(|key| AnyKeyPair::Dilithium(Box::new(key))),
+      #[cfg(feature = "spincs")]
+      "spincs" => crate::SPHINCSKeyPair::generate_key_pair().map(|key| AnyKeyPair::Sphincs(Box::new(key))),
+      #[cfg(feature = "falcon")]
+      "falcon" => crate::FalconKeyPair::generate_key_pair().map(|key| AnyKeyPair::Falcon(Box::new(key))),
+      #[cfg(feature = "kyber")]
+      "kyber" => crate::KyberKeyPair::generate_key_pair().map(|key| AnyKeyPair::Kyber(Box::new(key))),
+      other => Err(PKIError::UnsupportedOperation(format!("No key generator for feature '{}'", other))),
+    }
+  }
+
+  /// Returns the algorithm name, as reported by `PKITraits::key_type`.
+  pub fn key_type(&self) -> String {
+    with_key_pair!(self, key => key_type_of(key))
+  }
+
+  pub fn sign(&self, data: &[u8]) -> Result<Vec<u8>, PKIError> {
+    with_key_pair!(self, key => key.sign(data))
+  }
+
+  pub fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+    with_key_pair!(self, key => key.verify(data, signature))
+  }
+
+  pub fn get_public_key_raw_bytes(&self) -> Vec<u8> {
+    with_key_pair!(self, key => key.get_public_key_raw_bytes())
+  }
+}
+
+#[allow(dead_code)]
+fn key_type_of<K: PKITraits>(_key: &K) -> String {
+  K::key_type()
+}
+
+/// Generates one key pair per entry of `algorithms` in parallel on the rayon thread
+/// pool and returns them keyed by the requested name. Fails with the first error,
+/// e.g. for an algorithm that is not enabled.
+///
+/// With `deterministic-testing`, the seed set by `test_seed` applies to the calling
+/// thread only, so key sets are not reproducible; generate keys one by one instead.
+pub fn generate_keyset(algorithms: &[&str]) -> Result<HashMap<String, AnyKeyPair>, PKIError> {
+  algorithms
+    .par_iter()
+    .map(|algorithm| {
+      AnyKeyPair::generate(algorithm).map(|key_pair| (algorithm.to_string(), key_pair))
+    })
+    .collect()
+}
@@ -17,6 +17,23 @@ mod key_exchange;
 mod cipher_suite;
 // Modue containing the Trait for Key Serialization
 mod key_serde_trait;
+// Module providing the RNG behind key generation and encapsulation
+mod rng;
+// Module rate limiting verification of untrusted signatures
+mod verification_budget;
+// Module listing the algorithms compiled into this build
+mod algorithm_info;
+// Module generating key pairs of several algorithms at once
+mod keyset;
+// Module containing the trait for signing AsyncRead streams
+#[cfg(feature = "stream_signing")]
+mod stream_sign_trait;
+// Module encrypting one payload to several Kyber recipients
+#[cfg(feature = "envelope")]
+mod envelope;
+// Module sealing serialized keys under a passphrase
+#[cfg(feature = "sealed_keys")]
+mod sealed_key;
 /// # Overview
 /// This library is designed to facilitate cryptographic operations for
 /// secure communication and data integrity. By using standardized algorithms
@@ -35,8 +52,26 @@ pub use pki_error::PKIError;
 // Publicly export the `KeyExchange` trait for use by external Modules
 pub use key_exchange::KeyExchange;
 // CipherSuite Supported By Crate
-pub use cipher_suite::CipherSuite;
+pub use cipher_suite::{CipherSuite, CipherSuitePolicy};
 // Publicly export the `KeySerialization`trait for use by external Module
-pub use key_serde_trait::KeySerialization;
+pub use key_serde_trait::{peek_algorithm, KeySerialization, KEY_FORMAT_MAGIC, KEY_FORMAT_VERSION};
+// Publicly export the verification rate limiter
+pub use verification_budget::VerificationBudget;
+// Publicly export the runtime list of enabled algorithms
+pub use algorithm_info::{supported_algorithms, AlgorithmInfo};
+// Publicly export parallel key set generation
+pub use keyset::{generate_keyset, AnyKeyPair};
+// Publicly export the `StreamSigning` trait for signing streams
+#[cfg(feature = "stream_signing")]
+pub use stream_sign_trait::{DetachedStreamSigning, StreamSigning, DETACHED_STREAM_MAX_LEN};
+// Publicly export the multi-recipient envelope types
+#[cfg(feature = "envelope")]
+pub use envelope::{MultiRecipientEnvelope, WrappedKey};
+// Publicly export the sealed key file header constants
+#[cfg(feature = "sealed_keys")]
+pub use sealed_key::{SEALED_KEY_MAGIC, SEALED_KEY_VERSION};
+// Seed key generation for reproducible tests (debug builds only).
+#[cfg(feature = "deterministic-testing")]
+pub use rng::test_seed;
 // Publicly export all contents of the `pki` module for external use.
 pub use pki::*;
\ No newline at end of file
@@ -4,15 +4,22 @@
 #[cfg(feature = "dilithium")]
 use crate::{PKIError, PKITraits};
 #[cfg(feature = "dilithium")]
+use crate::rng::KeyRng;
+#[cfg(feature = "dilithium")]
+use crate::pki::check_signature_len;
+#[cfg(feature = "dilithium")]
 use fips204::ml_dsa_87::{self, PrivateKey, PublicKey};
 #[cfg(feature = "dilithium")]
 use fips204::traits::{SerDes, Signer, Verifier};
+#[cfg(feature = "dilithium")]
+use zeroize::Zeroizing;
 
 // ======================= Dilithium Key Pair Definition =======================
 /// A struct representing a Dilithium key pair.
 ///
 /// This struct encapsulates the private and public keys required for
 /// signing and verification using the Dilithium digital signature algorithm.
+/// `fips204` zeroizes the private key when it is dropped.
 #[cfg(feature = "dilithium")]
 #[derive(Clone)]
 pub struct DilithiumKeyPair {
@@ -31,7 +38,7 @@ impl PKITraits for DilithiumKeyPair {
     /// Generates a new Dilithium key pair.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
         let result = std::panic::catch_unwind(|| {
-            ml_dsa_87::try_keygen()
+            ml_dsa_87::try_keygen_with_rng(&mut KeyRng)
                 .map_err(|e| PKIError::KeyPairGenerationError(format!("Key generation failed: {}", e)))
         });
 
@@ -70,14 +77,29 @@ impl PKITraits for DilithiumKeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("Dilithium", signature, 4627..=4627)?;
         let signature_array: [u8; 4627] = signature
             .try_into()
-            .map_err(|_| PKIError::VerificationError("Invalid signature length".to_string()))?;
+            .map_err(|_| PKIError::MalformedSignature("Invalid signature length".to_string()))?;
 
         let is_valid = self.public_key.verify(data, &signature_array, &[]);
         Ok(is_valid)
     }
 
+    /// Verifies a signature against raw ML-DSA-87 public key bytes.
+    fn verify_with_public_key(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        let public_key = PublicKey::try_from_bytes(public_key.try_into().map_err(|_| {
+            PKIError::InvalidKey("Invalid Dilithium public key size".to_string())
+        })?)
+        .map_err(|_| PKIError::InvalidKey("Invalid Dilithium public key".to_string()))?;
+        check_signature_len("Dilithium", signature, 4627..=4627)?;
+        let signature_array: [u8; 4627] = signature
+            .try_into()
+            .map_err(|_| PKIError::MalformedSignature("Invalid signature length".to_string()))?;
+
+        Ok(public_key.verify(data, &signature_array, &[]))
+    }
+
     /// Retrieves the public key as raw bytes.
     fn get_public_key_raw_bytes(&self) -> Vec<u8> {
         self.public_key.clone().into_bytes().to_vec()
@@ -92,14 +114,16 @@ impl PKITraits for DilithiumKeyPair {
 // ======================= Key Serialization Implementation =======================
 #[cfg(feature = "dilithium")]
 impl crate::KeySerialization for DilithiumKeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_DILITHIUM;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let public_key_bytes = self.public_key.clone().into_bytes().to_vec();
-        let private_key_bytes = self.private_key.clone().into_bytes().to_vec();
+        let private_key_bytes = Zeroizing::new(self.private_key.clone().into_bytes());
 
-        [public_key_bytes, private_key_bytes].concat()
+        [public_key_bytes.as_slice(), private_key_bytes.as_slice()].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
         let public_key_len = 2592; // Confirmed from PublicKey::into_bytes()
         let private_key_len = 4896; // Confirmed from PrivateKey::into_bytes()
     
@@ -133,8 +157,9 @@ impl crate::KeySerialization for DilithiumKeyPair {
 // ================== Additional Methods ======================================
 #[cfg(feature = "dilithium")]
 impl DilithiumKeyPair {
-    /// Retrieves the private key as raw bytes.
-    pub fn private_key_raw_bytes(&self) -> Vec<u8> {
-        self.private_key.clone().into_bytes().to_vec()
+    /// Retrieves the private key as raw bytes, wiped when the returned buffer is dropped.
+    pub fn private_key_raw_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let private_key_bytes = Zeroizing::new(self.private_key.clone().into_bytes());
+        Zeroizing::new(private_key_bytes.to_vec())
     }
 }
\ No newline at end of file
@@ -6,6 +6,7 @@ use crate::{PKIError, PKITraits, KeyExchange};
 #[cfg(feature = "ecdsa")]
 use p256::ecdsa::{
     signature::{Signer, Verifier},
+    signature::hazmat::{PrehashSigner, PrehashVerifier},
     Signature, SigningKey, VerifyingKey,
 };
 #[cfg(feature = "ecdsa")]
@@ -17,7 +18,14 @@ use p256::{
 #[cfg(feature = "ecdsa")]
 use sha2::Digest;
 #[cfg(feature = "ecdsa")]
-use rand_core::OsRng;
+use crate::rng::KeyRng;
+#[cfg(feature = "ecdsa")]
+use crate::pki::check_signature_len;
+
+/// A DER `SEQUENCE { r, s }` of two 256-bit integers: 8 bytes for one-byte
+/// integers up to 72 with both padded to 33 bytes.
+#[cfg(feature = "ecdsa")]
+const DER_SIGNATURE_LEN: std::ops::RangeInclusive<usize> = 8..=72;
 
 // ======================= ECDSA Key Pair Definition =======================
 #[cfg(feature = "ecdsa")]
@@ -35,7 +43,7 @@ impl PKITraits for ECDSAKeyPair {
 
     /// Generates a new ECDSA key pair.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
-        let signing_key = SigningKey::random(&mut OsRng);
+        let signing_key = SigningKey::random(&mut KeyRng);
         let verifying_key = VerifyingKey::from(&signing_key);
 
         Ok(Self {
@@ -52,6 +60,7 @@ impl PKITraits for ECDSAKeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("ECDSA", signature, DER_SIGNATURE_LEN)?;
         let signature = Signature::from_der(signature)
             .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
         self.verifying_key
@@ -60,6 +69,42 @@ impl PKITraits for ECDSAKeyPair {
             .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
     }
 
+    /// Signs a SHA-256 digest, producing the same DER encoding as `sign`.
+    fn sign_prehashed(&self, digest: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if digest.len() != 32 {
+            return Err(PKIError::SigningError(format!("Expected a 32-byte SHA-256 digest, got {} bytes", digest.len())));
+        }
+        let signature: Signature = self
+            .signing_key
+            .sign_prehash(digest)
+            .map_err(|e| PKIError::SigningError(format!("Prehash signing failed: {}", e)))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+
+    /// Verifies a DER signature over a SHA-256 digest.
+    fn verify_prehashed(&self, digest: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        if digest.len() != 32 {
+            return Err(PKIError::VerificationError(format!("Expected a 32-byte SHA-256 digest, got {} bytes", digest.len())));
+        }
+        check_signature_len("ECDSA", signature, DER_SIGNATURE_LEN)?;
+        let signature = Signature::from_der(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
+        self.verifying_key
+            .verify_prehash(digest, &signature)
+            .map(|_| true)
+            .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
+    }
+
+    /// Verifies a DER signature against a SEC1-encoded public key.
+    fn verify_with_public_key(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| PKIError::InvalidKey("Invalid ECDSA public key".to_string()))?;
+        check_signature_len("ECDSA", signature, DER_SIGNATURE_LEN)?;
+        let signature = Signature::from_der(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
+        Ok(verifying_key.verify(data, &signature).is_ok())
+    }
+
     /// Retrieves the public key from the key pair.
     fn get_public_key_raw_bytes(&self) -> Vec<u8> {
         self.verifying_key.to_encoded_point(false).as_bytes().to_vec()
@@ -84,7 +129,7 @@ impl KeyExchange for ECDSAKeyPair {
         public_key: &Self::PublicKey,
         _context: Option<&[u8]>,
     ) -> Result<(Self::SharedSecretKey, Vec<u8>), Self::Error> {
-        let ephemeral_secret = SigningKey::random(&mut OsRng);
+        let ephemeral_secret = SigningKey::random(&mut KeyRng);
         let ephemeral_public_key = VerifyingKey::from(&ephemeral_secret).to_encoded_point(false);
 
         let peer_point = ProjectivePoint::from(public_key);
@@ -151,14 +196,16 @@ impl KeyExchange for ECDSAKeyPair {
 // ======================= Key Serialization Implementation =======================
 #[cfg(feature = "ecdsa")]
 impl crate::KeySerialization for ECDSAKeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_ECDSA;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let signing_key_bytes = self.signing_key.to_bytes().to_vec();
         let verifying_key_bytes = self.verifying_key.to_encoded_point(false).as_bytes().to_vec();
 
         [signing_key_bytes, verifying_key_bytes].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
         let signing_key_size = 32; // ECDSA private key size
         if bytes.len() < signing_key_size + 65 {
             return Err(PKIError::InvalidKey("Insufficient data for deserialization".to_string()));
@@ -211,4 +258,49 @@ impl ECDSAKeyPair {
     
             Ok(shared_secret)
         }
-}
\ No newline at end of file
+}
+
+// ======================= Signature Encoding Helpers =======================
+#[cfg(feature = "ecdsa")]
+impl ECDSAKeyPair {
+    /// Signs data and returns an ASN.1 DER signature (same encoding as `sign`).
+    pub fn sign_der(&self, data: &[u8]) -> Result<Vec<u8>, PKIError> {
+        self.sign(data)
+    }
+
+    /// Verifies an ASN.1 DER signature.
+    pub fn verify_der(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+        self.verify(data, signature)
+    }
+
+    /// Signs data and returns the fixed-size 64-byte `r || s` encoding.
+    pub fn sign_raw(&self, data: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature: Signature = self.signing_key.sign(data);
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    /// Verifies a fixed-size 64-byte `r || s` signature.
+    pub fn verify_raw(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+        check_signature_len("ECDSA", signature, 64..=64)?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid raw signature: {}", e)))?;
+        self.verifying_key
+            .verify(data, &signature)
+            .map(|_| true)
+            .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
+    }
+
+    /// Converts a DER signature to its raw `r || s` form.
+    pub fn der_to_raw(der: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature = Signature::from_der(der)
+            .map_err(|e| PKIError::MalformedSignature(format!("Invalid DER signature: {}", e)))?;
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    /// Converts a raw `r || s` signature to DER.
+    pub fn raw_to_der(raw: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature = Signature::from_slice(raw)
+            .map_err(|e| PKIError::MalformedSignature(format!("Invalid raw signature: {}", e)))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+}
@@ -5,7 +5,11 @@ use crate::{PKIError, PKITraits, KeyExchange};
 #[cfg(feature = "ed25519")]
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 #[cfg(feature = "ed25519")]
-use rand_core::{OsRng, RngCore};
+use rand_core::RngCore;
+#[cfg(feature = "ed25519")]
+use crate::rng::KeyRng;
+#[cfg(feature = "ed25519")]
+use crate::pki::check_signature_len;
 #[cfg(feature = "ed25519")]
 use std::convert::TryInto;
 #[cfg(feature = "ed25519")]
@@ -32,7 +36,7 @@ impl PKITraits for Ed25519KeyPair {
     /// Generates a new Ed25519 key pair.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
         let mut private_key = [0u8; 32];
-        OsRng.fill_bytes(&mut private_key);
+        KeyRng.fill_bytes(&mut private_key);
 
         let signing_key = SigningKey::from_bytes(&private_key);
         let verifying_key = signing_key.verifying_key();
@@ -51,9 +55,10 @@ impl PKITraits for Ed25519KeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("Ed25519", signature, 64..=64)?;
         let signature_array: [u8; 64] = signature
             .try_into()
-            .map_err(|_| PKIError::VerificationError("Invalid signature length".to_string()))?;
+            .map_err(|_| PKIError::MalformedSignature("Invalid signature length".to_string()))?;
 
         let signature = Signature::from_bytes(&signature_array);
 
@@ -63,6 +68,21 @@ impl PKITraits for Ed25519KeyPair {
             .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
     }
 
+    /// Verifies a signature against a 32-byte Ed25519 public key.
+    fn verify_with_public_key(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        let public_key_array: [u8; 32] = public_key
+            .try_into()
+            .map_err(|_| PKIError::InvalidKey("Invalid Ed25519 public key size".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&public_key_array)
+            .map_err(|_| PKIError::InvalidKey("Invalid Ed25519 public key".to_string()))?;
+        check_signature_len("Ed25519", signature, 64..=64)?;
+        let signature_array: [u8; 64] = signature
+            .try_into()
+            .map_err(|_| PKIError::MalformedSignature("Invalid signature length".to_string()))?;
+
+        Ok(verifying_key.verify(data, &Signature::from_bytes(&signature_array)).is_ok())
+    }
+
     /// Retrieves the public key from the key pair.
     fn get_public_key_raw_bytes(&self) -> Vec<u8> {
         self.verifying_key.clone().to_bytes().to_vec()
@@ -86,7 +106,7 @@ impl KeyExchange for Ed25519KeyPair {
         public_key: &Self::PublicKey,
         _context: Option<&[u8]>,
     ) -> Result<(Self::SharedSecretKey, Vec<u8>), Self::Error> {
-        let mut rng = OsRng;
+        let mut rng = KeyRng;
 
         let ephemeral_private_key = {
             let mut bytes = [0u8; 32];
@@ -127,14 +147,16 @@ impl KeyExchange for Ed25519KeyPair {
 // ======================= Key Serialization Implementation =======================
 #[cfg(feature = "ed25519")]
 impl crate::KeySerialization for Ed25519KeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_ED25519;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let signing_key_bytes = self.signing_key.to_bytes().to_vec();
         let verifying_key_bytes = self.verifying_key.to_bytes().to_vec();
 
         [signing_key_bytes, verifying_key_bytes].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
         let signing_key_size = 32; // Ed25519 private key size
         if bytes.len() < signing_key_size + 32 {
             return Err(PKIError::InvalidKey("Insufficient data for deserialization".to_string()));
@@ -167,4 +189,71 @@ impl Ed25519KeyPair {
     pub fn private_key_raw_bytes(&self) -> Vec<u8>{
         self.signing_key.clone().to_bytes().to_vec()
     }
+
+    /// The static X25519 private key: the clamped SHA-512 expansion of the seed that
+    /// Ed25519 signs with, reduced modulo the group order.
+    pub fn x25519_private_key(&self) -> Scalar {
+        self.signing_key.to_scalar()
+    }
+
+    /// The static X25519 public key matching `x25519_private_key`. It is the Montgomery
+    /// form of the Ed25519 public key, so peers can derive it from our identity.
+    pub fn x25519_public_key(&self) -> MontgomeryPoint {
+        self.verifying_key.to_montgomery()
+    }
+
+    /// Static-static exchange: both parties use their long-term keys, so no message
+    /// is sent and the secret is the same every time for a given pair of keys.
+    pub fn static_static(&self, peer_public_key: &MontgomeryPoint) -> Result<Vec<u8>, PKIError> {
+        shared_secret_bytes(peer_public_key.mul_clamped(self.signing_key.to_scalar_bytes()))
+    }
+
+    /// Opens an ephemeral-static "seal" produced by `KeyExchange::encapsulate` against
+    /// `x25519_public_key`, using this key pair's static key.
+    pub fn open_sealed(&self, ephemeral_public_key: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let ephemeral_public_key: [u8; 32] = ephemeral_public_key
+            .try_into()
+            .map_err(|_| PKIError::KeyExchangeError("Invalid ciphertext length".to_string()))?;
+        shared_secret_bytes(MontgomeryPoint(ephemeral_public_key).mul_clamped(self.signing_key.to_scalar_bytes()))
+    }
+}
+
+// ================== Ephemeral-Ephemeral Exchange ============================
+/// A single-use X25519 key for ephemeral-ephemeral exchanges. Each side generates
+/// one, sends `public_key`, and consumes it in `diffie_hellman` so the secret
+/// cannot be recomputed later (forward secrecy).
+#[cfg(feature = "ed25519")]
+pub struct X25519Ephemeral {
+    private_key: Scalar,
+    public_key: MontgomeryPoint,
+}
+
+#[cfg(feature = "ed25519")]
+impl X25519Ephemeral {
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 32];
+        KeyRng.fill_bytes(&mut bytes);
+        let private_key = Scalar::from_bytes_mod_order(bytes);
+        let public_key = EdwardsPoint::mul_base(&private_key).to_montgomery();
+        Self { private_key, public_key }
+    }
+
+    pub fn public_key(&self) -> MontgomeryPoint {
+        self.public_key
+    }
+
+    pub fn diffie_hellman(self, peer_public_key: &MontgomeryPoint) -> Result<Vec<u8>, PKIError> {
+        shared_secret_bytes(peer_public_key * self.private_key)
+    }
+}
+
+/// Rejects the all-zero output produced by low-order peer points.
+#[cfg(feature = "ed25519")]
+fn shared_secret_bytes(shared: MontgomeryPoint) -> Result<Vec<u8>, PKIError> {
+    if shared.to_bytes() == [0u8; 32] {
+        return Err(PKIError::KeyExchangeError(
+            "Peer public key produced an all-zero shared secret".to_string(),
+        ));
+    }
+    Ok(shared.to_bytes().to_vec())
 }
\ No newline at end of file
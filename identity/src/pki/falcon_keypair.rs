@@ -7,7 +7,17 @@ use crate::{PKIError, PKITraits,KeySerialization};
 use pqcrypto_falcon::falcon512::*;
 #[cfg(feature = "falcon")]
 use pqcrypto_traits::sign::{DetachedSignature, PublicKey as PublicKeyTrait,SecretKey as SecretKeyTrait};
+#[cfg(feature = "falcon")]
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+#[cfg(feature = "falcon")]
+use crate::pki::check_signature_len;
+
+/// A Falcon signature carries at least its header byte and 40-byte nonce.
+#[cfg(feature = "falcon")]
+const FALCON_SIGNATURE_LEN_MIN: usize = 41;
 // ======================= Falcon Key Pair Definition =======================
+/// A Falcon-512 key pair. The secret key is overwritten with zeros when the pair is
+/// dropped.
 #[cfg(feature = "falcon")]
 #[derive(Clone)]
 pub struct FalconKeyPair {
@@ -21,7 +31,8 @@ impl PKITraits for FalconKeyPair {
     type KeyPair = Self;
     type Error = PKIError;
 
-    /// Generates a new Falcon key pair.
+    /// Generates a new Falcon key pair. Always uses the system RNG, even after
+    /// `test_seed`.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
         let (public_key, secret_key) = keypair();
         Ok(Self {
@@ -38,6 +49,7 @@ impl PKITraits for FalconKeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("Falcon", signature, FALCON_SIGNATURE_LEN_MIN..=signature_bytes())?;
         let detached_signature = DetachedSignature::from_bytes(signature)
             .map_err(|_| PKIError::VerificationError("Invalid signature format".to_string()))?;
 
@@ -46,6 +58,17 @@ impl PKITraits for FalconKeyPair {
             .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
     }
 
+    /// Verifies a detached signature against raw Falcon-512 public key bytes.
+    fn verify_with_public_key(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        let public_key = pqcrypto_falcon::falcon512::PublicKey::from_bytes(public_key)
+            .map_err(|_| PKIError::InvalidKey("Invalid Falcon public key".to_string()))?;
+        check_signature_len("Falcon", signature, FALCON_SIGNATURE_LEN_MIN..=signature_bytes())?;
+        let detached_signature = DetachedSignature::from_bytes(signature)
+            .map_err(|_| PKIError::VerificationError("Invalid signature format".to_string()))?;
+
+        Ok(verify_detached_signature(&detached_signature, data, &public_key).is_ok())
+    }
+
     /// Retrieves the public key from the key pair.
     fn get_public_key_raw_bytes(&self) -> Vec<u8> {
         self.public_key.clone().as_bytes().to_vec()
@@ -59,14 +82,16 @@ impl PKITraits for FalconKeyPair {
 // ======================= Key Serialization Implmentation ====================
 #[cfg(feature = "falcon")]
 impl KeySerialization for FalconKeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_FALCON;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let public_key_bytes = self.public_key.clone().as_bytes().to_vec();
-        let private_key_bytes = self.secret_key.clone().as_bytes().to_vec();
-        
-        [public_key_bytes, private_key_bytes].concat()
+        let private_key_bytes = Zeroizing::new(self.secret_key.as_bytes().to_vec());
+
+        [public_key_bytes.as_slice(), private_key_bytes.as_slice()].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError>
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError>
     where
         Self: Sized,
     {
@@ -97,11 +122,88 @@ impl KeySerialization for FalconKeyPair {
     }
 }
 // ================== Additional Methods ======================================
+/// Length of a Falcon-512 public key in bytes.
+#[cfg(feature = "falcon")]
+const FALCON_PUBLIC_KEY_LEN: usize = 897;
+
 #[cfg(feature = "falcon")]
 impl FalconKeyPair {
-    pub fn private_key_raw_bytes(&self) -> Vec<u8> {
-        SecretKey::as_bytes(&self.secret_key).to_vec()
+    /// Builds a verify-only object from the 897-byte public key alone, for checking a
+    /// peer's signatures without any private-key material.
+    pub fn public_from_bytes(bytes: &[u8]) -> Result<FalconPublicVerifier, PKIError> {
+        if bytes.len() != FALCON_PUBLIC_KEY_LEN {
+            return Err(PKIError::InvalidKey(format!(
+                "Invalid Falcon public key length. Expected {}, got {}",
+                FALCON_PUBLIC_KEY_LEN,
+                bytes.len()
+            )));
+        }
+        let public_key = PublicKey::from_bytes(bytes)
+            .map_err(|_| PKIError::InvalidKey("Invalid Falcon public key".to_string()))?;
+        Ok(FalconPublicVerifier { public_key })
+    }
+
+    /// Retrieves the secret key as raw bytes, wiped when the returned buffer is dropped.
+    pub fn private_key_raw_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(SecretKey::as_bytes(&self.secret_key).to_vec())
     }
 }
+
+// ======================= Public-Only Verifier ================================
+/// A Falcon-512 public key on its own, able to verify but not sign. Built with
+/// `FalconKeyPair::public_from_bytes`.
+#[cfg(feature = "falcon")]
+#[derive(Clone)]
+pub struct FalconPublicVerifier {
+    public_key: PublicKey,
+}
+
+#[cfg(feature = "falcon")]
+impl FalconPublicVerifier {
+    /// Verifies a detached signature over `data`.
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+        check_signature_len("Falcon", signature, FALCON_SIGNATURE_LEN_MIN..=signature_bytes())?;
+        let detached_signature = DetachedSignature::from_bytes(signature)
+            .map_err(|_| PKIError::VerificationError("Invalid signature format".to_string()))?;
+
+        verify_detached_signature(&detached_signature, data, &self.public_key)
+            .map(|_| true)
+            .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
+    }
+
+    /// Returns the raw public key bytes.
+    pub fn public_key_raw_bytes(&self) -> Vec<u8> {
+        self.public_key.as_bytes().to_vec()
+    }
+}
+
+// ======================= Zeroization =========================================
+#[cfg(feature = "falcon")]
+impl Zeroize for FalconKeyPair {
+    /// Overwrites the secret key with zeros.
+    ///
+    /// `pqcrypto` keeps the key in a private fixed-size array with no mutable access,
+    /// so it is replaced by an all-zero key; the volatile write keeps the store from
+    /// being optimised away when the pair is about to be freed.
+    fn zeroize(&mut self) {
+        let zero_key = SecretKey::from_bytes(&vec![0u8; secret_key_bytes()])
+            .expect("an all-zero buffer of the secret key length is a valid SecretKey");
+        // SAFETY: `self.secret_key` is a valid, aligned, initialised `SecretKey`, and
+        // `SecretKey` is plain data without a `Drop` impl, so overwriting it in place
+        // leaks nothing.
+        unsafe { std::ptr::write_volatile(&mut self.secret_key, zero_key) };
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature = "falcon")]
+impl Drop for FalconKeyPair {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "falcon")]
+impl ZeroizeOnDrop for FalconKeyPair {}
 // ======================= Future Enhancements =================================
 // Additional features such as key serialization and deserialization can be implemented here if required.
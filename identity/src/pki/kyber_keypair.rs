@@ -3,15 +3,17 @@
 
 use crate::pki_error::PKIError;
 use crate::{KeyExchange, PKITraits};
+use crate::rng::KeyRng;
 #[cfg(feature = "kyber")]
 use fips203::ml_kem_1024::{EncapsKey, DecapsKey, KG, CipherText};
 #[cfg(feature = "kyber")]
 use fips203::traits::{SerDes, KeyGen, Decaps, Encaps};
 #[cfg(feature = "kyber")]
 use sha2::{Sha256, Digest};
+use zeroize::Zeroizing;
 
 // ======================= Kyber Key Pair Definition =======================
-/// Represents a Kyber key pair.
+/// Represents a Kyber key pair. `fips203` zeroizes the decapsulation key on drop.
 #[derive(Clone)]
 pub struct KyberKeyPair {
     pub public_key: EncapsKey,
@@ -24,7 +26,7 @@ impl PKITraits for KyberKeyPair {
     type Error = PKIError;
 
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
-        let (public_key, private_key) = KG::try_keygen().map_err(|e| {
+        let (public_key, private_key) = KG::try_keygen_with_rng(&mut KeyRng).map_err(|e| {
             PKIError::KeyPairGenerationError(format!("Key generation failed: {:?}", e))
         })?;
         Ok(KyberKeyPair {
@@ -66,7 +68,7 @@ impl KeyExchange for KyberKeyPair {
         }
 
         let (shared_secret, ciphertext) = public_key
-            .try_encaps()
+            .try_encaps_with_rng(&mut KeyRng)
             .map_err(|e| PKIError::KeyExchangeError(format!("Encapsulation failed: {}", e)))?;
 
         let mut hasher = Sha256::new();
@@ -123,14 +125,16 @@ impl KeyExchange for KyberKeyPair {
 }
 // ======================= Key Serialization Implmentation =======================
 impl crate::KeySerialization for KyberKeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_KYBER;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let public_key_bytes = self.public_key.clone().into_bytes().to_vec();
-        let private_key_bytes = self.private_key.clone().into_bytes().to_vec();
+        let private_key_bytes = Zeroizing::new(self.private_key.clone().into_bytes());
 
-        [public_key_bytes, private_key_bytes].concat()
+        [public_key_bytes.as_slice(), private_key_bytes.as_slice()].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
         let key_len = 1568; // Length of the public key in bytes
         if bytes.len() < 2 * key_len {
             return Err(PKIError::InvalidKey("Insufficient data for deserialization".to_string()));
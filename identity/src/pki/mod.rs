@@ -5,6 +5,49 @@
 //! `PKITraits` trait and supports operations like key generation, signing,
 //! and verification.
 // identity\src\pki\mod.rs
+#[cfg(any(
+    feature = "pki_rsa",
+    feature = "secp256k1",
+    feature = "ecdsa",
+    feature = "ed25519",
+    feature = "dilithium",
+    feature = "spincs",
+    feature = "falcon"
+))]
+use crate::PKIError;
+
+/// Rejects a signature whose length falls outside `expected` before it reaches the
+/// algorithm's parser, so untrusted input never drives a primitive that assumes a size.
+#[cfg(any(
+    feature = "pki_rsa",
+    feature = "secp256k1",
+    feature = "ecdsa",
+    feature = "ed25519",
+    feature = "dilithium",
+    feature = "spincs",
+    feature = "falcon"
+))]
+pub(crate) fn check_signature_len(
+    algorithm: &str,
+    signature: &[u8],
+    expected: std::ops::RangeInclusive<usize>,
+) -> Result<(), PKIError> {
+    if expected.contains(&signature.len()) {
+        return Ok(());
+    }
+    let expected = if expected.start() == expected.end() {
+        format!("{} bytes", expected.start())
+    } else {
+        format!("{} to {} bytes", expected.start(), expected.end())
+    };
+    Err(PKIError::MalformedSignature(format!(
+        "{} signature of {} bytes, expected {}",
+        algorithm,
+        signature.len(),
+        expected
+    )))
+}
+
 // RSA key pair implementation
 #[cfg(feature = "pki_rsa")]
 mod rsa_keypair;
@@ -27,7 +70,7 @@ pub use ecdsa_keypair::ECDSAKeyPair;
 #[cfg(feature = "ed25519")]
 mod ed25519_keypair;
 #[cfg(feature = "ed25519")]
-pub use ed25519_keypair::Ed25519KeyPair;
+pub use ed25519_keypair::{Ed25519KeyPair, X25519Ephemeral};
 
 // Dilithium key pair implementation
 #[cfg(feature = "dilithium")]
@@ -45,7 +88,7 @@ pub use spincs_keypair::SPHINCSKeyPair;
 #[cfg(feature = "falcon")]
 mod falcon_keypair;
 #[cfg(feature = "falcon")]
-pub use falcon_keypair::FalconKeyPair;
+pub use falcon_keypair::{FalconKeyPair, FalconPublicVerifier};
 
 
 // Kyber key pair Implementation
@@ -6,10 +6,21 @@ use crate::{PKIError, PKITraits, KeyExchange};
 #[cfg(feature = "secp256k1")]
 use k256::ecdsa::{
     signature::{Signer, Verifier},
-    Signature, SigningKey, VerifyingKey,
+    signature::hazmat::{PrehashSigner, PrehashVerifier},
+    RecoveryId, Signature, SigningKey, VerifyingKey,
 };
 #[cfg(feature = "secp256k1")]
-use rand_core::OsRng;
+use crate::rng::KeyRng;
+#[cfg(feature = "secp256k1")]
+use crate::pki::check_signature_len;
+
+/// A DER `SEQUENCE { r, s }` of two 256-bit integers: 8 bytes for one-byte
+/// integers up to 72 with both padded to 33 bytes.
+#[cfg(feature = "secp256k1")]
+const DER_SIGNATURE_LEN: std::ops::RangeInclusive<usize> = 8..=72;
+/// A raw `r || s` signature followed by the one-byte recovery id.
+#[cfg(feature = "secp256k1")]
+const RECOVERABLE_SIGNATURE_LEN: usize = 65;
 #[cfg(feature = "secp256k1")]
 use k256::elliptic_curve::sec1::ToEncodedPoint;
 
@@ -29,7 +40,7 @@ impl PKITraits for SECP256K1KeyPair {
 
     /// Generates a new SECP256K1 key pair.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
-        let signing_key = SigningKey::random(&mut OsRng);
+        let signing_key = SigningKey::random(&mut KeyRng);
         let verifying_key = *signing_key.verifying_key();
 
         Ok(Self {
@@ -46,6 +57,7 @@ impl PKITraits for SECP256K1KeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("secp256k1", signature, DER_SIGNATURE_LEN)?;
         let signature = Signature::from_der(signature)
             .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
         self.verifying_key
@@ -54,6 +66,42 @@ impl PKITraits for SECP256K1KeyPair {
             .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
     }
 
+    /// Signs a SHA-256 digest, producing the same DER encoding as `sign`.
+    fn sign_prehashed(&self, digest: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        if digest.len() != 32 {
+            return Err(PKIError::SigningError(format!("Expected a 32-byte SHA-256 digest, got {} bytes", digest.len())));
+        }
+        let signature: Signature = self
+            .signing_key
+            .sign_prehash(digest)
+            .map_err(|e| PKIError::SigningError(format!("Prehash signing failed: {}", e)))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+
+    /// Verifies a DER signature over a SHA-256 digest.
+    fn verify_prehashed(&self, digest: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        if digest.len() != 32 {
+            return Err(PKIError::VerificationError(format!("Expected a 32-byte SHA-256 digest, got {} bytes", digest.len())));
+        }
+        check_signature_len("secp256k1", signature, DER_SIGNATURE_LEN)?;
+        let signature = Signature::from_der(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
+        self.verifying_key
+            .verify_prehash(digest, &signature)
+            .map(|_| true)
+            .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
+    }
+
+    /// Verifies a DER signature against a SEC1-encoded public key.
+    fn verify_with_public_key(public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        let verifying_key = VerifyingKey::from_sec1_bytes(public_key)
+            .map_err(|_| PKIError::InvalidKey("Invalid secp256k1 public key".to_string()))?;
+        check_signature_len("secp256k1", signature, DER_SIGNATURE_LEN)?;
+        let signature = Signature::from_der(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid signature format: {}", e)))?;
+        Ok(verifying_key.verify(data, &signature).is_ok())
+    }
+
     /// Retrieves the public key from the key pair.
     fn get_public_key_raw_bytes(&self) -> Vec<u8> {
         // Get the public key in uncompressed format (0x04 indicates uncompressed)
@@ -79,7 +127,7 @@ impl KeyExchange for SECP256K1KeyPair {
         public_key: &Self::PublicKey,
         _context: Option<&[u8]>,
     ) -> Result<(Self::SharedSecretKey, Vec<u8>), Self::Error> {
-        let ephemeral_private_key = k256::SecretKey::random(&mut OsRng);
+        let ephemeral_private_key = k256::SecretKey::random(&mut KeyRng);
         let ephemeral_public_key = ephemeral_private_key.public_key();
 
         // Compute the shared secret
@@ -123,14 +171,16 @@ impl KeyExchange for SECP256K1KeyPair {
 // ======================= Key Serialization Implmentation =======================
 #[cfg(feature = "secp256k1")]
 impl crate::KeySerialization for SECP256K1KeyPair {
-    fn to_bytes(&self) -> Vec<u8> {
+    const ALGORITHM_TAG: u8 = crate::key_serde_trait::ALGORITHM_TAG_SECP256K1;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
         let signing_key_bytes = self.signing_key.to_bytes().to_vec();
         let verifying_key_bytes = self.verifying_key.to_encoded_point(false).as_bytes().to_vec();
 
         [signing_key_bytes, verifying_key_bytes].concat()
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, PKIError> {
         let signing_key_size = 32; // SECP256K1 private key size
         if bytes.len() <= signing_key_size {
             return Err(PKIError::InvalidKey("Insufficient data for deserialization".to_string()));
@@ -149,3 +199,82 @@ impl crate::KeySerialization for SECP256K1KeyPair {
         })
     }
 }
+
+// ======================= Signature Encoding Helpers =======================
+#[cfg(feature = "secp256k1")]
+impl SECP256K1KeyPair {
+    /// Signs data and returns an ASN.1 DER signature (same encoding as `sign`).
+    pub fn sign_der(&self, data: &[u8]) -> Result<Vec<u8>, PKIError> {
+        self.sign(data)
+    }
+
+    /// Verifies an ASN.1 DER signature.
+    pub fn verify_der(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+        self.verify(data, signature)
+    }
+
+    /// Signs data and returns the fixed-size 64-byte `r || s` encoding.
+    pub fn sign_raw(&self, data: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature: Signature = self.signing_key.sign(data);
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    /// Verifies a fixed-size 64-byte `r || s` signature.
+    pub fn verify_raw(&self, data: &[u8], signature: &[u8]) -> Result<bool, PKIError> {
+        check_signature_len("secp256k1", signature, 64..=64)?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid raw signature: {}", e)))?;
+        self.verifying_key
+            .verify(data, &signature)
+            .map(|_| true)
+            .map_err(|e| PKIError::VerificationError(format!("Verification failed: {}", e)))
+    }
+
+    /// Signs a 32-byte message hash and returns the 65-byte recoverable form
+    /// `r || s || v`, where `v` is the recovery id (0-3).
+    pub fn sign_recoverable(&self, message_hash: &[u8]) -> Result<Vec<u8>, PKIError> {
+        if message_hash.len() != 32 {
+            return Err(PKIError::SigningError(format!("Expected a 32-byte message hash, got {} bytes", message_hash.len())));
+        }
+        let (signature, recovery_id) = self
+            .signing_key
+            .sign_prehash_recoverable(message_hash)
+            .map_err(|e| PKIError::SigningError(format!("Recoverable signing failed: {}", e)))?;
+
+        let mut recoverable = signature.to_bytes().to_vec();
+        recoverable.push(recovery_id.to_byte());
+        Ok(recoverable)
+    }
+
+    /// Recovers the public key that produced a `sign_recoverable` signature over
+    /// `message_hash`, returned in the uncompressed form of `get_public_key_raw_bytes`.
+    pub fn recover_public_key(message_hash: &[u8], recoverable_sig: &[u8]) -> Result<Vec<u8>, PKIError> {
+        if message_hash.len() != 32 {
+            return Err(PKIError::VerificationError(format!("Expected a 32-byte message hash, got {} bytes", message_hash.len())));
+        }
+        check_signature_len("secp256k1", recoverable_sig, RECOVERABLE_SIGNATURE_LEN..=RECOVERABLE_SIGNATURE_LEN)?;
+        let (raw, recovery_byte) = recoverable_sig.split_at(64);
+        let signature = Signature::from_slice(raw)
+            .map_err(|e| PKIError::VerificationError(format!("Invalid raw signature: {}", e)))?;
+        let recovery_id = RecoveryId::from_byte(recovery_byte[0])
+            .ok_or_else(|| PKIError::MalformedSignature(format!("Invalid recovery id {}", recovery_byte[0])))?;
+
+        let verifying_key = VerifyingKey::recover_from_prehash(message_hash, &signature, recovery_id)
+            .map_err(|e| PKIError::VerificationError(format!("Public key recovery failed: {}", e)))?;
+        Ok(verifying_key.to_encoded_point(false).as_bytes().to_vec())
+    }
+
+    /// Converts a DER signature to its raw `r || s` form.
+    pub fn der_to_raw(der: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature = Signature::from_der(der)
+            .map_err(|e| PKIError::MalformedSignature(format!("Invalid DER signature: {}", e)))?;
+        Ok(signature.to_bytes().to_vec())
+    }
+
+    /// Converts a raw `r || s` signature to DER.
+    pub fn raw_to_der(raw: &[u8]) -> Result<Vec<u8>, PKIError> {
+        let signature = Signature::from_slice(raw)
+            .map_err(|e| PKIError::MalformedSignature(format!("Invalid raw signature: {}", e)))?;
+        Ok(signature.to_der().as_bytes().to_vec())
+    }
+}
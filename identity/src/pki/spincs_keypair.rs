@@ -4,6 +4,10 @@
 #[cfg(feature = "spincs")]
 use crate::{PKIError, PKITraits};
 #[cfg(feature = "spincs")]
+use crate::rng::KeyRng;
+#[cfg(feature = "spincs")]
+use crate::pki::check_signature_len;
+#[cfg(feature = "spincs")]
 use fips205::slh_dsa_shake_256s::{self, PrivateKey, PublicKey};
 #[cfg(feature = "spincs")]
 use fips205::traits::{SerDes, Signer, Verifier};
@@ -25,7 +29,7 @@ impl PKITraits for SPHINCSKeyPair {
 
     /// Generates a new SPHINCS+ key pair.
     fn generate_key_pair() -> Result<Self::KeyPair, Self::Error> {
-        let (public_key, private_key) = slh_dsa_shake_256s::try_keygen()
+        let (public_key, private_key) = slh_dsa_shake_256s::try_keygen_with_rng(&mut KeyRng)
             .map_err(|e| PKIError::KeyPairGenerationError(format!("Key generation failed: {}", e)))?;
 
         Ok(Self {
@@ -46,9 +50,10 @@ impl PKITraits for SPHINCSKeyPair {
 
     /// Verifies a signature using the public key.
     fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error> {
+        check_signature_len("SPHINCS+", signature, slh_dsa_shake_256s::SIG_LEN..=slh_dsa_shake_256s::SIG_LEN)?;
         let signature_array: [u8; slh_dsa_shake_256s::SIG_LEN] = signature
             .try_into()
-            .map_err(|_| PKIError::VerificationError("Invalid signature length".to_string()))?;
+            .map_err(|_| PKIError::MalformedSignature("Invalid signature length".to_string()))?;
 
         let is_valid = self.public_key.verify(data, &signature_array, &[]);
         Ok(is_valid)
@@ -29,6 +29,12 @@ use std::fmt;
 ///   message detailing the key exchange failure.
 /// - `InvalidKey`: Used when an invalid key is encountered. Contains a message
 ///   explaining why the key is considered invalid.
+/// - `DecryptionFailed`: Used when an encrypted key cannot be opened, usually
+///   because the passphrase is wrong.
+/// - `MalformedSignature`: Used when a signature's length is impossible for the
+///   algorithm, so it is rejected before reaching the primitive.
+/// - `RateLimited`: Used when a `VerificationBudget` refuses to run another
+///   verification. Contains a message describing the exhausted budget.
 #[derive(Debug, Clone)]
 pub enum PKIError {
     /// Error during key pair generation.
@@ -86,6 +92,25 @@ pub enum PKIError {
     /// This variant is used when an invalid key is encountered. It contains
     /// a message explaining why the key is considered invalid.
     InvalidKey(String),
+
+    /// Error opening an encrypted key.
+    ///
+    /// This variant is used when a passphrase-protected key fails AES-GCM
+    /// authentication, which means the passphrase is wrong or the data was
+    /// altered. No key material is returned.
+    DecryptionFailed(String),
+
+    /// Signature of an impossible size.
+    ///
+    /// This variant is returned by `verify` and its variants when the signature
+    /// is too short or too long for the algorithm; the primitive is never called.
+    MalformedSignature(String),
+
+    /// Verification refused by a rate limiter.
+    ///
+    /// This variant is returned by `VerificationBudget` once its per-second
+    /// allowance is used up; the signature was not checked at all.
+    RateLimited(String),
 }
 
 /// Implementation of the `fmt::Display` trait for `PKIError`.
@@ -109,6 +134,9 @@ impl fmt::Display for PKIError {
             PKIError::DecodingError(msg) => write!(f, "Decoding error: {}", msg),
             PKIError::KeyExchangeError(msg) => write!(f, "Key exchange error: {}", msg),
             PKIError::InvalidKey(msg) => write!(f, "Invalid key: {}", msg),
+            PKIError::DecryptionFailed(msg) => write!(f, "Decryption failed: {}", msg),
+            PKIError::MalformedSignature(msg) => write!(f, "Malformed signature: {}", msg),
+            PKIError::RateLimited(msg) => write!(f, "Rate limited: {}", msg),
             PKIError::GenericError(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -1,4 +1,7 @@
 // identity\src\pki_trait.rs
+use crate::PKIError;
+use subtle::ConstantTimeEq;
+
 /// A trait defining core functionalities for Public Key Infrastructure (PKI) operations.
 /// 
 /// This trait provides methods for generating key pairs, signing and verifying data,
@@ -44,9 +47,89 @@ pub trait PKITraits {
   /// - `Err(Error)`: If verification fails due to other reasons.
   fn verify(&self, data: &[u8], signature: &[u8]) -> Result<bool, Self::Error>;
 
+  /// Signs a message digest that the caller has already computed.
+  ///
+  /// The digest must be the SHA-256 hash of the message, so that a signature from
+  /// `sign_prehashed(&sha256(msg))` verifies with `verify(msg, ..)` and vice versa.
+  /// Schemes without a prehash mode return `UnsupportedOperation`.
+  ///
+  /// # Arguments
+  /// - `digest`: The 32-byte SHA-256 digest of the message.
+  ///
+  /// # Returns
+  /// - `Ok(Vec<u8>)`: The signature over the digest.
+  /// - `Err(Error)`: If signing fails or the scheme has no prehash mode.
+  fn sign_prehashed(&self, _digest: &[u8]) -> Result<Vec<u8>, Self::Error>
+  where
+    Self::Error: From<PKIError>,
+  {
+    Err(PKIError::UnsupportedOperation(format!("{} does not support prehashed signing", Self::key_type())).into())
+  }
+
+  /// Verifies a signature over a SHA-256 digest computed by the caller.
+  ///
+  /// # Arguments
+  /// - `digest`: The 32-byte SHA-256 digest of the message.
+  /// - `signature`: A slice of bytes representing the signature.
+  ///
+  /// # Returns
+  /// - `Ok(true)`: If the signature is valid for the digest.
+  /// - `Err(Error)`: If verification fails or the scheme has no prehash mode.
+  fn verify_prehashed(&self, _digest: &[u8], _signature: &[u8]) -> Result<bool, Self::Error>
+  where
+    Self::Error: From<PKIError>,
+  {
+    Err(PKIError::UnsupportedOperation(format!("{} does not support prehashed verification", Self::key_type())).into())
+  }
+
+  /// Verifies a signature using only an encoded public key, in the format returned
+  /// by `get_public_key_raw_bytes`. Schemes that cannot rebuild a verifier from raw
+  /// public key bytes return `UnsupportedOperation`.
+  ///
+  /// # Returns
+  /// - `Ok(true)`: If the signature is valid for `public_key`.
+  /// - `Ok(false)`: If the signature does not verify under `public_key`.
+  /// - `Err(Error)`: If the key or signature is malformed.
+  fn verify_with_public_key(_public_key: &[u8], _data: &[u8], _signature: &[u8]) -> Result<bool, Self::Error>
+  where
+    Self::Error: From<PKIError>,
+  {
+    Err(PKIError::UnsupportedOperation(format!("{} does not support verification from raw public keys", Self::key_type())).into())
+  }
+
+  /// Finds which of several trusted public keys produced `signature`.
+  ///
+  /// Every candidate is fully verified in turn; the search only stops on a key that
+  /// verifies, never on the outcome of a partial comparison. A key that cannot be
+  /// used (malformed, or rejecting the signature's encoding) counts as a non-match.
+  ///
+  /// # Returns
+  /// - `Ok(Some(index))`: The index in `keys` of the first key that verifies.
+  /// - `Ok(None)`: If no key verifies the signature.
+  fn verify_any(data: &[u8], signature: &[u8], keys: &[&[u8]]) -> Result<Option<usize>, Self::Error>
+  where
+    Self::Error: From<PKIError>,
+  {
+    for (index, public_key) in keys.iter().enumerate() {
+      if let Ok(true) = Self::verify_with_public_key(public_key, data, signature) {
+        return Ok(Some(index));
+      }
+    }
+    Ok(None)
+  }
+
   /// Retrieves the public key from the key pair.
   fn get_public_key_raw_bytes(&self) -> Vec<u8>;
 
+  /// Checks whether `other_raw` is this key pair's public key, in the encoding of
+  /// `get_public_key_raw_bytes`.
+  ///
+  /// The bytes are compared in constant time, so this is the way to pin a peer's
+  /// identity (e.g. to confirm a reconnecting peer presents the same key).
+  fn public_key_equals(&self, other_raw: &[u8]) -> bool {
+    self.get_public_key_raw_bytes().as_slice().ct_eq(other_raw).into()
+  }
+
   /// Retrieves the key type (e.g., "RSA", "Ed25519").
   fn key_type() -> String;
 }
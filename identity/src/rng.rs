@@ -0,0 +1,70 @@
+// identity\src\rng.rs
+//! Randomness used by `generate_key_pair` and `encapsulate`.
+//!
+//! By default `KeyRng` is the operating system RNG. With the `deterministic-testing`
+//! feature, `test_seed` installs a thread-local `ChaCha20Rng` that every key
+//! generation and encapsulation on that thread draws from instead. Falcon is the
+//! exception: `pqcrypto-falcon` calls the system RNG from C and cannot be seeded.
+
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+#[cfg(all(feature = "deterministic-testing", not(debug_assertions)))]
+compile_error!("the `deterministic-testing` feature must not be enabled in release builds");
+
+#[cfg(feature = "deterministic-testing")]
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+#[cfg(feature = "deterministic-testing")]
+use std::cell::RefCell;
+
+#[cfg(feature = "deterministic-testing")]
+thread_local! {
+    static SEEDED_RNG: RefCell<Option<ChaCha20Rng>> = const { RefCell::new(None) };
+}
+
+/// Seeds the key-generation RNG for the current thread. Every later
+/// `generate_key_pair`/`encapsulate` call on this thread is reproducible, except
+/// Falcon key generation, which ignores the seed.
+#[cfg(feature = "deterministic-testing")]
+pub fn test_seed(seed: u64) {
+    SEEDED_RNG.with(|rng| *rng.borrow_mut() = Some(ChaCha20Rng::seed_from_u64(seed)));
+}
+
+/// RNG handle used by the key pair implementations.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct KeyRng;
+
+impl KeyRng {
+    #[cfg(feature = "deterministic-testing")]
+    fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        SEEDED_RNG.with(|seeded| match seeded.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut OsRng),
+        })
+    }
+
+    #[cfg(not(feature = "deterministic-testing"))]
+    fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        f(&mut OsRng)
+    }
+}
+
+impl RngCore for KeyRng {
+    fn next_u32(&mut self) -> u32 {
+        Self::with_rng(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Self::with_rng(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Self::with_rng(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        Self::with_rng(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+// Both sources behind `KeyRng` are cryptographically secure.
+impl CryptoRng for KeyRng {}
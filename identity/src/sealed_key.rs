@@ -0,0 +1,99 @@
+// identity\src\sealed_key.rs
+use crate::rng::KeyRng;
+use crate::PKIError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use data_encryption::{derive_key_from_passphrase, DerivedKey, PassphraseKdf};
+use rand_core::RngCore;
+use zeroize::Zeroizing;
+
+/// Magic bytes opening every sealed key file ("Nautilus Sealed").
+pub const SEALED_KEY_MAGIC: [u8; 2] = *b"NS";
+/// Current version of the sealed key layout.
+pub const SEALED_KEY_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+/// Argon2id cost used when sealing: 64 MiB, 3 passes, 1 lane.
+const ARGON2_MEMORY_KIB: u32 = 64 * 1024;
+const ARGON2_ITERATIONS: u32 = 3;
+const ARGON2_PARALLELISM: u32 = 1;
+/// Upper bounds on the costs accepted from a file, so a crafted header cannot make
+/// `open` allocate or run without limit.
+const ARGON2_MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const ARGON2_MAX_ITERATIONS: u32 = 16;
+const ARGON2_MAX_PARALLELISM: u32 = 16;
+/// magic + version + three u32 Argon2 costs + salt + nonce.
+const HEADER_LEN: usize = SEALED_KEY_MAGIC.len() + 1 + 12 + SALT_LEN + NONCE_LEN;
+
+/// Encrypts `plaintext` under a key derived from `passphrase`.
+///
+/// Layout: `SEALED_KEY_MAGIC`, `SEALED_KEY_VERSION`, Argon2id memory / iterations /
+/// parallelism as big-endian `u32`s, the salt, the AES-GCM nonce, then the ciphertext.
+pub(crate) fn seal(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, PKIError> {
+  let mut salt = [0u8; SALT_LEN];
+  let mut nonce = [0u8; NONCE_LEN];
+  KeyRng.fill_bytes(&mut salt);
+  KeyRng.fill_bytes(&mut nonce);
+
+  let key = derive_key(passphrase, &salt, ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, ARGON2_PARALLELISM)?;
+  let ciphertext = Aes256Gcm::new_from_slice(key.key())
+    .map_err(|_| PKIError::EncodingError("Invalid AES-256 key length".to_string()))?
+    .encrypt(Nonce::from_slice(&nonce), plaintext)
+    .map_err(|_| PKIError::EncodingError("AES-GCM encryption failed".to_string()))?;
+
+  let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+  out.extend_from_slice(&SEALED_KEY_MAGIC);
+  out.push(SEALED_KEY_VERSION);
+  out.extend_from_slice(&ARGON2_MEMORY_KIB.to_be_bytes());
+  out.extend_from_slice(&ARGON2_ITERATIONS.to_be_bytes());
+  out.extend_from_slice(&ARGON2_PARALLELISM.to_be_bytes());
+  out.extend_from_slice(&salt);
+  out.extend_from_slice(&nonce);
+  out.extend_from_slice(&ciphertext);
+  Ok(out)
+}
+
+/// Reverses [`seal`]. A malformed header is a `DecodingError`; a wrong passphrase
+/// (or tampered ciphertext) fails authentication and is a `DecryptionFailed`.
+pub(crate) fn open(data: &[u8], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, PKIError> {
+  if data.len() < HEADER_LEN || !data.starts_with(&SEALED_KEY_MAGIC) {
+    return Err(PKIError::DecodingError("not a sealed key file".to_string()));
+  }
+  if data[SEALED_KEY_MAGIC.len()] != SEALED_KEY_VERSION {
+    return Err(PKIError::DecodingError("unsupported sealed key version".to_string()));
+  }
+
+  let (costs, rest) = data[SEALED_KEY_MAGIC.len() + 1..].split_at(12);
+  let cost = |i: usize| u32::from_be_bytes(costs[i * 4..i * 4 + 4].try_into().unwrap());
+  let (memory_kib, iterations, parallelism) = (cost(0), cost(1), cost(2));
+  if memory_kib > ARGON2_MAX_MEMORY_KIB {
+    return Err(PKIError::DecodingError("sealed key memory cost is too large".to_string()));
+  }
+  if !(1..=ARGON2_MAX_ITERATIONS).contains(&iterations) {
+    return Err(PKIError::DecodingError("sealed key iteration count is out of range".to_string()));
+  }
+  if !(1..=ARGON2_MAX_PARALLELISM).contains(&parallelism) {
+    return Err(PKIError::DecodingError("sealed key parallelism is out of range".to_string()));
+  }
+  let (salt, rest) = rest.split_at(SALT_LEN);
+  let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+  let key = derive_key(passphrase, salt, memory_kib, iterations, parallelism)?;
+  let plaintext = Aes256Gcm::new_from_slice(key.key())
+    .map_err(|_| PKIError::DecodingError("Invalid AES-256 key length".to_string()))?
+    .decrypt(Nonce::from_slice(nonce), ciphertext)
+    .map_err(|_| PKIError::DecryptionFailed("wrong passphrase or corrupted key file".to_string()))?;
+  Ok(Zeroizing::new(plaintext))
+}
+
+fn derive_key(
+  passphrase: &str,
+  salt: &[u8],
+  memory_kib: u32,
+  iterations: u32,
+  parallelism: u32,
+) -> Result<DerivedKey, PKIError> {
+  let kdf = PassphraseKdf::Argon2id { memory_size_kb: memory_kib, iterations, parallelism };
+  derive_key_from_passphrase(passphrase, salt, &kdf).map_err(PKIError::GenericError)
+}
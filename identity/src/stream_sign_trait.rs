@@ -0,0 +1,120 @@
+// identity\src\stream_sign_trait.rs
+use crate::{PKIError, PKITraits};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Size of the buffer used while hashing a stream.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest stream `DetachedStreamSigning` reads into memory.
+pub const DETACHED_STREAM_MAX_LEN: usize = 64 * 1024 * 1024;
+
+/// Signs and verifies data read from an `AsyncRead` without buffering it in memory.
+///
+/// The stream is hashed incrementally with SHA-256 and the digest goes through the
+/// prehashed path, so a stream signature verifies with `verify` over the same bytes.
+/// Implemented for every `PKITraits` type whose error is `PKIError`; schemes without
+/// a prehash mode return `UnsupportedOperation`.
+pub trait StreamSigning: PKITraits<Error = PKIError> + Sync {
+  /// Signs everything read from `reader` until EOF.
+  fn sign_stream<R>(&self, reader: R) -> impl Future<Output = Result<Vec<u8>, PKIError>> + Send
+  where
+    R: AsyncRead + Unpin + Send,
+  {
+    async move {
+      let digest = sha256_stream(reader).await?;
+      self.sign_prehashed(&digest)
+    }
+  }
+
+  /// Verifies `signature` over everything read from `reader` until EOF.
+  fn verify_stream<R>(&self, reader: R, signature: &[u8]) -> impl Future<Output = Result<bool, PKIError>> + Send
+  where
+    R: AsyncRead + Unpin + Send,
+  {
+    async move {
+      let digest = sha256_stream(reader).await?;
+      self.verify_prehashed(&digest, signature)
+    }
+  }
+}
+
+impl<T> StreamSigning for T where T: PKITraits<Error = PKIError> + Sync {}
+
+/// Signs and verifies streams for the detached-signature schemes (Falcon, Dilithium).
+///
+/// Neither has a prehash mode whose signatures `verify` accepts over the original
+/// message, so the stream is read into memory and signed in one shot. Streams longer
+/// than `DETACHED_STREAM_MAX_LEN` are refused rather than buffered. Signatures are
+/// interchangeable with `sign`/`verify` over the same bytes.
+pub trait DetachedStreamSigning: PKITraits<Error = PKIError> + Sync {
+  /// Signs everything read from `reader` until EOF.
+  fn sign_stream_detached<R>(&self, reader: R) -> impl Future<Output = Result<Vec<u8>, PKIError>> + Send
+  where
+    R: AsyncRead + Unpin + Send,
+  {
+    async move {
+      let message = read_capped(reader)
+        .await
+        .map_err(PKIError::SigningError)?;
+      self.sign(&message)
+    }
+  }
+
+  /// Verifies `signature` over everything read from `reader` until EOF.
+  fn verify_stream_detached<R>(&self, reader: R, signature: &[u8]) -> impl Future<Output = Result<bool, PKIError>> + Send
+  where
+    R: AsyncRead + Unpin + Send,
+  {
+    async move {
+      let message = read_capped(reader)
+        .await
+        .map_err(PKIError::VerificationError)?;
+      self.verify(&message, signature)
+    }
+  }
+}
+
+#[cfg(feature = "falcon")]
+impl DetachedStreamSigning for crate::FalconKeyPair {}
+
+#[cfg(feature = "dilithium")]
+impl DetachedStreamSigning for crate::DilithiumKeyPair {}
+
+/// Reads a stream to EOF, failing once it exceeds `DETACHED_STREAM_MAX_LEN`.
+async fn read_capped<R>(reader: R) -> Result<Vec<u8>, String>
+where
+  R: AsyncRead + Unpin,
+{
+  let mut message = Vec::new();
+  reader
+    .take(DETACHED_STREAM_MAX_LEN as u64 + 1)
+    .read_to_end(&mut message)
+    .await
+    .map_err(|e| format!("Failed to read stream: {}", e))?;
+  if message.len() > DETACHED_STREAM_MAX_LEN {
+    return Err(format!("Stream exceeds {} bytes", DETACHED_STREAM_MAX_LEN));
+  }
+  Ok(message)
+}
+
+/// Hashes a stream to EOF in fixed-size chunks.
+async fn sha256_stream<R>(mut reader: R) -> Result<Vec<u8>, PKIError>
+where
+  R: AsyncRead + Unpin,
+{
+  let mut hasher = Sha256::new();
+  let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+  loop {
+    let read = reader
+      .read(&mut buffer)
+      .await
+      .map_err(|e| PKIError::SigningError(format!("Failed to read stream: {}", e)))?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buffer[..read]);
+  }
+  Ok(hasher.finalize().to_vec())
+}
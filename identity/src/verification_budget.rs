@@ -0,0 +1,91 @@
+// identity\src\verification_budget.rs
+use crate::{PKIError, PKITraits};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Caps how many signature verifications run per second.
+///
+/// Verifying SPHINCS+ or large RSA signatures is expensive, so a peer that floods a
+/// node with signatures to check can exhaust its CPU. Put signatures from untrusted
+/// sources through a shared `VerificationBudget`: it is a token bucket holding up to
+/// `max_per_second` verifications, refilled continuously, and requests beyond it fail
+/// with `PKIError::RateLimited` before any verification work is done.
+#[derive(Debug)]
+pub struct VerificationBudget {
+  max_per_second: u32,
+  bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+  tokens: f64,
+  refilled_at: Instant,
+}
+
+impl VerificationBudget {
+  /// Creates a budget allowing `max_per_second` verifications per second, all of
+  /// which are available immediately.
+  pub fn new(max_per_second: u32) -> Self {
+    Self {
+      max_per_second,
+      bucket: Mutex::new(Bucket {
+        tokens: f64::from(max_per_second),
+        refilled_at: Instant::now(),
+      }),
+    }
+  }
+
+  /// Returns the configured number of verifications per second.
+  pub fn max_per_second(&self) -> u32 {
+    self.max_per_second
+  }
+
+  /// Takes one verification from the budget.
+  ///
+  /// # Returns
+  /// - `Ok(())`: If a verification may run now.
+  /// - `Err(PKIError::RateLimited)`: If the budget is exhausted.
+  pub fn try_acquire(&self) -> Result<(), PKIError> {
+    self.try_acquire_many(1)
+  }
+
+  /// Verifies `signature` with `K::verify_with_public_key` if the budget allows it.
+  pub fn verify_with_public_key<K>(&self, public_key: &[u8], data: &[u8], signature: &[u8]) -> Result<bool, PKIError>
+  where
+    K: PKITraits<Error = PKIError>,
+  {
+    self.try_acquire()?;
+    K::verify_with_public_key(public_key, data, signature)
+  }
+
+  /// Runs `K::verify_any` if the budget allows one verification per candidate key.
+  ///
+  /// Keys are charged up front so a large key list cannot bypass the budget; if not
+  /// all of them fit, no verification runs.
+  pub fn verify_any<K>(&self, data: &[u8], signature: &[u8], keys: &[&[u8]]) -> Result<Option<usize>, PKIError>
+  where
+    K: PKITraits<Error = PKIError>,
+  {
+    self.try_acquire_many(keys.len())?;
+    K::verify_any(data, signature, keys)
+  }
+
+  /// Takes `count` verifications from the budget at once, or none of them.
+  fn try_acquire_many(&self, count: usize) -> Result<(), PKIError> {
+    let mut bucket = self.bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let now = Instant::now();
+    let capacity = f64::from(self.max_per_second);
+    let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * capacity;
+    bucket.tokens = (bucket.tokens + refill).min(capacity);
+    bucket.refilled_at = now;
+
+    if bucket.tokens < count as f64 {
+      return Err(PKIError::RateLimited(format!(
+        "budget of {} signature verifications per second exhausted",
+        self.max_per_second
+      )));
+    }
+    bucket.tokens -= count as f64;
+    Ok(())
+  }
+}
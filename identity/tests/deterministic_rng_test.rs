@@ -0,0 +1,61 @@
+#[cfg(feature = "deterministic-testing")]
+#[cfg(test)]
+mod deterministic_rng_tests {
+    use identity::{test_seed, PKITraits};
+
+    #[cfg(feature = "kyber")]
+    #[test]
+    fn test_seeded_kyber_key_generation_is_reproducible() {
+        use identity::KyberKeyPair;
+
+        test_seed(42);
+        let first = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        test_seed(42);
+        let second = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        assert_eq!(first.get_public_key_raw_bytes(), second.get_public_key_raw_bytes());
+
+        test_seed(43);
+        let other = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        assert_ne!(first.get_public_key_raw_bytes(), other.get_public_key_raw_bytes());
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn test_seeded_ed25519_key_generation_is_reproducible() {
+        use identity::Ed25519KeyPair;
+
+        test_seed(7);
+        let first = Ed25519KeyPair::generate_key_pair().expect("Failed to generate key pair");
+        test_seed(7);
+        let second = Ed25519KeyPair::generate_key_pair().expect("Failed to generate key pair");
+        assert_eq!(first.get_public_key_raw_bytes(), second.get_public_key_raw_bytes());
+    }
+
+    // pqcrypto-falcon draws from the system RNG in C, so seeding has no effect and
+    // Falcon stays out of the reproducible algorithms.
+    #[cfg(feature = "falcon")]
+    #[test]
+    fn test_seeded_falcon_key_generation_is_not_reproducible() {
+        use identity::FalconKeyPair;
+
+        test_seed(7);
+        let first = FalconKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        test_seed(7);
+        let second = FalconKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        assert_ne!(first.get_public_key_raw_bytes(), second.get_public_key_raw_bytes());
+    }
+
+    #[cfg(all(feature = "kyber", feature = "pki_rsa"))]
+    #[test]
+    fn test_one_seed_covers_several_algorithms() {
+        use identity::{KyberKeyPair, RSAkeyPair};
+
+        let run = || {
+            test_seed(2024);
+            let kyber = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+            let rsa = RSAkeyPair::generate_key_pair().expect("Failed to generate key pair");
+            (kyber.get_public_key_raw_bytes(), rsa.get_public_key_raw_bytes())
+        };
+        assert_eq!(run(), run());
+    }
+}
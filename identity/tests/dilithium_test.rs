@@ -254,7 +254,22 @@ mod tests {
             _ => panic!("Unexpected outcome in fake stack overflow test"),
         }
     }
-    
+
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = DilithiumKeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                DilithiumKeyPair::verify_with_public_key(&public_key, b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
 }
 
 #[cfg(feature = "dilithium")]
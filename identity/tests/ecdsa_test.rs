@@ -308,7 +308,22 @@ mod tests {
             "Shared secrets computed by both parties should match"
         );
     }
-    
+
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = ECDSAKeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                ECDSAKeyPair::verify_with_public_key(&public_key, b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
 }
 
 #[cfg(feature = "ecdsa")]
@@ -352,4 +367,39 @@ mod serialization_tests {
 
         assert_eq!(key_pair.get_public_key_raw_bytes(), deserialized.get_public_key_raw_bytes());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sign_and_verify_prehashed() {
+        use sha2::{Digest, Sha256};
+
+        let key_pair = ECDSAKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let data = b"Large payload hashed once by the caller";
+        let digest = Sha256::digest(data);
+
+        let signature = key_pair.sign_prehashed(&digest).expect("Prehash signing failed");
+        assert!(key_pair.verify_prehashed(&digest, &signature).expect("Prehash verification failed"));
+        assert!(key_pair.verify(data, &signature).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_der_and_raw_signature_encodings() {
+        let key_pair = ECDSAKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let data = b"Interop with external ECDSA tooling";
+
+        let der = key_pair.sign_der(data).expect("DER signing failed");
+        let raw = ECDSAKeyPair::der_to_raw(&der).expect("DER to raw conversion failed");
+        assert_eq!(raw.len(), 64);
+        assert!(key_pair.verify_der(data, &der).expect("DER verification failed"));
+        assert!(key_pair.verify_raw(data, &raw).expect("Raw verification failed"));
+
+        // Conversion is lossless in both directions.
+        assert_eq!(ECDSAKeyPair::raw_to_der(&raw).expect("Raw to DER conversion failed"), der);
+
+        let raw_signed = key_pair.sign_raw(data).expect("Raw signing failed");
+        let der_converted = ECDSAKeyPair::raw_to_der(&raw_signed).expect("Raw to DER conversion failed");
+        assert!(key_pair.verify_der(data, &der_converted).expect("DER verification failed"));
+
+        assert!(matches!(ECDSAKeyPair::der_to_raw(&raw), Err(identity::PKIError::MalformedSignature(_))));
+        assert!(matches!(ECDSAKeyPair::raw_to_der(&der), Err(identity::PKIError::MalformedSignature(_))));
+    }
+}
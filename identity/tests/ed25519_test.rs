@@ -3,7 +3,7 @@
 #[cfg(feature = "ed25519")]
 mod tests {
     use std::time::Instant;
-    use identity::{Ed25519KeyPair,PKITraits,KeyExchange};
+    use identity::{Ed25519KeyPair,PKITraits,KeyExchange,X25519Ephemeral};
     use curve25519_dalek::{EdwardsPoint,Scalar};
     #[test]
     fn test_ed25519_keypair() {
@@ -194,6 +194,70 @@ mod tests {
         assert_eq!(alice_shared_secret, bob_shared_secret);
     }
 
+    #[test]
+    fn test_ed25519_sealed_exchange_opens_only_for_recipient() {
+        let recipient = Ed25519KeyPair::generate_key_pair().unwrap();
+        let third_party = Ed25519KeyPair::generate_key_pair().unwrap();
+
+        let (sender_secret, ephemeral_public_key) =
+            Ed25519KeyPair::encapsulate(&recipient.x25519_public_key(), None).unwrap();
+
+        let opened = recipient.open_sealed(&ephemeral_public_key).unwrap();
+        assert_eq!(sender_secret, opened);
+
+        let intercepted = third_party.open_sealed(&ephemeral_public_key).unwrap();
+        assert_ne!(sender_secret, intercepted, "A third party must not derive the sealed secret");
+    }
+
+    #[test]
+    fn test_ed25519_static_static_exchange() {
+        let alice = Ed25519KeyPair::generate_key_pair().unwrap();
+        let bob = Ed25519KeyPair::generate_key_pair().unwrap();
+        let carol = Ed25519KeyPair::generate_key_pair().unwrap();
+
+        let alice_secret = alice.static_static(&bob.x25519_public_key()).unwrap();
+        let bob_secret = bob.static_static(&alice.x25519_public_key()).unwrap();
+        assert_eq!(alice_secret, bob_secret);
+
+        // Deterministic for the same pair, distinct for a different peer.
+        assert_eq!(alice_secret, alice.static_static(&bob.x25519_public_key()).unwrap());
+        assert_ne!(alice_secret, alice.static_static(&carol.x25519_public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_ed25519_ephemeral_ephemeral_exchange() {
+        let alice = X25519Ephemeral::generate();
+        let bob = X25519Ephemeral::generate();
+        let alice_public_key = alice.public_key();
+        let bob_public_key = bob.public_key();
+
+        let alice_secret = alice.diffie_hellman(&bob_public_key).unwrap();
+        let bob_secret = bob.diffie_hellman(&alice_public_key).unwrap();
+        assert_eq!(alice_secret, bob_secret);
+    }
+
+    #[test]
+    fn test_ed25519_rejects_low_order_peer_key() {
+        let key_pair = Ed25519KeyPair::generate_key_pair().unwrap();
+        let identity_point = curve25519_dalek::MontgomeryPoint([0u8; 32]);
+        assert!(key_pair.static_static(&identity_point).is_err());
+        assert!(key_pair.open_sealed(&identity_point.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_x25519_key_follows_public_identity() {
+        let key_pair = Ed25519KeyPair::generate_key_pair().unwrap();
+        let public_key: [u8; 32] = key_pair.get_public_key_raw_bytes().try_into().unwrap();
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key).unwrap();
+
+        // Peers can derive our X25519 key from the Ed25519 public key alone.
+        assert_eq!(key_pair.x25519_public_key(), verifying_key.to_montgomery());
+        assert_eq!(
+            key_pair.x25519_public_key(),
+            EdwardsPoint::mul_base(&key_pair.x25519_private_key()).to_montgomery()
+        );
+    }
+
     #[test]
     fn test_ed25519_key_exchange_type() {
         assert_eq!(Ed25519KeyPair::key_exchange_type(), "X25519-Ed25519");
@@ -216,6 +280,22 @@ mod tests {
 
         assert!(is_valid);
     }
+
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = Ed25519KeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                Ed25519KeyPair::verify_with_public_key(&public_key, b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
 }
 
 
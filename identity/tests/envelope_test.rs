@@ -0,0 +1,38 @@
+#[cfg(test)]
+#[cfg(feature = "envelope")]
+mod tests {
+  use identity::{KyberKeyPair, MultiRecipientEnvelope, PKITraits};
+
+  #[test]
+  fn test_every_recipient_can_open_envelope() {
+    let recipients: Vec<KyberKeyPair> = (0..3)
+      .map(|_| KyberKeyPair::generate_key_pair().expect("Key pair generation failed"))
+      .collect();
+    let outsider = KyberKeyPair::generate_key_pair().expect("Key pair generation failed");
+    let public_keys: Vec<_> = recipients.iter().map(|k| k.public_key.clone()).collect();
+    let message = b"group message for three recipients";
+
+    let envelope = MultiRecipientEnvelope::seal_for_recipients(message, &public_keys).expect("Sealing failed");
+    assert_eq!(envelope.recipients.len(), 3);
+
+    for recipient in &recipients {
+      let opened = envelope.open(&recipient.private_key).expect("Recipient failed to open envelope");
+      assert_eq!(opened, message);
+    }
+    assert!(envelope.open(&outsider.private_key).is_err(), "Non-recipient must not open the envelope");
+  }
+
+  #[test]
+  fn test_tampered_payload_is_rejected() {
+    let recipient = KyberKeyPair::generate_key_pair().expect("Key pair generation failed");
+    let mut envelope =
+      MultiRecipientEnvelope::seal_for_recipients(b"payload", std::slice::from_ref(&recipient.public_key)).expect("Sealing failed");
+    envelope.ciphertext[0] ^= 0x01;
+    assert!(envelope.open(&recipient.private_key).is_err());
+  }
+
+  #[test]
+  fn test_envelope_requires_recipients() {
+    assert!(MultiRecipientEnvelope::seal_for_recipients(b"payload", &[]).is_err());
+  }
+}
@@ -184,6 +184,42 @@ mod tests {
         let result = key_pair.verify(data, &invalid_signature);
         assert!(result.is_err(), "Verification should fail for invalid signature format");
     }
+
+    #[test]
+    fn test_public_only_verifier() {
+        let message = b"Verified with the public key alone";
+        let key_pair = FalconKeyPair::generate_key_pair().expect("Key pair generation failed");
+        let signature = key_pair.sign(message).expect("Signing failed");
+
+        let exported = key_pair.get_public_key_raw_bytes();
+        let verifier = FalconKeyPair::public_from_bytes(&exported).expect("Public key rejected");
+        assert_eq!(verifier.public_key_raw_bytes(), exported);
+        assert!(verifier.verify(message, &signature).expect("Verification failed"));
+        assert!(verifier.verify(b"Tampered message", &signature).is_err());
+
+        assert!(FalconKeyPair::public_from_bytes(&exported[..896]).is_err());
+    }
+
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = FalconKeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        let verifier = FalconKeyPair::public_from_bytes(&public_key).expect("Public key rejected");
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                FalconKeyPair::verify_with_public_key(&public_key, b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                verifier.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
 }
 
 
@@ -191,7 +227,7 @@ mod tests {
 #[cfg(feature = "falcon")]
 mod serialization_tests {
     use pqcrypto_falcon::falcon512::keypair;
-    use identity::{KeySerialization,FalconKeyPair};
+    use identity::{KeySerialization,FalconKeyPair,PKITraits};
     use pqcrypto_traits::sign::{SecretKey,PublicKey};
     #[test]
     fn test_falcon_serialization() {
@@ -223,10 +259,90 @@ mod serialization_tests {
         );
     }
 
+    #[test]
+    fn test_peek_algorithm_of_falcon_key() {
+        let (public_key, secret_key) = keypair();
+        let serialized = FalconKeyPair { public_key, secret_key }.to_bytes();
+        assert_eq!(identity::peek_algorithm(&serialized), Some("Falcon"));
+
+        // Only the header is read: a truncated blob that could never parse still peeks.
+        assert_eq!(identity::peek_algorithm(&serialized[..4]), Some("Falcon"));
+        assert!(FalconKeyPair::from_bytes(&serialized[..4]).is_err());
+    }
+
     #[test]
     fn test_invalid_falcon_deserialization() {
         let invalid_bytes = vec![0u8; 100]; // Invalid size
         let result = FalconKeyPair::from_bytes(&invalid_bytes);
         assert!(result.is_err(), "Deserialization should fail with incorrect input size");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_falcon_secret_key_zeroized() {
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<FalconKeyPair>();
+
+        let mut keypair = FalconKeyPair::generate_key_pair().expect("Key pair generation failed");
+        let private_bytes = keypair.private_key_raw_bytes();
+        assert!(private_bytes.iter().any(|&byte| byte != 0), "Generated secret key should not be all zeros");
+
+        // Drop runs the same zeroize, so inspect its effect on a live pair.
+        keypair.zeroize();
+        assert!(
+            keypair.secret_key.as_bytes().iter().all(|&byte| byte == 0),
+            "Secret key should be wiped after zeroize"
+        );
+    }
+
+    #[cfg(feature = "sealed_keys")]
+    #[test]
+    fn test_falcon_encrypted_round_trip() {
+        let (public_key, secret_key) = keypair();
+        let keypair = FalconKeyPair { public_key, secret_key };
+
+        let sealed = keypair.to_encrypted_bytes("correct horse battery staple").expect("Sealing failed");
+        assert!(sealed.starts_with(&identity::SEALED_KEY_MAGIC));
+
+        let opened = FalconKeyPair::from_encrypted_bytes(&sealed, "correct horse battery staple")
+            .expect("Opening with the right passphrase failed");
+        assert_eq!(keypair.public_key.as_bytes(), opened.public_key.as_bytes());
+        assert_eq!(keypair.secret_key.as_bytes(), opened.secret_key.as_bytes());
+    }
+
+    #[cfg(feature = "sealed_keys")]
+    #[test]
+    fn test_falcon_encrypted_wrong_passphrase() {
+        let (public_key, secret_key) = keypair();
+        let sealed = FalconKeyPair { public_key, secret_key }
+            .to_encrypted_bytes("correct horse battery staple")
+            .expect("Sealing failed");
+
+        match FalconKeyPair::from_encrypted_bytes(&sealed, "wrong passphrase") {
+            Err(identity::PKIError::DecryptionFailed(_)) => {}
+            Err(other) => panic!("Expected DecryptionFailed, got {:?}", other),
+            Ok(_) => panic!("Wrong passphrase opened the key"),
+        }
+    }
+
+    #[cfg(feature = "sealed_keys")]
+    #[test]
+    fn test_falcon_encrypted_rejects_excessive_costs() {
+        let (public_key, secret_key) = keypair();
+        let sealed = FalconKeyPair { public_key, secret_key }
+            .to_encrypted_bytes("correct horse battery staple")
+            .expect("Sealing failed");
+
+        // Header: magic (2), version (1), then memory, iterations and parallelism.
+        for offset in [7, 11] {
+            let mut crafted = sealed.clone();
+            crafted[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            match FalconKeyPair::from_encrypted_bytes(&crafted, "correct horse battery staple") {
+                Err(identity::PKIError::DecodingError(_)) => {}
+                Err(other) => panic!("Expected DecodingError, got {:?}", other),
+                Ok(_) => panic!("Crafted costs were accepted"),
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod tests {
+    use identity::{generate_keyset, AnyKeyPair, PKIError};
+
+    #[test]
+    #[cfg(all(feature = "falcon", feature = "ed25519"))]
+    fn test_generates_falcon_and_ed25519_concurrently() {
+        let keys = generate_keyset(&["Falcon", "ED25519"]).expect("Key set generation failed");
+        assert_eq!(keys.len(), 2);
+
+        let data = b"Provisioned key set";
+        for (name, expected) in [("Falcon", "Falcon"), ("ED25519", "ED25519")] {
+            let key_pair = &keys[name];
+            assert_eq!(key_pair.key_type(), expected);
+            let signature = key_pair.sign(data).expect("Signing failed");
+            assert!(key_pair.verify(data, &signature).expect("Verification failed"));
+        }
+        assert!(matches!(keys["Falcon"], AnyKeyPair::Falcon(_)));
+        assert!(matches!(keys["ED25519"], AnyKeyPair::Ed25519(_)));
+    }
+
+    #[test]
+    #[cfg(feature = "pki_rsa")]
+    fn test_unknown_algorithm_fails_the_key_set() {
+        let result = generate_keyset(&["rsa", "NotAnAlgorithm"]);
+        assert!(matches!(result, Err(PKIError::UnsupportedOperation(_))));
+
+        let keys = generate_keyset(&["rsa"]).expect("Key set generation failed");
+        assert!(matches!(keys["rsa"], AnyKeyPair::Rsa(_)));
+    }
+}
@@ -118,7 +118,7 @@ mod tests {
 #[cfg(test)]
 #[cfg(feature = "kyber")]
 mod serialization_tests {
-    use identity::{KyberKeyPair,PKITraits,KeySerialization};
+    use identity::{KyberKeyPair,PKIError,PKITraits,KeySerialization,KEY_FORMAT_MAGIC,KEY_FORMAT_VERSION};
     use fips203::traits::SerDes;
 
     #[test]
@@ -138,4 +138,50 @@ mod serialization_tests {
         let result = KyberKeyPair::from_bytes(&invalid_bytes);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_versioned_header_round_trip() {
+        let key_pair = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let serialized = key_pair.to_bytes();
+
+        assert_eq!(&serialized[..2], &KEY_FORMAT_MAGIC);
+        assert_eq!(serialized[2], KEY_FORMAT_VERSION);
+        assert_eq!(identity::peek_algorithm(&serialized), Some("Kyber"));
+        assert_eq!(&serialized[4..], key_pair.to_raw_bytes().as_slice());
+        assert!(KyberKeyPair::from_bytes(&serialized).is_ok());
+    }
+
+    #[test]
+    fn test_untagged_version_one_still_parses() {
+        let key_pair = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let mut version_one = KEY_FORMAT_MAGIC.to_vec();
+        version_one.push(1);
+        version_one.extend_from_slice(&key_pair.to_raw_bytes());
+
+        assert_eq!(identity::peek_algorithm(&version_one), None);
+        assert!(KyberKeyPair::from_bytes(&version_one).is_ok());
+    }
+
+    #[test]
+    fn test_future_version_rejected() {
+        let key_pair = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let mut serialized = key_pair.to_bytes();
+        serialized[2] = KEY_FORMAT_VERSION + 1;
+
+        match KyberKeyPair::from_bytes(&serialized) {
+            Err(PKIError::InvalidKey(msg)) => assert_eq!(msg, "unsupported key format version"),
+            Err(other) => panic!("Expected an unsupported version error, got {}", other),
+            Ok(_) => panic!("A future key format version must be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_headerless_bytes_need_legacy_flag() {
+        let key_pair = KyberKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let legacy = key_pair.to_raw_bytes();
+
+        let result = KyberKeyPair::from_bytes(&legacy);
+        assert_eq!(result.is_ok(), cfg!(feature = "legacy_key_format"));
+        assert!(KyberKeyPair::from_raw_bytes(&legacy).is_ok());
+    }
 }
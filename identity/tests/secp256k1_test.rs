@@ -285,6 +285,22 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Invalid public key format"));
     }
+
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = SECP256K1KeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+            assert!(matches!(
+                SECP256K1KeyPair::verify_with_public_key(&public_key, b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
 }
 
 
@@ -310,4 +326,49 @@ mod serialization_test {
         let result = SECP256K1KeyPair::from_bytes(&invalid_bytes);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_der_and_raw_signature_encodings() {
+        let key_pair = SECP256K1KeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let data = b"Interop with external ECDSA tooling";
+
+        let der = key_pair.sign_der(data).expect("DER signing failed");
+        let raw = SECP256K1KeyPair::der_to_raw(&der).expect("DER to raw conversion failed");
+        assert_eq!(raw.len(), 64);
+        assert!(key_pair.verify_der(data, &der).expect("DER verification failed"));
+        assert!(key_pair.verify_raw(data, &raw).expect("Raw verification failed"));
+
+        // Conversion is lossless in both directions.
+        assert_eq!(SECP256K1KeyPair::raw_to_der(&raw).expect("Raw to DER conversion failed"), der);
+
+        let raw_signed = key_pair.sign_raw(data).expect("Raw signing failed");
+        let der_converted = SECP256K1KeyPair::raw_to_der(&raw_signed).expect("Raw to DER conversion failed");
+        assert!(key_pair.verify_der(data, &der_converted).expect("DER verification failed"));
+
+        assert!(matches!(SECP256K1KeyPair::der_to_raw(&raw), Err(identity::PKIError::MalformedSignature(_))));
+        assert!(matches!(SECP256K1KeyPair::raw_to_der(&der), Err(identity::PKIError::MalformedSignature(_))));
+    }
+
+    #[test]
+    fn test_recover_public_key_from_recoverable_signature() {
+        let key_pair = SECP256K1KeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let other = SECP256K1KeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let message_hash = [0x5Au8; 32];
+
+        let signature = key_pair.sign_recoverable(&message_hash).expect("Recoverable signing failed");
+        assert_eq!(signature.len(), 65);
+        let recovered = SECP256K1KeyPair::recover_public_key(&message_hash, &signature).expect("Recovery failed");
+        assert_eq!(recovered, key_pair.get_public_key_raw_bytes());
+        assert_ne!(recovered, other.get_public_key_raw_bytes());
+
+        // A different message recovers some other key, not the signer's.
+        let other_hash = [0xA5u8; 32];
+        if let Ok(recovered) = SECP256K1KeyPair::recover_public_key(&other_hash, &signature) {
+            assert_ne!(recovered, key_pair.get_public_key_raw_bytes());
+        }
+        assert!(matches!(
+            SECP256K1KeyPair::recover_public_key(&message_hash, &signature[..64]),
+            Err(identity::PKIError::MalformedSignature(_))
+        ));
+    }
 }
@@ -130,4 +130,14 @@ mod tests {
         assert!(is_valid, "Signature verification for large message should succeed");
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_truncated_signatures_are_malformed() {
+        let key_pair = SPHINCSKeyPair::generate_key_pair().expect("Key pair generation failed");
+        for signature in [&[][..], &[0x30][..]] {
+            assert!(matches!(
+                key_pair.verify(b"message", signature),
+                Err(identity::PKIError::MalformedSignature(_))
+            ));
+        }
+    }
+}
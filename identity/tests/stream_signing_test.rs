@@ -0,0 +1,83 @@
+#[cfg(all(feature = "stream_signing", feature = "pki_rsa"))]
+#[cfg(test)]
+mod stream_signing_tests {
+    use identity::{PKITraits, RSAkeyPair, StreamSigning};
+    use sha2::{Digest, Sha256};
+
+    fn payload() -> Vec<u8> {
+        (0..200_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn test_sign_stream_matches_prehashed_signature() {
+        let key_pair = RSAkeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let data = payload();
+        let digest = Sha256::digest(&data);
+
+        let stream_signature = key_pair.sign_stream(&data[..]).await.expect("Stream signing failed");
+        let prehashed_signature = key_pair.sign_prehashed(&digest).expect("Prehash signing failed");
+
+        // PKCS#1 v1.5 is deterministic, so both paths yield the same signature.
+        assert_eq!(stream_signature, prehashed_signature);
+        assert!(key_pair.verify_stream(&data[..], &prehashed_signature).await.expect("Stream verification failed"));
+        assert!(key_pair.verify(&data, &stream_signature).expect("Verification failed"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_stream_rejects_modified_data() {
+        let key_pair = RSAkeyPair::generate_key_pair().expect("Failed to generate key pair");
+        let mut data = payload();
+        let signature = key_pair.sign_stream(&data[..]).await.expect("Stream signing failed");
+
+        data[100_000] ^= 0xFF;
+        let result = key_pair.verify_stream(&data[..], &signature).await;
+        assert!(!matches!(result, Ok(true)));
+    }
+}
+
+#[cfg(all(feature = "stream_signing", any(feature = "falcon", feature = "dilithium")))]
+#[cfg(test)]
+mod detached_stream_signing_tests {
+    use identity::{DetachedStreamSigning, PKITraits};
+
+    /// Several megabytes, well past any single read.
+    fn large_payload() -> Vec<u8> {
+        (0..3 * 1024 * 1024u32).map(|i| (i % 253) as u8).collect()
+    }
+
+    /// Stream and one-shot signatures verify the same way on both paths.
+    async fn check_matches_one_shot<K: DetachedStreamSigning>(key_pair: &K) {
+        let data = large_payload();
+        let one_shot = key_pair.sign(&data).expect("Signing failed");
+        let streamed = key_pair.sign_stream_detached(&data[..]).await.expect("Stream signing failed");
+
+        for signature in [&one_shot, &streamed] {
+            let one_shot_result = key_pair.verify(&data, signature).expect("Verification failed");
+            let stream_result = key_pair
+                .verify_stream_detached(&data[..], signature)
+                .await
+                .expect("Stream verification failed");
+            assert!(one_shot_result);
+            assert_eq!(stream_result, one_shot_result);
+        }
+
+        let mut modified = data.clone();
+        modified[2 * 1024 * 1024] ^= 0xFF;
+        let result = key_pair.verify_stream_detached(&modified[..], &streamed).await;
+        assert!(!matches!(result, Ok(true)));
+    }
+
+    #[cfg(feature = "falcon")]
+    #[tokio::test]
+    async fn test_falcon_stream_matches_one_shot() {
+        let key_pair = identity::FalconKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        check_matches_one_shot(&key_pair).await;
+    }
+
+    #[cfg(feature = "dilithium")]
+    #[tokio::test]
+    async fn test_dilithium_stream_matches_one_shot() {
+        let key_pair = identity::DilithiumKeyPair::generate_key_pair().expect("Failed to generate key pair");
+        check_matches_one_shot(&key_pair).await;
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use identity::supported_algorithms;
+
+    #[test]
+    fn test_listed_algorithms_match_enabled_features() {
+        let algorithms = supported_algorithms();
+        let features = [
+            ("pki_rsa", cfg!(feature = "pki_rsa")),
+            ("secp256k1", cfg!(feature = "secp256k1")),
+            ("ecdsa", cfg!(feature = "ecdsa")),
+            ("ed25519", cfg!(feature = "ed25519")),
+            ("dilithium", cfg!(feature = "dilithium")),
+            ("spincs", cfg!(feature = "spincs")),
+            ("falcon", cfg!(feature = "falcon")),
+            ("kyber", cfg!(feature = "kyber")),
+        ];
+
+        for (feature, enabled) in features {
+            let listed = algorithms.iter().any(|info| info.feature == feature);
+            assert_eq!(listed, enabled, "feature {} listed = {}", feature, listed);
+        }
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "ed25519",
+        feature = "falcon",
+        not(any(
+            feature = "pki_rsa",
+            feature = "secp256k1",
+            feature = "ecdsa",
+            feature = "dilithium",
+            feature = "spincs",
+            feature = "kyber"
+        ))
+    ))]
+    fn test_only_ed25519_and_falcon() {
+        let names: Vec<_> = supported_algorithms().into_iter().map(|info| info.name).collect();
+        assert_eq!(names, ["ED25519", "Falcon"]);
+    }
+}
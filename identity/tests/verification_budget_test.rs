@@ -0,0 +1,46 @@
+#[cfg(feature = "pki_rsa")]
+#[cfg(test)]
+mod tests {
+    use identity::{PKIError, PKITraits, RSAkeyPair, VerificationBudget};
+    use std::time::Duration;
+
+    #[test]
+    fn test_budget_rejects_verification_flood() {
+        let key_pair = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        let data = b"peer announcement";
+        let signature = key_pair.sign(data).expect("Signing failed");
+
+        let budget = VerificationBudget::new(5);
+        let results: Vec<_> = (0..50)
+            .map(|_| budget.verify_with_public_key::<RSAkeyPair>(&public_key, data, &signature))
+            .collect();
+
+        let verified = results.iter().filter(|result| matches!(result, Ok(true))).count();
+        let throttled = results.iter().filter(|result| matches!(result, Err(PKIError::RateLimited(_)))).count();
+        assert_eq!(verified, 5, "only the budgeted verifications should run");
+        assert_eq!(throttled, 45, "the rest of the flood should be throttled");
+
+        // The budget refills over time.
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(budget.verify_with_public_key::<RSAkeyPair>(&public_key, data, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_budget_charges_every_candidate_key() {
+        let key_pair = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+        let public_key = key_pair.get_public_key_raw_bytes();
+        let data = b"peer announcement";
+        let signature = key_pair.sign(data).expect("Signing failed");
+        let candidates: Vec<&[u8]> = vec![public_key.as_slice(); 3];
+
+        let budget = VerificationBudget::new(4);
+        assert_eq!(budget.verify_any::<RSAkeyPair>(data, &signature, &candidates).unwrap(), Some(0));
+        // One verification is left, which cannot cover three candidates.
+        assert!(matches!(
+            budget.verify_any::<RSAkeyPair>(data, &signature, &candidates),
+            Err(PKIError::RateLimited(_))
+        ));
+        assert!(budget.try_acquire().is_ok());
+    }
+}
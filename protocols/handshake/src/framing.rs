@@ -0,0 +1,78 @@
+// protocols/handshake/src/framing.rs
+use crate::traits::HandshakeStream;
+use crate::handshake_error::HandshakeError;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Default upper bound for a single length-prefixed handshake message (64 KiB).
+pub const DEFAULT_MAX_HANDSHAKE_MESSAGE: usize = 64 * 1024;
+
+/// Error reading or writing a frame.
+#[derive(Debug, thiserror::Error)]
+pub enum FrameError {
+    #[error("frame of {len} bytes exceeds the limit of {max} bytes")]
+    TooLarge { len: usize, max: usize },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<FrameError> for HandshakeError {
+    fn from(err: FrameError) -> Self {
+        match err {
+            FrameError::TooLarge { .. } => HandshakeError::Generic("message too large".to_string()),
+            FrameError::Io(err) => HandshakeError::IoError(err),
+        }
+    }
+}
+
+/// Write `payload` as one frame: its length as a 4-byte big-endian prefix, then the
+/// bytes. This is the framing used by both the handshake steps and the record layer.
+pub async fn write_frame<S>(stream: &mut S, payload: &[u8]) -> Result<(), FrameError>
+where
+    S: AsyncWrite + Unpin + ?Sized,
+{
+    let len = u32::try_from(payload.len()).map_err(|_| FrameError::TooLarge {
+        len: payload.len(),
+        max: u32::MAX as usize,
+    })?;
+    stream.write_all(&len.to_be_bytes()).await?;
+    stream.write_all(payload).await?;
+    Ok(())
+}
+
+/// Read one frame written by `write_frame`, waiting for all of it even if it arrives
+/// in pieces.
+///
+/// The declared length is checked against `max_len` before any buffer is
+/// allocated, so a peer cannot force a large allocation by lying about it.
+pub async fn read_frame<S>(stream: &mut S, max_len: usize) -> Result<Vec<u8>, FrameError>
+where
+    S: AsyncRead + Unpin + ?Sized,
+{
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+    if len > max_len {
+        return Err(FrameError::TooLarge { len, max: max_len });
+    }
+
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+/// `write_frame` for handshake streams, reporting errors as `HandshakeError`.
+pub async fn write_length_prefixed(
+    stream: &mut dyn HandshakeStream,
+    payload: &[u8],
+) -> Result<(), HandshakeError> {
+    Ok(write_frame(stream, payload).await?)
+}
+
+/// `read_frame` for handshake streams, reporting errors as `HandshakeError`.
+pub async fn read_length_prefixed(
+    stream: &mut dyn HandshakeStream,
+    max_len: usize,
+) -> Result<Vec<u8>, HandshakeError> {
+    Ok(read_frame(stream, max_len).await?)
+}
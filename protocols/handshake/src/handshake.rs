@@ -1,10 +1,14 @@
 use crate::traits::{HandshakeStep, HandshakeStream};
 use crate::handshake_error::HandshakeError;
+use crate::cancellation::CancellationToken;
+use crate::framing::DEFAULT_MAX_HANDSHAKE_MESSAGE;
 use std::collections::VecDeque;
+use std::time::Duration;
 
 pub struct Handshake {
     protocol_id: String,
     steps: VecDeque<Box<dyn HandshakeStep>>,
+    max_handshake_message: usize,
 }
 
 impl Handshake {
@@ -13,9 +17,20 @@ impl Handshake {
         Self {
             protocol_id: protocol_id.to_string(),
             steps: VecDeque::new(),
+            max_handshake_message: DEFAULT_MAX_HANDSHAKE_MESSAGE,
         }
     }
 
+    /// Set the maximum size of a single length-prefixed handshake message.
+    pub fn set_max_handshake_message(&mut self, max_handshake_message: usize) {
+        self.max_handshake_message = max_handshake_message;
+    }
+
+    /// Get the maximum size of a single length-prefixed handshake message.
+    pub fn max_handshake_message(&self) -> usize {
+        self.max_handshake_message
+    }
+
     /// Get the protocol ID
     pub fn protocol_id(&self) -> &str {
         &self.protocol_id
@@ -75,10 +90,53 @@ impl Handshake {
     pub async fn execute(
         &mut self,
         stream: &mut dyn HandshakeStream,
+    ) -> Result<Vec<u8>, HandshakeError> {
+        let mut current_step = 0;
+        self.execute_tracked(stream, &mut current_step).await
+    }
+
+    /// Execute the handshake, failing with `HandshakeError::Timeout` if the whole
+    /// sequence does not finish within `total`. The error carries the index of the
+    /// step that was running when the deadline expired.
+    pub async fn run_with_deadline(
+        &mut self,
+        stream: &mut dyn HandshakeStream,
+        total: Duration,
+    ) -> Result<Vec<u8>, HandshakeError> {
+        let mut current_step = 0;
+        match tokio::time::timeout(total, self.execute_tracked(stream, &mut current_step)).await {
+            Ok(result) => result,
+            Err(_) => Err(HandshakeError::Timeout { step: current_step }),
+        }
+    }
+
+    /// Execute the handshake, failing with `HandshakeError::Cancelled` as soon as
+    /// `token` is cancelled, including while a step is waiting on the peer. The
+    /// running step's future is dropped, so the stream should be discarded afterwards.
+    pub async fn run_with_cancellation(
+        &mut self,
+        stream: &mut dyn HandshakeStream,
+        token: &CancellationToken,
+    ) -> Result<Vec<u8>, HandshakeError> {
+        let mut current_step = 0;
+        let result = tokio::select! {
+            biased;
+            _ = token.cancelled() => None,
+            result = self.execute_tracked(stream, &mut current_step) => Some(result),
+        };
+        result.unwrap_or(Err(HandshakeError::Cancelled { step: current_step }))
+    }
+
+    async fn execute_tracked(
+        &mut self,
+        stream: &mut dyn HandshakeStream,
+        current_step: &mut usize,
     ) -> Result<Vec<u8>, HandshakeError> {
         let mut input = Vec::new();
-        for step in &mut self.steps {
+        for (index, step) in self.steps.iter_mut().enumerate() {
+            *current_step = index;
             if step.supports_protocol(&self.protocol_id) {
+                step.set_max_message_size(self.max_handshake_message);
                 // Each step returns a new Vec<u8>
                 input = step.execute(stream, input).await?;
             } else {
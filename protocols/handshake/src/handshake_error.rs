@@ -27,4 +27,10 @@ pub enum HandshakeError {
 
     #[error("Negotiation failed: {0}")]
     NegotiationError(String),
+
+    #[error("Handshake deadline exceeded during step {step}")]
+    Timeout { step: usize },
+
+    #[error("Handshake cancelled during step {step}")]
+    Cancelled { step: usize },
 }
@@ -3,8 +3,12 @@ mod handshake;
 mod handshake_error;
 mod traits;
 mod steps;
+mod framing;
+mod cancellation;
 
 pub use handshake::Handshake;
+pub use cancellation::CancellationToken;
 pub use handshake_error::HandshakeError;
 pub use traits::{HandshakeStep,HandshakeStream};
-pub use steps::{CipherSuiteAck,CipherSuiteExchange,NodeHello,HelloResponse,CustomProtocolStep};
\ No newline at end of file
+pub use steps::{CipherSuiteAck,CipherSuiteExchange,NodeHello,HelloResponse,CustomProtocolStep};
+pub use framing::{read_frame, write_frame, FrameError, read_length_prefixed, write_length_prefixed, DEFAULT_MAX_HANDSHAKE_MESSAGE};
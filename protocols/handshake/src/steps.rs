@@ -1,6 +1,6 @@
 use crate::traits::{HandshakeStep, HandshakeStream};
 use crate::handshake_error::HandshakeError;
-use tokio::io::{AsyncWriteExt, AsyncReadExt};
+use crate::framing::{read_frame, write_frame, DEFAULT_MAX_HANDSHAKE_MESSAGE};
 use futures::future::BoxFuture;
 
 /// Node Hello step
@@ -30,10 +30,8 @@ impl HandshakeStep for NodeHello {
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
             // Send "HELLO"
-            stream
-                .write_all(b"HELLO")
-                .await
-                .map_err(|e| HandshakeError::Generic(e.to_string()))?;
+            write_frame(stream, b"HELLO")
+                .await?;
             println!("N1 -> N2: Sending HELLO");
 
             // Return an empty Vec<u8> or some data
@@ -45,11 +43,12 @@ impl HandshakeStep for NodeHello {
 /// Hello Response step
 pub struct HelloResponse {
     protocol_id: Option<String>,
+    max_message_size: usize,
 }
 
 impl HelloResponse {
     pub fn new() -> Self {
-        Self { protocol_id: None }
+        Self { protocol_id: None, max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE }
     }
 }
 
@@ -62,18 +61,19 @@ impl HandshakeStep for HelloResponse {
         self.protocol_id = Some(protocol_id.to_string());
     }
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
         _input: Vec<u8>,
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
-            let mut buffer = [0u8; 5];
-            stream
-                .read_exact(&mut buffer)
-                .await
-                .map_err(|e| HandshakeError::Generic(e.to_string()))?;
-            if &buffer == b"HELLO" {
+            let buffer = read_frame(stream, self.max_message_size)
+                .await?;
+            if buffer == b"HELLO" {
                 println!("N1 <- N2: Receiving HELLO");
                 Ok(vec![])
             } else {
@@ -112,10 +112,8 @@ impl HandshakeStep for CipherSuiteExchange {
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
             // Send "CIPHERSUITES"
-            stream
-                .write_all(b"CIPHERSUITES")
-                .await
-                .map_err(|e| HandshakeError::NegotiationFailed(e.to_string()))?;
+            write_frame(stream, b"CIPHERSUITES")
+                .await?;
             println!("N1 -> N2: Exchanging Cipher Suites");
             // Return some data so that next step sees it
             Ok(b"CIPHER_ACK".to_vec())
@@ -126,11 +124,12 @@ impl HandshakeStep for CipherSuiteExchange {
 /// Cipher Suite Acknowledgment step
 pub struct CipherSuiteAck {
     protocol_id: Option<String>,
+    max_message_size: usize,
 }
 
 impl CipherSuiteAck {
     pub fn new() -> Self {
-        Self { protocol_id: None }
+        Self { protocol_id: None, max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE }
     }
 }
 
@@ -143,6 +142,10 @@ impl HandshakeStep for CipherSuiteAck {
         self.protocol_id = Some(protocol_id.to_string());
     }
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
@@ -150,12 +153,9 @@ impl HandshakeStep for CipherSuiteAck {
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
             // Read a response
-            let mut buffer = vec![0; 1024];
-            let n = stream
-                .read(&mut buffer)
-                .await
-                .map_err(|e| HandshakeError::NegotiationFailed(e.to_string()))?;
-            let received = std::str::from_utf8(&buffer[..n])
+            let buffer = read_frame(stream, self.max_message_size)
+                .await?;
+            let received = std::str::from_utf8(&buffer)
                 .map_err(|e| HandshakeError::Generic(e.to_string()))?;
             println!("N1 <- N2: Acknowledging Cipher Suites: {}", received);
 
@@ -191,10 +191,8 @@ impl HandshakeStep for CustomProtocolStep {
         _input: Vec<u8>,
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
-            stream
-                .write_all(b"CUSTOM_STEP")
-                .await
-                .map_err(|e| HandshakeError::Generic(e.to_string()))?;
+            write_frame(stream, b"CUSTOM_STEP")
+                .await?;
             println!("CustomProtocolStep executed.");
             // Return empty or some data
             Ok(vec![])
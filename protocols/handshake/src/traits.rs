@@ -2,6 +2,10 @@ use tokio::io::{AsyncRead, AsyncWrite};
 use futures::future::BoxFuture;
 use crate::handshake_error::HandshakeError;
 
+/// One stage of a `Handshake`.
+///
+/// Steps are `Send + Sync` so a boxed handshake can run inside `tokio::spawn` and a
+/// step can be shared as an `Arc<dyn HandshakeStep>` between tasks.
 pub trait HandshakeStep: Send + Sync {
     /// Get the protocol ID of the step
     fn get_protocol_id(&self) -> &str;
@@ -14,7 +18,17 @@ pub trait HandshakeStep: Send + Sync {
         self.get_protocol_id() == protocol_id
     }
 
-    /// Execute the step
+    /// Set the maximum size accepted for a single length-prefixed message.
+    /// Steps that read framed messages should honour this limit; the default
+    /// implementation ignores it.
+    fn set_max_message_size(&mut self, _max_message_size: usize) {}
+
+    /// Execute the step.
+    ///
+    /// `input` is exactly what the previous step returned (empty for the first step),
+    /// and the returned bytes become the next step's `input`. A step that does not
+    /// consume input should reject a non-empty one rather than silently dropping it,
+    /// and should return an empty `Vec` unless it has something for the next step.
     fn execute<'a>(
         &'a mut self, 
         stream: &'a mut dyn HandshakeStream,
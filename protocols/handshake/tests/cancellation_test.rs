@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod cancellation_testing {
+    use futures::future::BoxFuture;
+    use handshake::{
+        read_length_prefixed, write_length_prefixed, Handshake, HandshakeError, HandshakeStep,
+        HandshakeStream, CancellationToken, DEFAULT_MAX_HANDSHAKE_MESSAGE,
+    };
+    use std::time::{Duration, Instant};
+
+    /// Step that sends a hello frame and returns immediately.
+    struct HelloStep {
+        protocol_id: Option<String>,
+    }
+
+    impl HandshakeStep for HelloStep {
+        fn get_protocol_id(&self) -> &str {
+            self.protocol_id.as_deref().unwrap_or("")
+        }
+
+        fn set_protocol_id(&mut self, protocol_id: &str) {
+            self.protocol_id = Some(protocol_id.to_string());
+        }
+
+        fn execute<'a>(
+            &'a mut self,
+            stream: &'a mut dyn HandshakeStream,
+            _input: Vec<u8>,
+        ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
+            Box::pin(async move {
+                write_length_prefixed(stream, b"HELLO").await?;
+                Ok(Vec::new())
+            })
+        }
+    }
+
+    /// Step that waits for the responder's KEM ciphertext.
+    struct KemStep {
+        protocol_id: Option<String>,
+    }
+
+    impl HandshakeStep for KemStep {
+        fn get_protocol_id(&self) -> &str {
+            self.protocol_id.as_deref().unwrap_or("")
+        }
+
+        fn set_protocol_id(&mut self, protocol_id: &str) {
+            self.protocol_id = Some(protocol_id.to_string());
+        }
+
+        fn execute<'a>(
+            &'a mut self,
+            stream: &'a mut dyn HandshakeStream,
+            _input: Vec<u8>,
+        ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
+            Box::pin(async move { read_length_prefixed(stream, DEFAULT_MAX_HANDSHAKE_MESSAGE).await })
+        }
+    }
+
+    fn build_handshake() -> Handshake {
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.add_step(Box::new(HelloStep { protocol_id: None }));
+        handshake.add_step(Box::new(KemStep { protocol_id: None }));
+        handshake
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_kem_step() {
+        let (mut client, _responder) = tokio::io::duplex(1024);
+        let token = CancellationToken::new();
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        // The responder never answers, so only the token can end the KEM step.
+        let started = Instant::now();
+        let mut handshake = build_handshake();
+        let result = handshake.run_with_cancellation(&mut client, &token).await;
+
+        match result {
+            Err(HandshakeError::Cancelled { step }) => assert_eq!(step, 1),
+            other => panic!("Expected cancellation in KEM step, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_already_cancelled_token_runs_no_steps() {
+        let (mut client, mut responder) = tokio::io::duplex(1024);
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let mut handshake = build_handshake();
+        let result = handshake.run_with_cancellation(&mut client, &token).await;
+        assert!(matches!(result, Err(HandshakeError::Cancelled { step: 0 })));
+
+        // The hello step never ran, so nothing was written.
+        drop(client);
+        let mut sent = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut responder, &mut sent).await.unwrap();
+        assert!(sent.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod deadline_testing {
+    use futures::future::BoxFuture;
+    use handshake::{
+        read_length_prefixed, write_length_prefixed, Handshake, HandshakeError, HandshakeStep,
+        HandshakeStream, DEFAULT_MAX_HANDSHAKE_MESSAGE,
+    };
+    use std::time::Duration;
+
+    /// Step that sends a hello frame and returns immediately.
+    struct HelloStep {
+        protocol_id: Option<String>,
+    }
+
+    impl HandshakeStep for HelloStep {
+        fn get_protocol_id(&self) -> &str {
+            self.protocol_id.as_deref().unwrap_or("")
+        }
+
+        fn set_protocol_id(&mut self, protocol_id: &str) {
+            self.protocol_id = Some(protocol_id.to_string());
+        }
+
+        fn execute<'a>(
+            &'a mut self,
+            stream: &'a mut dyn HandshakeStream,
+            _input: Vec<u8>,
+        ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
+            Box::pin(async move {
+                write_length_prefixed(stream, b"HELLO").await?;
+                Ok(Vec::new())
+            })
+        }
+    }
+
+    /// Step that waits for the responder's KEM ciphertext.
+    struct KemStep {
+        protocol_id: Option<String>,
+    }
+
+    impl HandshakeStep for KemStep {
+        fn get_protocol_id(&self) -> &str {
+            self.protocol_id.as_deref().unwrap_or("")
+        }
+
+        fn set_protocol_id(&mut self, protocol_id: &str) {
+            self.protocol_id = Some(protocol_id.to_string());
+        }
+
+        fn execute<'a>(
+            &'a mut self,
+            stream: &'a mut dyn HandshakeStream,
+            _input: Vec<u8>,
+        ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
+            Box::pin(async move { read_length_prefixed(stream, DEFAULT_MAX_HANDSHAKE_MESSAGE).await })
+        }
+    }
+
+    fn build_handshake() -> Handshake {
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.add_step(Box::new(HelloStep { protocol_id: None }));
+        handshake.add_step(Box::new(KemStep { protocol_id: None }));
+        handshake
+    }
+
+    #[tokio::test]
+    async fn test_deadline_reports_stalled_kem_step() {
+        let (mut client, _responder) = tokio::io::duplex(1024);
+
+        // The responder never answers, so the KEM step (index 1) stalls.
+        let mut handshake = build_handshake();
+        let result = handshake
+            .run_with_deadline(&mut client, Duration::from_millis(100))
+            .await;
+
+        match result {
+            Err(HandshakeError::Timeout { step }) => assert_eq!(step, 1),
+            other => panic!("Expected timeout in KEM step, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deadline_not_hit_when_responder_answers() {
+        let (mut client, mut responder) = tokio::io::duplex(1024);
+        write_length_prefixed(&mut responder, b"CIPHERTEXT").await.unwrap();
+
+        let mut handshake = build_handshake();
+        let result = handshake
+            .run_with_deadline(&mut client, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(result, b"CIPHERTEXT");
+    }
+}
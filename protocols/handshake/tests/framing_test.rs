@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod framing_testing {
+    use futures::future::BoxFuture;
+    use handshake::{
+        read_frame, read_length_prefixed, write_frame, write_length_prefixed, FrameError,
+        Handshake, HandshakeError, HandshakeStep, HelloResponse, HandshakeStream, DEFAULT_MAX_HANDSHAKE_MESSAGE,
+    };
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+
+    /// Step that reads one length-prefixed message using the configured limit.
+    struct FramedReadStep {
+        protocol_id: Option<String>,
+        max_message_size: usize,
+    }
+
+    impl HandshakeStep for FramedReadStep {
+        fn get_protocol_id(&self) -> &str {
+            self.protocol_id.as_deref().unwrap_or("")
+        }
+
+        fn set_protocol_id(&mut self, protocol_id: &str) {
+            self.protocol_id = Some(protocol_id.to_string());
+        }
+
+        fn set_max_message_size(&mut self, max_message_size: usize) {
+            self.max_message_size = max_message_size;
+        }
+
+        fn execute<'a>(
+            &'a mut self,
+            stream: &'a mut dyn HandshakeStream,
+            _input: Vec<u8>,
+        ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
+            Box::pin(async move { read_length_prefixed(stream, self.max_message_size).await })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_length_prefixed_round_trip() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        write_length_prefixed(&mut client, b"CIPHERSUITES").await.unwrap();
+
+        let payload = read_length_prefixed(&mut server, DEFAULT_MAX_HANDSHAKE_MESSAGE)
+            .await
+            .unwrap();
+        assert_eq!(payload, b"CIPHERSUITES");
+    }
+
+    #[tokio::test]
+    async fn test_huge_length_prefix_aborts_handshake() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        // Declare a ~4 GB message but never send the body.
+        client.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.add_step(Box::new(FramedReadStep {
+            protocol_id: None,
+            max_message_size: usize::MAX,
+        }));
+        assert_eq!(handshake.max_handshake_message(), DEFAULT_MAX_HANDSHAKE_MESSAGE);
+
+        let result = handshake.execute(&mut server).await;
+        match result {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "message too large"),
+            other => panic!("Expected message too large error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_built_in_step_reports_message_too_large() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        client.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.add_step(Box::new(HelloResponse::new()));
+        match handshake.execute(&mut server).await {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "message too large"),
+            other => panic!("Expected message too large error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_limit_is_enforced() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        write_length_prefixed(&mut client, &[0u8; 32]).await.unwrap();
+
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.set_max_handshake_message(16);
+        handshake.add_step(Box::new(FramedReadStep {
+            protocol_id: None,
+            max_message_size: usize::MAX,
+        }));
+
+        assert!(handshake.execute(&mut server).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_frame_survives_partial_reads() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        let mut encoded = Vec::new();
+        write_frame(&mut encoded, b"HANDSHAKE_DONE").await.unwrap();
+
+        let writer = tokio::spawn(async move {
+            for chunk in encoded.chunks(3) {
+                client.write_all(chunk).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+
+        let payload = read_frame(&mut server, DEFAULT_MAX_HANDSHAKE_MESSAGE).await.unwrap();
+        assert_eq!(payload, b"HANDSHAKE_DONE");
+        writer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_length_frame() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        write_frame(&mut client, b"").await.unwrap();
+        write_frame(&mut client, b"OK").await.unwrap();
+
+        assert!(read_frame(&mut server, 16).await.unwrap().is_empty());
+        assert_eq!(read_frame(&mut server, 16).await.unwrap(), b"OK");
+    }
+
+    #[tokio::test]
+    async fn test_frame_over_limit_is_rejected() {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        write_frame(&mut client, &[0u8; 32]).await.unwrap();
+
+        match read_frame(&mut server, 16).await {
+            Err(FrameError::TooLarge { len, max }) => assert_eq!((len, max), (32, 16)),
+            other => panic!("Expected FrameError::TooLarge, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use handshake::{Handshake, HandshakeStep, NodeHello};
+    use std::sync::Arc;
+
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_handshake_step_trait_object_moves_into_task() {
+        assert_send_sync::<dyn HandshakeStep>();
+
+        let step: Arc<dyn HandshakeStep + Send + Sync> = Arc::new(NodeHello::new());
+        let shared = Arc::clone(&step);
+        let protocol = tokio::spawn(async move { shared.get_protocol_id().to_string() })
+            .await
+            .unwrap();
+        assert_eq!(protocol, step.get_protocol_id());
+
+        let mut handshake = Handshake::new("protocol_a");
+        handshake.add_step(Box::new(NodeHello::new()));
+        assert_send(&handshake);
+    }
+}
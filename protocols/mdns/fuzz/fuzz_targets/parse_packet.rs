@@ -0,0 +1,16 @@
+// protocols\mdns\fuzz\fuzz_targets\parse_packet.rs
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mdns::DnsPacket;
+
+// Seeds live in fuzz/corpus/parse_packet (advertise, query and TXT/AAAA packets
+// produced by `DnsPacket::serialize`).
+fuzz_target!(|data: &[u8]| {
+    let _ = DnsPacket::parse(data);
+    if let Ok(packet) = DnsPacket::parse_strict(data) {
+        // Whatever parses strictly must survive a serialize/parse round trip.
+        let reparsed = DnsPacket::parse_strict(&packet.serialize()).expect("re-parse of serialized packet failed");
+        assert_eq!(reparsed.serialize(), packet.serialize());
+    }
+});
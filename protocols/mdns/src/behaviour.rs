@@ -5,6 +5,17 @@
 mod mdns_event;
 mod mdns_error;
 mod mdns_service;
+mod mdns_reflector;
+mod mdns_metrics;
+mod mdns_status;
+mod mdns_address_provider;
+mod mdns_hostname;
+mod mdns_backoff;
+mod mdns_interface_policy;
+mod mdns_signature;
+mod mdns_source_policy;
+mod mdns_clock;
+mod mdns_browse;
 mod records;
 
 // =================================================
@@ -12,6 +23,17 @@ mod records;
 // Public Exports
 pub use mdns_event::MdnsEvent;
 pub use mdns_error::MdnsError;
-pub use mdns_service::MdnsService;
-pub use records::{MdnsRegistry, ServiceRecord, NodeRecord};
+pub use mdns_service::{MdnsService, DEFAULT_NEGATIVE_CACHE_TTL, DEFAULT_REGISTRY_PRINT_INTERVAL};
+pub use mdns_reflector::{MdnsReflector, PacketSink, ReflectorSide};
+pub use mdns_metrics::{MdnsMetrics, MdnsMetricsSnapshot};
+pub use mdns_status::MdnsStatus;
+pub use mdns_backoff::BackoffState;
+pub use mdns_address_provider::{AddressProvider, StaticAddressProvider, SystemAddressProvider};
+pub use mdns_hostname::{default_origin, local_hostname};
+pub use mdns_interface_policy::InterfacePolicy;
+pub use mdns_signature::{ServiceSignature, SignatureVerifier, DEFAULT_VERIFICATIONS_PER_SECOND};
+pub use mdns_source_policy::{IpNet, SourcePolicy};
+pub use mdns_clock::{Clock, MockClock, SystemClock};
+pub use mdns_browse::{ServiceEvent, ServiceStream};
+pub use records::{MdnsRegistry, DEFAULT_MAX_NODES, ServiceRecord, ServiceRecordBuilder, NodeRecord};
 // =================================================
\ No newline at end of file
@@ -0,0 +1,131 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Source of the local addresses this node advertises in its A records.
+pub trait AddressProvider: Send + Sync {
+    /// Returns the addresses assigned to this host.
+    fn local_addresses(&self) -> Vec<IpAddr>;
+}
+
+/// Default provider that enumerates the host's network interfaces.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemAddressProvider;
+
+impl AddressProvider for SystemAddressProvider {
+    fn local_addresses(&self) -> Vec<IpAddr> {
+        interface_addresses()
+    }
+}
+
+/// Provider returning a fixed list of addresses (tests, containers, pinned setups).
+#[derive(Debug, Clone, Default)]
+pub struct StaticAddressProvider {
+    addresses: Vec<IpAddr>,
+}
+
+impl StaticAddressProvider {
+    pub fn new(addresses: Vec<IpAddr>) -> Self {
+        Self { addresses }
+    }
+}
+
+impl AddressProvider for StaticAddressProvider {
+    fn local_addresses(&self) -> Vec<IpAddr> {
+        self.addresses.clone()
+    }
+}
+
+/// Picks the IPv4 address to advertise: the first non-loopback one, falling back to
+/// loopback if that is all the host has.
+pub(crate) fn preferred_ipv4(addresses: &[IpAddr]) -> Option<Ipv4Addr> {
+    let ipv4: Vec<Ipv4Addr> = addresses
+        .iter()
+        .filter_map(|addr| match addr {
+            IpAddr::V4(ip) if !ip.is_unspecified() => Some(*ip),
+            _ => None,
+        })
+        .collect();
+
+    ipv4.iter()
+        .find(|ip| !ip.is_loopback())
+        .or_else(|| ipv4.first())
+        .copied()
+}
+
+#[cfg(unix)]
+fn interface_addresses() -> Vec<IpAddr> {
+    use std::net::Ipv6Addr;
+
+    let mut addresses = Vec::new();
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+
+    // SAFETY: getifaddrs fills `ifap` with a linked list we own until freeifaddrs.
+    if unsafe { libc::getifaddrs(&mut ifap) } != 0 {
+        return addresses;
+    }
+
+    let mut cursor = ifap;
+    while !cursor.is_null() {
+        // SAFETY: `cursor` is a non-null node of the list returned above, and each
+        // sockaddr is read as the concrete type its family says it is.
+        unsafe {
+            let ifa = &*cursor;
+            if !ifa.ifa_addr.is_null() {
+                match i32::from((*ifa.ifa_addr).sa_family) {
+                    libc::AF_INET => {
+                        let sin = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                        addresses.push(IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr))));
+                    }
+                    libc::AF_INET6 => {
+                        let sin6 = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                        addresses.push(IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr)));
+                    }
+                    _ => {}
+                }
+            }
+            cursor = ifa.ifa_next;
+        }
+    }
+
+    // SAFETY: `ifap` came from a successful getifaddrs call and is freed once.
+    unsafe { libc::freeifaddrs(ifap) };
+    addresses
+}
+
+#[cfg(not(unix))]
+fn interface_addresses() -> Vec<IpAddr> {
+    default_route_ipv4().map(|ip| vec![IpAddr::V4(ip)]).unwrap_or_default()
+}
+
+/// Asks the routing table which local address reaches the mDNS group. Connecting a
+/// UDP socket sends nothing, so this also works without internet access.
+pub(crate) fn default_route_ipv4() -> Option<Ipv4Addr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("224.0.0.251:5353").ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferred_ipv4_skips_loopback() {
+        let addresses = vec![
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            "fe80::1".parse().unwrap(),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+        ];
+        assert_eq!(preferred_ipv4(&addresses), Some(Ipv4Addr::new(192, 168, 1, 20)));
+        assert_eq!(preferred_ipv4(&[IpAddr::V4(Ipv4Addr::LOCALHOST)]), Some(Ipv4Addr::LOCALHOST));
+        assert_eq!(preferred_ipv4(&[]), None);
+    }
+
+    #[test]
+    fn test_system_provider_lists_loopback() {
+        let addresses = SystemAddressProvider.local_addresses();
+        assert!(addresses.iter().any(|addr| addr.is_loopback()));
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+/// Default interval between periodic queries while the network is changing.
+pub(crate) const DEFAULT_QUERY_INTERVAL: Duration = Duration::from_secs(10);
+/// Longest interval the backoff may reach (RFC 6762 §5.2 caps it at one hour).
+pub(crate) const MAX_QUERY_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How aggressively the service re-queries the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackoffState {
+    /// Something new was seen recently; query at the base interval.
+    Active,
+    /// Nothing new was seen; each interval doubles up to the maximum.
+    Backoff,
+}
+
+/// Tracks the periodic query interval. Automatic transitions are skipped while the
+/// state is pinned by an operator override.
+#[derive(Debug)]
+pub(crate) struct QueryBackoff {
+    state: BackoffState,
+    pinned: bool,
+    base: Duration,
+    current: Duration,
+}
+
+impl QueryBackoff {
+    pub(crate) fn new(base: Duration) -> Self {
+        Self {
+            state: BackoffState::Active,
+            pinned: false,
+            base,
+            current: base,
+        }
+    }
+
+    pub(crate) fn state(&self) -> BackoffState {
+        self.state
+    }
+
+    pub(crate) fn set_base_interval(&mut self, base: Duration) {
+        self.base = base;
+        self.current = base;
+    }
+
+    /// Forces `state` and disables automatic transitions until `unpin`.
+    pub(crate) fn pin(&mut self, state: BackoffState) {
+        self.state = state;
+        self.pinned = true;
+        self.current = self.base;
+    }
+
+    pub(crate) fn unpin(&mut self) {
+        self.pinned = false;
+    }
+
+    /// Moves to `Active` when new records were seen since the last query, otherwise
+    /// to `Backoff`. Does nothing while pinned.
+    pub(crate) fn adjust(&mut self, saw_new_records: bool) {
+        if self.pinned {
+            return;
+        }
+        if saw_new_records {
+            self.state = BackoffState::Active;
+            self.current = self.base;
+        } else {
+            self.state = BackoffState::Backoff;
+        }
+    }
+
+    /// Returns the delay before the next query, advancing the backoff.
+    pub(crate) fn next_interval(&mut self) -> Duration {
+        match self.state {
+            BackoffState::Active => {
+                self.current = self.base;
+                self.base
+            }
+            BackoffState::Backoff => {
+                self.current = (self.current * 2).min(MAX_QUERY_INTERVAL.max(self.base));
+                self.current
+            }
+        }
+    }
+}
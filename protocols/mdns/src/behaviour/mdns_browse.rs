@@ -0,0 +1,116 @@
+// protocols\mdns\src\behaviour\mdns_browse.rs
+use crate::behaviour::mdns_service::normalize_service_type;
+use crate::behaviour::records::ServiceRecord;
+use crate::{MdnsEvent, MdnsService};
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{self, Instant};
+
+/// A change to the set of known services of one type, yielded by `ServiceStream`.
+#[derive(Debug, Clone)]
+pub enum ServiceEvent {
+    /// A service of the browsed type appeared.
+    Added(ServiceRecord),
+    /// A known service changed (port, TTL, TXT data, ...).
+    Updated(ServiceRecord),
+    /// A known service expired or was removed from the registry.
+    Removed(ServiceRecord),
+}
+
+/// A standing browse for one service type, created by
+/// `MdnsService::subscribe_service_type`.
+///
+/// While `next` is being awaited the stream re-sends its PTR query at the service's
+/// backoff interval and compares the registry with what it has already reported.
+/// Services present when the stream is created are reported as `Added` first.
+/// Discovered records only reach the registry while `run` (or `listen`) is active.
+pub struct ServiceStream<'a> {
+    service: &'a MdnsService,
+    service_type: String,
+    events: broadcast::Receiver<MdnsEvent>,
+    known: HashMap<String, (ServiceRecord, Vec<u8>)>,
+    pending: VecDeque<ServiceEvent>,
+    next_query: Instant,
+}
+
+impl<'a> ServiceStream<'a> {
+    pub(crate) fn new(service: &'a MdnsService, service_type: &str) -> Self {
+        Self {
+            service,
+            service_type: service_type.to_string(),
+            events: service.get_event_receiver(),
+            known: HashMap::new(),
+            pending: VecDeque::new(),
+            next_query: Instant::now(),
+        }
+    }
+
+    /// Returns the service type being browsed.
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    /// Waits for the next change. Returns `None` once the service's event channel
+    /// has closed.
+    pub async fn next(&mut self) -> Option<ServiceEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            self.refresh().await;
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+
+            tokio::select! {
+                _ = time::sleep_until(self.next_query) => {
+                    if let Err(err) = self.service.send_query(&self.service_type).await {
+                        eprintln!("(BROWSE) Failed to query {}: {:?}", self.service_type, err);
+                    }
+                    self.next_query = Instant::now() + self.service.next_query_interval();
+                }
+                received = self.events.recv() => match received {
+                    // Any event may have changed the registry; a lagging receiver
+                    // missed some, which the refresh catches up on.
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return None,
+                },
+            }
+        }
+    }
+
+    /// Compares the registry's services of the browsed type with those already
+    /// reported and queues the differences.
+    async fn refresh(&mut self) {
+        let wanted = normalize_service_type(&self.service_type);
+        let current: HashMap<String, (ServiceRecord, Vec<u8>)> = self
+            .service
+            .registry
+            .list_services()
+            .await
+            .into_iter()
+            .filter(|service| normalize_service_type(&service.service_type) == wanted)
+            .map(|service| {
+                let fingerprint = service.canonical_bytes();
+                (service.id.clone(), (service, fingerprint))
+            })
+            .collect();
+
+        let previous = std::mem::take(&mut self.known);
+        for (id, (service, _)) in &previous {
+            if !current.contains_key(id) {
+                self.pending.push_back(ServiceEvent::Removed(service.clone()));
+            }
+        }
+        for (id, (service, fingerprint)) in &current {
+            match previous.get(id) {
+                None => self.pending.push_back(ServiceEvent::Added(service.clone())),
+                Some((_, known)) if known != fingerprint => {
+                    self.pending.push_back(ServiceEvent::Updated(service.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        self.known = current;
+    }
+}
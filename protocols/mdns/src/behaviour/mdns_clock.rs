@@ -0,0 +1,61 @@
+// protocols\mdns\src\behaviour\mdns_clock.rs
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Time source for the service's time-based decisions, such as how long an empty
+/// `discover` result is reused.
+pub trait Clock: Send + Sync {
+    /// Milliseconds since the Unix epoch.
+    fn now_ms(&self) -> u64;
+}
+
+/// Default clock: the system time read once, then advanced by a monotonic `Instant`.
+///
+/// Later NTP steps or a VM resume never move it backwards, so TTL arithmetic only
+/// ever sees non-negative differences. The reading may drift from the wall clock by
+/// however much the system time was stepped since the first call.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        static ANCHOR: OnceLock<(u64, Instant)> = OnceLock::new();
+        let (start_ms, start) = ANCHOR.get_or_init(|| {
+            let start_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0);
+            (start_ms, Instant::now())
+        });
+        start_ms.saturating_add(start.elapsed().as_millis() as u64)
+    }
+}
+
+/// Clock that only moves when told to, so tests can step past a TTL without sleeping.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now_ms: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(start_ms: u64) -> Self {
+        Self { now_ms: AtomicU64::new(start_ms) }
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.now_ms.fetch_add(by.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Sets the current time.
+    pub fn set_ms(&self, now_ms: u64) {
+        self.now_ms.store(now_ms, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::Relaxed)
+    }
+}
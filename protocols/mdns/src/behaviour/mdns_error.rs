@@ -15,6 +15,9 @@ pub enum MdnsError {
     /// Indicates a timeout during mDNS operations.
     Timeout(String),
 
+    /// A name (e.g. the node origin) is not a valid DNS host name.
+    InvalidName(String),
+
     /// A generic error for uncategorized issues.
     Generic(String),
 }
@@ -26,6 +29,7 @@ impl fmt::Display for MdnsError {
             MdnsError::MulticastError(msg) => write!(f, "Multicast error: {}", msg),
             MdnsError::NetworkError(err) => write!(f, "Network error: {}", err),
             MdnsError::Timeout(msg) => write!(f, "Timeout: {}", msg),
+            MdnsError::InvalidName(msg) => write!(f, "Invalid name: {}", msg),
             MdnsError::Generic(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -0,0 +1,61 @@
+// protocols\mdns\src\behaviour\mdns_hostname.rs
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Returns this machine's host name as a single DNS label: the part before the first
+/// dot, lowercased, with characters other than ASCII letters, digits and `-`
+/// replaced by `-`. `None` if the name is unavailable or has nothing usable in it.
+pub fn local_hostname() -> Option<String> {
+    let raw = system_hostname()?;
+    let first = raw.trim().split('.').next()?;
+    let label: String = first
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .take(63)
+        .collect();
+    let label = label.trim_matches('-');
+    (!label.is_empty()).then(|| label.to_string())
+}
+
+/// The origin used when none is given: `<hostname>.local`, or `node-<random>.local`
+/// if the host name cannot be read, so nodes that omit an origin stay distinct.
+pub fn default_origin() -> String {
+    match local_hostname() {
+        Some(hostname) => format!("{}.local", hostname),
+        None => {
+            let suffix = RandomState::new().build_hasher().finish() as u32;
+            eprintln!("(INIT) Host name unavailable, using a random origin");
+            format!("node-{:08x}.local", suffix)
+        }
+    }
+}
+
+#[cfg(unix)]
+fn system_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: `buf` is writable for its full length, which is what we pass.
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&byte| byte == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+fn system_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_origin_is_a_valid_local_name() {
+        let origin = default_origin();
+        let label = origin.strip_suffix(".local").expect("origin ends with .local");
+        assert!(!label.is_empty() && label.len() <= 63);
+        assert!(label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        assert!(!label.starts_with('-') && !label.ends_with('-'));
+    }
+}
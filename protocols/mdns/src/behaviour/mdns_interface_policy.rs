@@ -0,0 +1,94 @@
+use crate::behaviour::mdns_address_provider::preferred_ipv4;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Chooses which local IPv4 interfaces join the mDNS group and which addresses are
+/// advertised in A records on a multi-homed host.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum InterfacePolicy {
+    /// Every non-loopback IPv4 interface (loopback only if nothing else exists).
+    All,
+    /// Only the interface the routing table uses for the mDNS group.
+    #[default]
+    DefaultRoute,
+    /// Only the listed interface addresses that are actually present.
+    Only(Vec<Ipv4Addr>),
+}
+
+impl InterfacePolicy {
+    /// Policy equivalent to binding a single interface address, where
+    /// `Ipv4Addr::UNSPECIFIED` leaves the choice to the routing table.
+    pub fn from_interface(interface: Ipv4Addr) -> Self {
+        if interface.is_unspecified() {
+            InterfacePolicy::DefaultRoute
+        } else {
+            InterfacePolicy::Only(vec![interface])
+        }
+    }
+
+    /// Applies the policy to the host's `addresses`. `default_route` is the address
+    /// the routing table picks for multicast; if it is unknown or not among
+    /// `addresses`, `DefaultRoute` falls back to the first non-loopback address.
+    pub fn select(&self, addresses: &[IpAddr], default_route: Option<Ipv4Addr>) -> Vec<Ipv4Addr> {
+        let ipv4: Vec<Ipv4Addr> = addresses
+            .iter()
+            .filter_map(|addr| match addr {
+                IpAddr::V4(ip) if !ip.is_unspecified() => Some(*ip),
+                _ => None,
+            })
+            .collect();
+
+        match self {
+            InterfacePolicy::All => {
+                let external: Vec<Ipv4Addr> = ipv4.iter().filter(|ip| !ip.is_loopback()).copied().collect();
+                if external.is_empty() {
+                    ipv4.into_iter().take(1).collect()
+                } else {
+                    external
+                }
+            }
+            InterfacePolicy::DefaultRoute => default_route
+                .filter(|route| ipv4.contains(route))
+                .or_else(|| preferred_ipv4(addresses))
+                .into_iter()
+                .collect(),
+            InterfacePolicy::Only(allowed) => ipv4.into_iter().filter(|ip| allowed.contains(ip)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> Vec<IpAddr> {
+        vec![
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+            "fe80::1".parse().unwrap(),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)),
+        ]
+    }
+
+    #[test]
+    fn test_all_skips_loopback() {
+        let selected = InterfacePolicy::All.select(&host(), None);
+        assert_eq!(selected, vec![Ipv4Addr::new(192, 168, 1, 20), Ipv4Addr::new(10, 0, 0, 5)]);
+        let loopback_only = InterfacePolicy::All.select(&[IpAddr::V4(Ipv4Addr::LOCALHOST)], None);
+        assert_eq!(loopback_only, vec![Ipv4Addr::LOCALHOST]);
+    }
+
+    #[test]
+    fn test_default_route_prefers_routing_table() {
+        let route = Some(Ipv4Addr::new(10, 0, 0, 5));
+        assert_eq!(InterfacePolicy::DefaultRoute.select(&host(), route), vec![Ipv4Addr::new(10, 0, 0, 5)]);
+        // A route address the host does not list falls back to the first external one.
+        let foreign = Some(Ipv4Addr::new(172, 16, 0, 1));
+        assert_eq!(InterfacePolicy::DefaultRoute.select(&host(), foreign), vec![Ipv4Addr::new(192, 168, 1, 20)]);
+    }
+
+    #[test]
+    fn test_only_ignores_absent_addresses() {
+        let policy = InterfacePolicy::Only(vec![Ipv4Addr::new(10, 0, 0, 5), Ipv4Addr::new(172, 16, 0, 1)]);
+        assert_eq!(policy.select(&host(), None), vec![Ipv4Addr::new(10, 0, 0, 5)]);
+    }
+}
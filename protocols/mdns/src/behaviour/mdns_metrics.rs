@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Counters describing what an `MdnsService` has done since it started.
+///
+/// All counters are monotonically increasing and updated with relaxed atomics, so
+/// they can be bumped from any task without locking.
+#[derive(Debug, Default)]
+pub struct MdnsMetrics {
+    services_registered: AtomicU64,
+    nodes_discovered: AtomicU64,
+    queries_sent: AtomicU64,
+    queries_received: AtomicU64,
+    queries_answered: AtomicU64,
+    responses_processed: AtomicU64,
+    advertisements_sent: AtomicU64,
+    conflicts_detected: AtomicU64,
+    events_dropped: AtomicU64,
+    /// When the last advertisement, query and received packet happened, in
+    /// milliseconds since the Unix epoch; zero means never.
+    last_advertisement_ms: AtomicU64,
+    last_query_ms: AtomicU64,
+    last_packet_received_ms: AtomicU64,
+}
+
+/// A point-in-time copy of `MdnsMetrics`, suitable for exporting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MdnsMetricsSnapshot {
+    pub services_registered: u64,
+    pub nodes_discovered: u64,
+    pub queries_sent: u64,
+    pub queries_received: u64,
+    pub queries_answered: u64,
+    pub responses_processed: u64,
+    pub advertisements_sent: u64,
+    pub conflicts_detected: u64,
+    /// Events a lagging subscriber missed because the event channel was full.
+    pub events_dropped: u64,
+}
+
+impl MdnsMetrics {
+    pub(crate) fn record_service_registered(&self) {
+        self.services_registered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_node_discovered(&self) {
+        self.nodes_discovered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_query_sent(&self) {
+        self.queries_sent.fetch_add(1, Ordering::Relaxed);
+        stamp(&self.last_query_ms);
+    }
+
+    pub(crate) fn record_query_received(&self) {
+        self.queries_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_query_answered(&self) {
+        self.queries_answered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_response_processed(&self) {
+        self.responses_processed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_advertisement_sent(&self) {
+        self.advertisements_sent.fetch_add(1, Ordering::Relaxed);
+        stamp(&self.last_advertisement_ms);
+    }
+
+    pub(crate) fn record_conflict_detected(&self) {
+        self.conflicts_detected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_event_dropped(&self) {
+        self.events_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_packet_received(&self) {
+        stamp(&self.last_packet_received_ms);
+    }
+
+    pub(crate) fn last_advertisement(&self) -> Option<SystemTime> {
+        read_stamp(&self.last_advertisement_ms)
+    }
+
+    pub(crate) fn last_query(&self) -> Option<SystemTime> {
+        read_stamp(&self.last_query_ms)
+    }
+
+    pub(crate) fn last_packet_received(&self) -> Option<SystemTime> {
+        read_stamp(&self.last_packet_received_ms)
+    }
+
+    /// Reads every counter into a plain snapshot.
+    pub fn snapshot(&self) -> MdnsMetricsSnapshot {
+        MdnsMetricsSnapshot {
+            services_registered: self.services_registered.load(Ordering::Relaxed),
+            nodes_discovered: self.nodes_discovered.load(Ordering::Relaxed),
+            queries_sent: self.queries_sent.load(Ordering::Relaxed),
+            queries_received: self.queries_received.load(Ordering::Relaxed),
+            queries_answered: self.queries_answered.load(Ordering::Relaxed),
+            responses_processed: self.responses_processed.load(Ordering::Relaxed),
+            advertisements_sent: self.advertisements_sent.load(Ordering::Relaxed),
+            conflicts_detected: self.conflicts_detected.load(Ordering::Relaxed),
+            events_dropped: self.events_dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn stamp(at: &AtomicU64) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0);
+    at.store(now.max(1), Ordering::Relaxed);
+}
+
+fn read_stamp(at: &AtomicU64) -> Option<SystemTime> {
+    match at.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+    }
+}
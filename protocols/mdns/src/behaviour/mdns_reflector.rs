@@ -0,0 +1,113 @@
+// protocols\mdns\src\behaviour\mdns_reflector.rs
+use crate::{DnsName, DnsPacket, DnsRecord, MdnsError, MdnsService};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Name of the additional TXT record used to tag packets the reflector has already forwarded.
+const REFLECTOR_MARKER: &str = "_nautilus-reflector.local";
+
+/// Anything that can put an mDNS packet on a network segment.
+pub trait PacketSink: Send + Sync + 'static {
+    fn send_packet(&self, packet: &DnsPacket) -> impl Future<Output = Result<(), MdnsError>> + Send;
+}
+
+impl PacketSink for MdnsService {
+    fn send_packet(&self, packet: &DnsPacket) -> impl Future<Output = Result<(), MdnsError>> + Send {
+        MdnsService::send_packet(self, packet)
+    }
+}
+
+/// Which interface a packet was received on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReflectorSide {
+    A,
+    B,
+}
+
+impl ReflectorSide {
+    fn tag(self) -> &'static [u8] {
+        match self {
+            ReflectorSide::A => b"from=a",
+            ReflectorSide::B => b"from=b",
+        }
+    }
+}
+
+/// Relays mDNS queries and responses between two network segments (e.g. VLANs).
+///
+/// Every forwarded packet carries a marker TXT record in its additionals section; packets
+/// that already carry it are never forwarded again, so a packet cannot bounce back onto
+/// the interface it came from.
+pub struct MdnsReflector<A: PacketSink, B: PacketSink> {
+    side_a: Arc<A>,
+    side_b: Arc<B>,
+}
+
+impl<A: PacketSink, B: PacketSink> MdnsReflector<A, B> {
+    pub fn new(side_a: Arc<A>, side_b: Arc<B>) -> Arc<Self> {
+        Arc::new(Self { side_a, side_b })
+    }
+
+    /// Returns true if the packet was already forwarded by a reflector.
+    pub fn is_reflected(packet: &DnsPacket) -> bool {
+        packet.additionals.iter().any(|record| {
+            matches!(record, DnsRecord::TXT { name, .. } if name.to_string() == REFLECTOR_MARKER)
+        })
+    }
+
+    /// Forwards a packet received on `from` to the opposite side.
+    ///
+    /// Returns `Ok(false)` if the packet was dropped for loop prevention.
+    pub async fn forward(&self, packet: &DnsPacket, from: ReflectorSide) -> Result<bool, MdnsError> {
+        if Self::is_reflected(packet) {
+            return Ok(false);
+        }
+
+        let mut reflected = packet.clone();
+        reflected.additionals.push(DnsRecord::TXT {
+            name: DnsName::new(REFLECTOR_MARKER).map_err(MdnsError::Generic)?,
+            ttl: 0,
+            txt_data: from.tag().to_vec(),
+        });
+
+        match from {
+            ReflectorSide::A => self.side_b.send_packet(&reflected).await?,
+            ReflectorSide::B => self.side_a.send_packet(&reflected).await?,
+        }
+        println!("(REFLECTOR) Forwarded packet from side {:?}", from);
+        Ok(true)
+    }
+}
+
+impl MdnsReflector<MdnsService, MdnsService> {
+    /// Spawns a receive loop per interface. Each packet is processed by the service that
+    /// received it and then forwarded to the other interface.
+    ///
+    /// Use this instead of `MdnsService::run`'s listen task for the two services.
+    pub fn run(self: &Arc<Self>) {
+        for side in [ReflectorSide::A, ReflectorSide::B] {
+            let reflector = Arc::clone(self);
+            tokio::spawn(async move {
+                let service = match side {
+                    ReflectorSide::A => Arc::clone(&reflector.side_a),
+                    ReflectorSide::B => Arc::clone(&reflector.side_b),
+                };
+                loop {
+                    match service.recv_packet().await {
+                        Ok(Some((packet, src))) => {
+                            service.handle_packet(&packet, &src).await;
+                            if let Err(err) = reflector.forward(&packet, side).await {
+                                eprintln!("(REFLECTOR) Failed to forward packet: {:?}", err);
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(err) => {
+                            eprintln!("(REFLECTOR) Receive error on side {:?}: {:?}", side, err);
+                            break;
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
@@ -1,24 +1,116 @@
-use crate::behaviour::records::{NodeRecord, ServiceRecord};
-use crate::{DnsName, DnsPacket, DnsRecord, MdnsError, MdnsRegistry, MdnsEvent};
+use crate::behaviour::records::{extract_service_type, NodeRecord, ServiceRecord};
+use crate::behaviour::mdns_address_provider::{default_route_ipv4, AddressProvider, SystemAddressProvider};
+use crate::behaviour::mdns_interface_policy::InterfacePolicy;
+use crate::behaviour::mdns_backoff::{BackoffState, QueryBackoff, DEFAULT_QUERY_INTERVAL};
+use crate::behaviour::mdns_metrics::{MdnsMetrics, MdnsMetricsSnapshot};
+use crate::behaviour::mdns_status::MdnsStatus;
+use crate::behaviour::mdns_signature::{decode_service_txt, encode_service_txt, SignatureVerifier};
+use crate::behaviour::mdns_source_policy::SourcePolicy;
+use crate::behaviour::mdns_clock::{Clock, SystemClock};
+use crate::behaviour::mdns_browse::ServiceStream;
+use crate::behaviour::mdns_hostname::default_origin;
+use identity::{PKIError, PKITraits};
+use crate::{DnsClass, DnsFlags, DnsName, DnsPacket, DnsQuestion, DnsRecord, DnsType, SvcParam, MdnsError, MdnsRegistry, MdnsEvent};
 use socket2::{Domain, Protocol, Socket, Type};
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tokio::net::UdpSocket;
 use tokio::sync::{broadcast, RwLock};
 use tokio::time::{self, Duration};
 
+/// Standard mDNS port; queries from any other source port come from legacy resolvers.
+const MDNS_PORT: u16 = 5353;
+/// Default size limit for a single outgoing packet, leaving headroom under a
+/// 1500-byte Ethernet MTU for IP/UDP headers.
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 1400;
+/// How long `discover` remembers that a service type had no instances.
+pub const DEFAULT_NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(3);
+/// Interval `print_node_registry` uses when none has been configured.
+pub const DEFAULT_REGISTRY_PRINT_INTERVAL: Duration = Duration::from_secs(10);
+/// Number of events buffered for subscribers before the oldest is overwritten.
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+/// Upper bound on TTLs in legacy unicast responses (RFC 6762 §6.7).
+const LEGACY_UNICAST_MAX_TTL: u32 = 10;
+
 /// Represents the mDNS service, including registry management and network communication.
 pub struct MdnsService {
     socket: Arc<UdpSocket>,
+    /// Socket outgoing packets are sent from; the multicast socket itself unless a
+    /// source port other than 5353 was requested.
+    send_socket: Arc<UdpSocket>,
     pub registry: Arc<MdnsRegistry>,
     event_sender: broadcast::Sender<MdnsEvent>,
-    origin: Arc<RwLock<Option<String>>>,
+    origin: Arc<RwLock<String>>,
     pub default_service_type: String,  // <--- [NEW] store the default service type
+    metrics: MdnsMetrics,
+    address_provider: Arc<dyn AddressProvider>,
+    interface_policy: InterfacePolicy,
+    /// Service types `process_response` keeps; `None` keeps everything.
+    interest_filter: RwLock<Option<HashSet<String>>>,
+    backoff_state: Mutex<QueryBackoff>,
+    /// Set when a response adds a node or service; consumed by `periodic_query`.
+    discovered_since_query: AtomicBool,
+    max_packet_size: AtomicUsize,
+    /// Reports malformed packets and unknown records instead of skipping them quietly.
+    strict: AtomicBool,
+    /// Rejects local services whose type is not of the DNS-SD form `_app._tcp.local.`.
+    validate_service_types: AtomicBool,
+    /// Normalized service types `discover` found nothing for, and when (`clock` ms).
+    negative_cache: Mutex<HashMap<String, u64>>,
+    negative_cache_ttl: Mutex<Duration>,
+    /// Checks signatures on discovered services; unsigned services are never
+    /// authenticated without one.
+    signature_verifier: Mutex<Option<SignatureVerifier>>,
+    /// Drops discovered services that are not authenticated.
+    require_signatures: AtomicBool,
+    /// Which source addresses responses and queries are accepted from.
+    source_policy: Mutex<SourcePolicy>,
+    /// Service ids advertised right after the default node service, in this order.
+    priority_services: Mutex<Vec<String>>,
+    /// ALPN protocol ids of local services that are also advertised with an SVCB
+    /// record, by service id.
+    svcb_alpn: Mutex<HashMap<String, Vec<String>>>,
+    /// Time source for the negative cache.
+    clock: Mutex<Arc<dyn Clock>>,
+    /// Reused across sends to avoid allocating a buffer per packet.
+    send_buffer: Mutex<Vec<u8>>,
+    /// Set until the next periodic query goes out with the QU bit.
+    unicast_query_pending: AtomicBool,
+    /// How often `run` logs the node registry; `None` (the default) disables it.
+    registry_print_interval: Mutex<Option<Duration>>,
+    /// The registry printing task spawned by `run`, if any.
+    registry_printer: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Last packet built by `create_advertise_packet`, reused until invalidated.
+    advertise_cache: Mutex<Option<AdvertiseCache>>,
+}
+
+/// A built advertisement and what it was built from.
+struct AdvertiseCache {
+    packet: DnsPacket,
+    /// The addresses its A records carry.
+    addresses: Vec<Ipv4Addr>,
+    /// `MdnsRegistry::service_removals` when it was built.
+    service_removals: u64,
+    /// When the first of its services expires.
+    valid_until: Option<SystemTime>,
+}
+
+impl AdvertiseCache {
+    fn is_current(&self, addresses: &[Ipv4Addr], service_removals: u64) -> bool {
+        self.addresses == addresses
+            && self.service_removals == service_removals
+            && self.valid_until.is_none_or(|deadline| SystemTime::now() < deadline)
+    }
 }
 
 impl MdnsService {
-    /// Sets up a multicast UDP socket for mDNS communication.
-    async fn setup_multicast_socket() -> Result<UdpSocket, MdnsError> {
+    /// Sets up a multicast UDP socket for mDNS communication that joins the group on
+    /// each of `interfaces` and sends on the first one joined. Interfaces that cannot
+    /// join are skipped; if none can, the OS picks the interface.
+    async fn setup_multicast_socket(interfaces: &[Ipv4Addr]) -> Result<UdpSocket, MdnsError> {
         let multicast_addr = Ipv4Addr::new(224, 0, 0, 251);
         let local_addr = Ipv4Addr::UNSPECIFIED;
         let port = 5353;
@@ -37,31 +129,174 @@ impl MdnsService {
             .bind(&SocketAddr::V4(SocketAddrV4::new(local_addr, port)).into())
             .map_err(MdnsError::NetworkError)?;
 
+        let mut joined = Vec::new();
+        for interface in interfaces.iter().filter(|interface| !interface.is_unspecified()) {
+            match socket.join_multicast_v4(&multicast_addr, interface) {
+                Ok(()) => joined.push(*interface),
+                Err(err) => eprintln!("(INIT) Cannot join {} on {}: {}", multicast_addr, interface, err),
+            }
+        }
+        match joined.first() {
+            Some(interface) => socket
+                .set_multicast_if_v4(interface)
+                .map_err(MdnsError::NetworkError)?,
+            None => socket
+                .join_multicast_v4(&multicast_addr, &Ipv4Addr::UNSPECIFIED)
+                .map_err(MdnsError::NetworkError)?,
+        }
+
+        // Tokio expects a non-blocking socket; a blocking one stalls the runtime thread.
+        socket.set_nonblocking(true).map_err(MdnsError::NetworkError)?;
         let udp_socket = UdpSocket::from_std(socket.into()).map_err(MdnsError::NetworkError)?;
-        udp_socket
-            .join_multicast_v4(multicast_addr, local_addr)
-            .map_err(MdnsError::NetworkError)?;
 
         println!("(INIT) Multicast socket set up on {}:{}", multicast_addr, port);
         Ok(udp_socket)
     }
 
+    /// Sets up the socket outgoing packets are sent from when they must originate from
+    /// `port` rather than 5353. It sends multicast on `interface` (unless unspecified)
+    /// and receives the unicast replies peers address to that port.
+    async fn setup_send_socket(port: u16, interface: Option<Ipv4Addr>) -> Result<UdpSocket, MdnsError> {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
+            .map_err(MdnsError::NetworkError)?;
+        socket
+            .set_reuse_address(true)
+            .map_err(MdnsError::NetworkError)?;
+        socket
+            .bind(&SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port)).into())
+            .map_err(MdnsError::NetworkError)?;
+        if let Some(interface) = interface.filter(|interface| !interface.is_unspecified()) {
+            socket
+                .set_multicast_if_v4(&interface)
+                .map_err(MdnsError::NetworkError)?;
+        }
+
+        socket.set_nonblocking(true).map_err(MdnsError::NetworkError)?;
+        let udp_socket = UdpSocket::from_std(socket.into()).map_err(MdnsError::NetworkError)?;
+        println!("(INIT) Sending from source port {}", port);
+        Ok(udp_socket)
+    }
+
     /// Creates a new mDNS service instance. We also register a default node service so that
     /// the node is always discoverable by at least one service type.
+    ///
+    /// With no `origin`, the node is named `<hostname>.local` (see `default_origin`).
     pub async fn new(
         origin: Option<String>,
         default_service_type: &str, // user picks what the "compulsory" service type is
     ) -> Result<Arc<Self>, MdnsError> {
-        let socket = Self::setup_multicast_socket().await?;
-        let registry = MdnsRegistry::new();
-        let (event_sender, _) = broadcast::channel(100);
+        Self::new_on_interface(origin, default_service_type, Ipv4Addr::UNSPECIFIED).await
+    }
+
+    /// Creates a new mDNS service that joins the multicast group and sends on a specific
+    /// local interface address (e.g. one side of an `MdnsReflector`).
+    pub async fn new_on_interface(
+        origin: Option<String>,
+        default_service_type: &str,
+        interface: Ipv4Addr,
+    ) -> Result<Arc<Self>, MdnsError> {
+        Self::with_address_provider(
+            origin,
+            default_service_type,
+            interface,
+            Arc::new(SystemAddressProvider),
+        )
+        .await
+    }
+
+    /// Creates a new mDNS service that advertises the addresses reported by
+    /// `address_provider` instead of enumerating the host's interfaces.
+    pub async fn with_address_provider(
+        origin: Option<String>,
+        default_service_type: &str,
+        interface: Ipv4Addr,
+        address_provider: Arc<dyn AddressProvider>,
+    ) -> Result<Arc<Self>, MdnsError> {
+        Self::with_interface_policy(
+            origin,
+            default_service_type,
+            InterfacePolicy::from_interface(interface),
+            address_provider,
+        )
+        .await
+    }
+
+    /// Creates a new mDNS service whose multicast membership and advertised A records
+    /// follow `policy`, applied to the addresses reported by `address_provider`.
+    pub async fn with_interface_policy(
+        origin: Option<String>,
+        default_service_type: &str,
+        policy: InterfacePolicy,
+        address_provider: Arc<dyn AddressProvider>,
+    ) -> Result<Arc<Self>, MdnsError> {
+        Self::with_registry(origin, default_service_type, policy, address_provider, MdnsRegistry::new()).await
+    }
+
+    /// Creates a new mDNS service like `with_interface_policy` that keeps discovered
+    /// records in `registry`, e.g. one built on other stores with `MdnsRegistry::with_stores`.
+    pub async fn with_registry(
+        origin: Option<String>,
+        default_service_type: &str,
+        policy: InterfacePolicy,
+        address_provider: Arc<dyn AddressProvider>,
+        registry: Arc<MdnsRegistry>,
+    ) -> Result<Arc<Self>, MdnsError> {
+        Self::with_source_port(origin, default_service_type, policy, address_provider, registry, MDNS_PORT).await
+    }
+
+    /// Creates a new mDNS service like `with_registry` whose queries and responses are
+    /// sent from `source_port` instead of 5353, e.g. to match a firewall rule. Peers
+    /// treat packets from any other port as legacy queries and answer them by unicast
+    /// to that port, which the service also listens on.
+    pub async fn with_source_port(
+        origin: Option<String>,
+        default_service_type: &str,
+        policy: InterfacePolicy,
+        address_provider: Arc<dyn AddressProvider>,
+        registry: Arc<MdnsRegistry>,
+        source_port: u16,
+    ) -> Result<Arc<Self>, MdnsError> {
+        // Without an explicit origin, name the node after the host so machines that
+        // all omit it do not collide on one default name.
+        let origin = normalize_origin(&origin.unwrap_or_else(default_origin))?;
+        let interfaces = policy.select(&address_provider.local_addresses(), default_route_ipv4());
+        let socket = Arc::new(Self::setup_multicast_socket(&interfaces).await?);
+        let send_socket = if source_port == MDNS_PORT {
+            Arc::clone(&socket)
+        } else {
+            Arc::new(Self::setup_send_socket(source_port, interfaces.first().copied()).await?)
+        };
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
 
         let service = Arc::new(Self {
-            socket: Arc::new(socket),
+            socket,
+            send_socket,
             registry,
             event_sender,
             origin: Arc::new(RwLock::new(origin)),
             default_service_type: default_service_type.to_string(),
+            metrics: MdnsMetrics::default(),
+            address_provider,
+            interface_policy: policy,
+            interest_filter: RwLock::new(None),
+            backoff_state: Mutex::new(QueryBackoff::new(DEFAULT_QUERY_INTERVAL)),
+            discovered_since_query: AtomicBool::new(false),
+            max_packet_size: AtomicUsize::new(DEFAULT_MAX_PACKET_SIZE),
+            strict: AtomicBool::new(false),
+            validate_service_types: AtomicBool::new(true),
+            negative_cache: Mutex::new(HashMap::new()),
+            negative_cache_ttl: Mutex::new(DEFAULT_NEGATIVE_CACHE_TTL),
+            signature_verifier: Mutex::new(None),
+            require_signatures: AtomicBool::new(false),
+            source_policy: Mutex::new(SourcePolicy::allow_all()),
+            priority_services: Mutex::new(Vec::new()),
+            svcb_alpn: Mutex::new(HashMap::new()),
+            clock: Mutex::new(Arc::new(SystemClock)),
+            send_buffer: Mutex::new(Vec::with_capacity(DEFAULT_MAX_PACKET_SIZE)),
+            unicast_query_pending: AtomicBool::new(true),
+            registry_print_interval: Mutex::new(None),
+            registry_printer: Mutex::new(None),
+            advertise_cache: Mutex::new(None),
         });
 
         // [NEW] Register the default service for our local node:
@@ -70,49 +305,255 @@ impl MdnsService {
         Ok(service)
     }
 
+    /// Returns the origin (node name) this service advertises under.
+    pub async fn origin(&self) -> String {
+        self.origin.read().await.clone()
+    }
+
     /// Registers the *compulsory* "default" service for this node.
     pub async fn register_default_node_service(&self) -> Result<(), MdnsError> {
-        let node_origin = {
-            let origin_lock = self.origin.read().await;
-            origin_lock
-                .clone()
-                .unwrap_or_else(|| "UnknownOrigin.local".to_string())
-        };
+        let node_origin = self.origin().await;
 
         // e.g. "MyLaptop.local._mdnsnode._tcp.local."
-        let default_id = format!(
-            "{}.{}",
-            node_origin.trim_end_matches('.'),
-            self.default_service_type.trim_start_matches('.')
-        );
+        let default_id = self.default_service_id(&node_origin);
 
         // Construct the never-expiring default service
-        let service_record = ServiceRecord {
-            id: default_id.clone(),
-            service_type: self.default_service_type.clone(),
-            port: 5353,             // or a relevant port
-            ttl: Some(u32::MAX),    // never expires
-            origin: node_origin.clone(),
-            priority: Some(0),
-            weight: Some(0),
-            node_id: node_origin.clone(),
-        };
+        let service_record = ServiceRecord::builder(default_id.clone(), self.default_service_type.clone())
+            .port(5353)             // or a relevant port
+            .ttl(Some(u32::MAX))    // never expires
+            .origin(node_origin)
+            .build();
 
         // Add the service to the registry
         self.registry.add_service(service_record.clone()).await?;
+        self.invalidate_advertise_cache();
 
         // Also ensure the node record exists and references this service
         self.link_service_to_node(&service_record).await?;
 
+        self.metrics.record_service_registered();
         println!("(DEFAULT-SERVICE) Registered default node service: {}", default_id);
         Ok(())
     }
 
+    /// The id the default node service is registered under for `origin`.
+    fn default_service_id(&self, origin: &str) -> String {
+        format!(
+            "{}.{}",
+            origin.trim_end_matches('.'),
+            self.default_service_type.trim_start_matches('.')
+        )
+    }
+
+    /// Lists the distinct service types this node currently offers.
+    pub async fn local_service_types(&self) -> Vec<String> {
+        let origin = self.origin().await;
+        self.registry.local_service_types(&origin).await
+    }
+
+    /// Restricts discovery to the given service types. Responses are then only mined
+    /// for SRV records of these types and the A records of their targets. `None`
+    /// restores processing of every record.
+    pub async fn set_interest_filter(&self, service_types: Option<HashSet<String>>) {
+        let normalized = service_types.map(|types| {
+            types.iter().map(|service_type| normalize_service_type(service_type)).collect()
+        });
+        *self.interest_filter.write().await = normalized;
+    }
+
+    /// Sets the size limit used when splitting advertisements into packets.
+    pub fn set_max_packet_size(&self, max_packet_size: usize) {
+        self.max_packet_size.store(max_packet_size, Ordering::Relaxed);
+    }
+
+    /// Returns the size limit used when splitting advertisements into packets.
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables strict parsing. In strict mode packets with malformed or
+    /// unknown records are logged with their raw bytes; they are still processed as far
+    /// as the lenient parser gets.
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.store(strict, Ordering::Relaxed);
+    }
+
+    /// Returns whether strict parsing is enabled.
+    pub fn is_strict(&self) -> bool {
+        self.strict.load(Ordering::Relaxed)
+    }
+
+    /// Enables or disables DNS-SD validation of local service types (on by default).
+    /// Disable it only for private, non-standard types; standard browsers will not
+    /// find services registered under them.
+    pub fn set_validate_service_types(&self, validate: bool) {
+        self.validate_service_types.store(validate, Ordering::Relaxed);
+    }
+
+    /// Returns whether local service types must follow the DNS-SD grammar.
+    pub fn validates_service_types(&self) -> bool {
+        self.validate_service_types.load(Ordering::Relaxed)
+    }
+
+    /// Makes `run` log the node registry every `interval`, for debugging. `None`, the
+    /// default, leaves the registry unlogged. Takes effect the next time `run` is called.
+    pub fn set_registry_print_interval(&self, interval: Option<Duration>) {
+        *self.registry_print_interval.lock().unwrap() = interval;
+    }
+
+    /// Returns how often `run` logs the node registry, if at all.
+    pub fn registry_print_interval(&self) -> Option<Duration> {
+        *self.registry_print_interval.lock().unwrap()
+    }
+
+    /// Returns whether a registry printing task started by `run` is active.
+    pub fn is_printing_registry(&self) -> bool {
+        self.registry_printer
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// Sets how long an empty `discover` result is reused before querying again.
+    /// `Duration::ZERO` disables the negative cache.
+    pub fn set_negative_cache_ttl(&self, ttl: Duration) {
+        *self.negative_cache_ttl.lock().unwrap() = ttl;
+    }
+
+    /// Returns how long an empty `discover` result is reused.
+    pub fn negative_cache_ttl(&self) -> Duration {
+        *self.negative_cache_ttl.lock().unwrap()
+    }
+
+    /// Replaces the clock used for time-based decisions (`SystemClock` by default).
+    /// Tests can install a `MockClock` and advance it instead of sleeping.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        *self.clock.lock().unwrap() = clock;
+    }
+
+    /// Returns the clock's current time in milliseconds.
+    fn now_ms(&self) -> u64 {
+        self.clock.lock().unwrap().now_ms()
+    }
+
+    /// Sets the service ids advertised right after the default node service, ahead of
+    /// every other service and in the given order. When an advertisement is split,
+    /// these land in the earliest packets.
+    pub fn set_priority_services(&self, service_ids: Vec<String>) {
+        *self.priority_services.lock().unwrap() = service_ids;
+        self.invalidate_advertise_cache();
+    }
+
+    /// Returns the service ids advertised ahead of the rest.
+    pub fn priority_services(&self) -> Vec<String> {
+        self.priority_services.lock().unwrap().clone()
+    }
+
+    /// Sets the verifier used to authenticate signed services found by
+    /// `process_response`; `None` leaves every discovered service unauthenticated.
+    pub fn set_signature_verifier(&self, verifier: Option<SignatureVerifier>) {
+        *self.signature_verifier.lock().unwrap() = verifier;
+    }
+
+    /// When enabled, discovered services without a valid signature are dropped and
+    /// no `Discovered` event is sent for them.
+    pub fn set_require_signatures(&self, require: bool) {
+        self.require_signatures.store(require, Ordering::Relaxed);
+    }
+
+    /// Returns whether discovered services must carry a valid signature.
+    pub fn require_signatures(&self) -> bool {
+        self.require_signatures.load(Ordering::Relaxed)
+    }
+
+    /// Replaces the source address policy. Packets from sources it rejects are
+    /// dropped before they can touch the registry or trigger a response.
+    pub fn set_source_policy(&self, policy: SourcePolicy) {
+        *self.source_policy.lock().unwrap() = policy;
+    }
+
+    /// Returns the current source address policy.
+    pub fn source_policy(&self) -> SourcePolicy {
+        self.source_policy.lock().unwrap().clone()
+    }
+
+    /// Checks `src` against the source policy, logging rejected packets.
+    fn source_permitted(&self, src: &SocketAddr, kind: &str) -> bool {
+        let permitted = self.source_policy.lock().unwrap().permits(src.ip());
+        if !permitted {
+            println!("(POLICY) Dropping {} from disallowed source {}", kind, src);
+        }
+        permitted
+    }
+
+    /// Returns the current query backoff state.
+    pub fn backoff_state(&self) -> BackoffState {
+        self.backoff_state.lock().unwrap().state()
+    }
+
+    /// Forces the backoff state. Automatic transitions stay disabled until
+    /// `clear_backoff_override` is called.
+    pub fn set_backoff_state(&self, state: BackoffState) {
+        self.backoff_state.lock().unwrap().pin(state);
+    }
+
+    /// Re-enables automatic backoff transitions after `set_backoff_state`.
+    pub fn clear_backoff_override(&self) {
+        self.backoff_state.lock().unwrap().unpin();
+    }
+
+    /// Updates the backoff state after a query cycle: `Active` if new records were
+    /// seen since the previous query, `Backoff` otherwise. Ignored while overridden.
+    pub fn adjust_backoff_state(&self, saw_new_records: bool) {
+        self.backoff_state.lock().unwrap().adjust(saw_new_records);
+    }
+
+    /// Returns the delay before the next periodic query and advances the backoff.
+    pub fn next_query_interval(&self) -> Duration {
+        self.backoff_state.lock().unwrap().next_interval()
+    }
+
+    /// Returns a snapshot of the service's activity counters.
+    pub fn metrics(&self) -> MdnsMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Returns a health snapshot: socket binding, registry sizes, when the service
+    /// last advertised, queried and received a packet, and the backoff state.
+    pub async fn status(&self) -> MdnsStatus {
+        let origin = self.origin().await;
+        MdnsStatus {
+            local_addr: self.socket.local_addr().ok(),
+            services: self.registry.list_services().await.len(),
+            local_services: self.registry.list_services_by_node(&origin).await.len(),
+            nodes: self.registry.list_nodes().await.len(),
+            last_advertisement: self.metrics.last_advertisement(),
+            last_query: self.metrics.last_query(),
+            last_packet_received: self.metrics.last_packet_received(),
+            backoff_state: self.backoff_state(),
+            metrics: self.metrics.snapshot(),
+        }
+    }
+
     /// Public helper to retrieve a broadcast receiver for events.
+    ///
+    /// Events are never waited on: a receiver that falls more than the channel's
+    /// capacity behind misses the oldest events (and sees `RecvError::Lagged`), and
+    /// each miss is counted in `MdnsMetricsSnapshot::events_dropped`.
     pub fn get_event_receiver(&self) -> broadcast::Receiver<MdnsEvent> {
         self.event_sender.subscribe()
     }
 
+    /// Publishes an event without blocking. When the channel is full the send
+    /// overwrites the oldest buffered event, which the slowest subscriber then loses.
+    fn emit_event(&self, event: MdnsEvent) {
+        if self.event_sender.receiver_count() > 0 && self.event_sender.len() >= EVENT_CHANNEL_CAPACITY {
+            self.metrics.record_event_dropped();
+        }
+        let _ = self.event_sender.send(event);
+    }
+
     /// Registers a local ephemeral (non-default) service to the registry.
     ///
     /// **Also** updates the node so that `NodeRecord.services` contains this service ID.
@@ -124,35 +565,154 @@ impl MdnsService {
         ttl: Option<u32>,
         origin: String,
     ) -> Result<(), MdnsError> {
-        let service = ServiceRecord {
-            id: id.clone(),
-            service_type,
-            port,
-            ttl,
-            origin: origin.clone(),
-            priority: Some(0),
-            weight: Some(0),
-            node_id: origin.clone(),
-        };
+        let service = ServiceRecord::builder(id, service_type)
+            .port(port)
+            .ttl(ttl)
+            .origin(origin)
+            .build();
+        self.add_local_service(service).await
+    }
+
+    /// Registers a local service like `register_local_service` that is also advertised
+    /// with an SVCB record carrying `alpn`, its port and our IPv4 addresses.
+    pub async fn register_local_service_with_alpn(
+        &self,
+        id: String,
+        service_type: String,
+        port: u16,
+        ttl: Option<u32>,
+        origin: String,
+        alpn: Vec<String>,
+    ) -> Result<(), MdnsError> {
+        self.svcb_alpn.lock().unwrap().insert(id.clone(), alpn);
+        let result = self.register_local_service(id.clone(), service_type, port, ttl, origin).await;
+        if result.is_err() {
+            self.svcb_alpn.lock().unwrap().remove(&id);
+        }
+        result
+    }
+
+    /// Builds the SVCB record for a service registered with
+    /// `register_local_service_with_alpn`, or `None` for any other service.
+    fn service_svcb_record(
+        &self,
+        service: &ServiceRecord,
+        id: &DnsName,
+        target: &DnsName,
+        addresses: &[Ipv4Addr],
+    ) -> Option<DnsRecord> {
+        let alpn = self.svcb_alpn.lock().unwrap().get(&service.id).cloned()?;
+        let mut params = vec![SvcParam::Alpn(alpn), SvcParam::Port(service.port)];
+        if !addresses.is_empty() {
+            params.push(SvcParam::Ipv4Hint(addresses.to_vec()));
+        }
+        Some(DnsRecord::SVCB {
+            name: id.clone(),
+            ttl: service.ttl.unwrap_or(120),
+            priority: 1,
+            target: target.clone(),
+            params,
+        })
+    }
+
+    /// Registers a local service like `register_local_service`, signed with `keypair`.
+    /// The signature and public key are advertised in the service's TXT record.
+    pub async fn register_signed_local_service<K: PKITraits<Error = PKIError>>(
+        &self,
+        id: String,
+        service_type: String,
+        port: u16,
+        ttl: Option<u32>,
+        origin: String,
+        keypair: &K,
+    ) -> Result<(), MdnsError> {
+        let mut service = ServiceRecord::builder(id, service_type)
+            .port(port)
+            .ttl(ttl)
+            .origin(origin)
+            .build();
+        service
+            .sign(keypair)
+            .map_err(|err| MdnsError::Generic(format!("Failed to sign service: {}", err)))?;
+        self.add_local_service(service).await
+    }
+
+    async fn add_local_service(&self, service: ServiceRecord) -> Result<(), MdnsError> {
+        let name = DnsName::try_new(&service.id)?;
+        let target = DnsName::try_new(&service.origin)?;
+        DnsName::try_new(&service.service_type)?;
+        if self.validates_service_types() {
+            validate_service_type(&service.service_type)?;
+        }
 
         self.registry.add_service(service.clone()).await?;
+        self.invalidate_advertise_cache();
 
         // Link the service to the node
         self.link_service_to_node(&service).await?;
+        self.metrics.record_service_registered();
 
         // Optionally, broadcast an event
-        let _ = self.event_sender.send(MdnsEvent::Discovered(DnsRecord::SRV {
-            name: DnsName::new(&service.id).unwrap(),
+        self.emit_event(MdnsEvent::Discovered(DnsRecord::SRV {
+            name,
             ttl: service.ttl.unwrap_or(120),
             priority: service.priority.unwrap_or(0),
             weight: service.weight.unwrap_or(0),
             port: service.port,
-            target: DnsName::new(&service.origin).unwrap(),
+            target,
         }));
 
         Ok(())
     }
 
+    /// Registers a service that this node answers for on behalf of another device
+    /// (sleep proxy). The device's node record stores `ip_address`, which is what
+    /// query responses advertise for it.
+    pub async fn register_proxy_service(
+        &self,
+        id: String,
+        service_type: String,
+        port: u16,
+        ttl: Option<u32>,
+        node_id: String,
+        ip_address: Ipv4Addr,
+    ) -> Result<(), MdnsError> {
+        let origin = self.origin().await;
+        let node_id = node_id.trim_end_matches('.').to_string();
+
+        let node = match self.registry.get_node(&node_id).await {
+            Some(mut node) => {
+                node.ip_address = ip_address.to_string();
+                node.last_seen = SystemTime::now();
+                node
+            }
+            None => NodeRecord {
+                id: node_id.clone(),
+                ip_address: ip_address.to_string(),
+                ttl,
+                services: Vec::new(),
+                last_seen: SystemTime::now(),
+            },
+        };
+        self.store_node(node).await?;
+
+        let service = ServiceRecord::builder(id, service_type)
+            .port(port)
+            .ttl(ttl)
+            .origin(origin)
+            .node_id(node_id)
+            .proxy(true)
+            .build();
+
+        self.registry.add_service(service.clone()).await?;
+        self.invalidate_advertise_cache();
+        self.link_service_to_node(&service).await?;
+        self.metrics.record_service_registered();
+
+        println!("(PROXY) Registered proxied service: {} for {}", service.id, service.node_id);
+        Ok(())
+    }
+
     /// [NEW] Updates the NodeRecord in the registry so that it includes the given service's ID.
     /// If the node doesn't exist, we create it; if it does, we add the service ID to the list.
     async fn link_service_to_node(&self, service: &ServiceRecord) -> Result<(), MdnsError> {
@@ -167,6 +727,7 @@ impl MdnsService {
                 ip_address: "0.0.0.0".to_string(),
                 ttl: service.ttl,
                 services: Vec::new(),
+                last_seen: SystemTime::now(),
             });
         }
 
@@ -175,140 +736,507 @@ impl MdnsService {
             if !node.services.contains(&service.id) {
                 node.services.push(service.id.clone());
             }
+            node.last_seen = SystemTime::now();
             // Update the node record in the registry
-            self.registry.add_node(node).await?;
+            self.store_node(node).await?;
         }
 
         Ok(())
     }
 
+    /// Handles a change of network, e.g. a new Wi-Fi network or container address.
+    /// Applications call this from their own network-change detection.
+    ///
+    /// Addresses are re-read from the address provider, our node record is updated,
+    /// peers learned on the old network are flushed (services proxied on a peer's
+    /// behalf, and their nodes, are kept), the query backoff returns to `Active`, a
+    /// QU query for the default service type goes out right away (the next periodic
+    /// query sets the QU bit too), and all local services are re-announced.
+    pub async fn on_network_change(&self) -> Result<(), MdnsError> {
+        let origin = self.origin().await;
+        let addresses = self.advertised_addresses();
+        println!("(NETWORK) Network changed, now advertising {:?}", addresses);
+        self.invalidate_advertise_cache();
+
+        let mut kept_nodes = HashSet::from([origin.clone()]);
+        for service in self.registry.list_services().await {
+            let node_id = service.node_id.trim_end_matches('.');
+            if node_id == origin || service.proxy {
+                kept_nodes.insert(node_id.to_string());
+            } else {
+                self.registry.remove_service(&service.id).await?;
+            }
+        }
+        for node in self.registry.list_nodes().await {
+            if node.id == origin {
+                if let Some(address) = addresses.first() {
+                    let mut node = node;
+                    node.ip_address = address.to_string();
+                    node.last_seen = SystemTime::now();
+                    self.store_node(node).await?;
+                }
+            } else if !kept_nodes.contains(&node.id) {
+                self.registry.remove_node(&node.id).await?;
+                if let Ok(name) = DnsName::new(&node.id) {
+                    let ip = node.ip_address.parse().unwrap_or(Ipv4Addr::UNSPECIFIED);
+                    self.emit_event(MdnsEvent::Expired(DnsRecord::A { name, ttl: 0, ip: ip.octets() }));
+                }
+            }
+        }
+        self.negative_cache.lock().unwrap().clear();
+
+        self.backoff_state.lock().unwrap().adjust(true);
+        self.request_unicast_query();
+        let mut query = Self::create_query_packet(&self.default_service_type)?;
+        for question in &mut query.questions {
+            question.set_unicast_response(true);
+        }
+        self.send_packet(&query).await?;
+        self.metrics.record_query_sent();
+
+        self.advertise_services().await
+    }
+
     /// Creates an mDNS "advertise" packet with all services registered under this node.
+    ///
+    /// The packet is built once and reused until a local service is registered,
+    /// removed or expires, the advertised addresses or the priority list change, or
+    /// `on_network_change` runs. Code that adds services to `registry` directly should
+    /// call `invalidate_advertise_cache` afterwards.
     pub async fn create_advertise_packet(&self) -> Result<DnsPacket, MdnsError> {
-        let origin = {
-            let origin_lock = self.origin.read().await;
-            origin_lock.clone().unwrap_or_else(|| "UnknownOrigin.local".to_string())
-        };
+        let addresses = self.advertised_addresses();
+        let service_removals = self.registry.service_removals();
+        if let Some(cache) = self.advertise_cache.lock().unwrap().as_ref() {
+            if cache.is_current(&addresses, service_removals) {
+                return Ok(cache.packet.clone());
+            }
+        }
+        let (packet, valid_until) = self.build_advertise_packet(&addresses).await?;
+        *self.advertise_cache.lock().unwrap() = Some(AdvertiseCache {
+            packet: packet.clone(),
+            addresses,
+            service_removals,
+            valid_until,
+        });
+        Ok(packet)
+    }
+
+    /// Drops the cached advertisement so the next one is rebuilt from the registry
+    /// and the current local addresses.
+    pub fn invalidate_advertise_cache(&self) {
+        self.advertise_cache.lock().unwrap().take();
+    }
 
-        let services = self.registry.list_services_by_node(&origin).await;
+    /// Builds the advertisement with A records for `local_ips`, along with when the
+    /// first of the advertised services expires.
+    async fn build_advertise_packet(&self, local_ips: &[Ipv4Addr]) -> Result<(DnsPacket, Option<SystemTime>), MdnsError> {
+        let origin = self.origin().await;
+
+        let mut services = self.registry.list_services_by_node(&origin).await;
+        // Default node service first, then the priority list, then the rest in
+        // registry order, so splitting never pushes them into a later packet.
+        let default_id = self.default_service_id(&origin);
+        let priority = self.priority_services();
+        services.sort_by_key(|service| {
+            if service.id == default_id {
+                0
+            } else {
+                priority
+                    .iter()
+                    .position(|id| *id == service.id)
+                    .map_or(usize::MAX, |index| index + 1)
+            }
+        });
         let mut packet = DnsPacket::new();
-        packet.flags = 0x8400; // Set response flags
+        packet.set_dns_flags(DnsFlags::authoritative_response());
 
-        let local_ip = get_local_ipv4()
-            .ok_or_else(|| MdnsError::Generic("Failed to get local IP".to_string()))?;
+        if local_ips.is_empty() {
+            return Err(MdnsError::Generic("Failed to get local IP".to_string()));
+        }
+        let valid_until = services
+            .iter()
+            .filter_map(|service| self.registry.service_deadline(&service.id))
+            .min();
 
         if services.is_empty() {
             println!("(ADVERTISE) No local services to advertise.");
         } else {
             for service in services {
+                let Some((service_type, id, target)) = service_names(&service, &origin) else {
+                    continue;
+                };
+                let Ok(host) = DnsName::try_new(&service.origin) else {
+                    eprintln!("(ADVERTISE) Skipping service {} with invalid origin {:?}", service.id, service.origin);
+                    continue;
+                };
                 println!("(ADVERTISE) Including service in packet: {:?}", service);
 
                 packet.answers.push(DnsRecord::PTR {
-                    name: DnsName::new(&service.service_type).unwrap(),
+                    name: service_type,
                     ttl: service.ttl.unwrap_or(120),
-                    ptr_name: DnsName::new(&service.id).unwrap(),
+                    ptr_name: id.clone(),
                 });
 
+                packet.answers.extend(self.service_svcb_record(&service, &id, &target, local_ips));
                 packet.answers.push(DnsRecord::SRV {
-                    name: DnsName::new(&service.id).unwrap(),
+                    name: id,
                     ttl: service.ttl.unwrap_or(120),
                     priority: service.priority.unwrap_or(0),
                     weight: service.weight.unwrap_or(0),
                     port: service.port,
-                    target: DnsName::new(&origin).unwrap(),
+                    target,
                 });
+                packet.answers.extend(service_txt_record(&service));
 
-                packet.answers.push(DnsRecord::A {
-                    name: DnsName::new(&service.origin).unwrap(),
-                    ttl: service.ttl.unwrap_or(120),
-                    ip: local_ip.octets(),
-                });
+                for local_ip in local_ips {
+                    packet.answers.push(DnsRecord::A {
+                        name: host.clone(),
+                        ttl: service.ttl.unwrap_or(120),
+                        ip: local_ip.octets(),
+                    });
+                }
             }
         }
 
-        Ok(packet)
+        Ok((packet, valid_until))
+    }
+
+    /// The interface policy this service was created with.
+    pub fn interface_policy(&self) -> &InterfacePolicy {
+        &self.interface_policy
+    }
+
+    /// Local addresses currently advertised in A records under the interface policy.
+    pub fn advertised_addresses(&self) -> Vec<Ipv4Addr> {
+        self.interface_policy
+            .select(&self.address_provider.local_addresses(), default_route_ipv4())
+    }
+
+    /// Builds the advertisement split into packets no larger than `max_packet_size`.
+    ///
+    /// Each service's PTR/SRV/A records stay in the same packet, and services keep the
+    /// order of `create_advertise_packet`, so the default node service and any
+    /// priority services are always in the first packet. Every packet except the last
+    /// has the TC bit set to signal that more records follow.
+    pub async fn create_advertise_packets(&self) -> Result<Vec<DnsPacket>, MdnsError> {
+        let full = self.create_advertise_packet().await?;
+        let max_size = self.max_packet_size();
+
+        let mut packets = Vec::new();
+        let mut current = DnsPacket::new();
+        current.flags = full.flags;
+        // create_advertise_packet emits PTR, SRV and the A records for each service in
+        // turn, so every group starts at a PTR record.
+        let starts: Vec<usize> = full
+            .answers
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| matches!(record, DnsRecord::PTR { .. }))
+            .map(|(index, _)| index)
+            .collect();
+        let groups = starts
+            .iter()
+            .zip(starts.iter().skip(1).copied().chain(std::iter::once(full.answers.len())))
+            .map(|(&start, end)| &full.answers[start..end]);
+        for group in groups {
+            let group_size: usize = group.iter().map(DnsRecord::wire_len).sum();
+            if !current.answers.is_empty() && current.estimated_size() + group_size > max_size {
+                let mut next = DnsPacket::new();
+                next.flags = full.flags;
+                packets.push(std::mem::replace(&mut current, next));
+            }
+            current.answers.extend_from_slice(group);
+        }
+        packets.push(current);
+
+        let last = packets.len() - 1;
+        for packet in &mut packets[..last] {
+            let mut flags = packet.dns_flags();
+            flags.truncated = true;
+            packet.set_dns_flags(flags);
+        }
+        Ok(packets)
+    }
+
+    /// Returns the local address outgoing packets are sent from.
+    pub fn source_addr(&self) -> Result<SocketAddr, MdnsError> {
+        self.send_socket.local_addr().map_err(MdnsError::NetworkError)
     }
 
     /// Sends an mDNS packet over the network to the multicast address.
     pub async fn send_packet(&self, packet: &DnsPacket) -> Result<(), MdnsError> {
-        let bytes = packet.serialize();
         let multicast_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(224, 0, 0, 251), 5353));
+        self.send_to(packet, &multicast_addr).await
+    }
 
-        self.socket
-            .send_to(&bytes, multicast_addr)
-            .await
-            .map_err(MdnsError::NetworkError)?;
+    /// Sends an mDNS packet directly to a single address.
+    pub async fn send_unicast(&self, packet: &DnsPacket, dest: &SocketAddr) -> Result<(), MdnsError> {
+        self.send_to(packet, dest).await
+    }
+
+    /// Serializes into the shared send buffer and sends. The buffer is taken out for
+    /// the duration of the send, so concurrent sends fall back to a fresh allocation
+    /// rather than waiting.
+    async fn send_to(&self, packet: &DnsPacket, dest: &SocketAddr) -> Result<(), MdnsError> {
+        let mut buffer = std::mem::take(&mut *self.send_buffer.lock().unwrap());
+        buffer.clear();
+        packet.serialize_into(&mut buffer);
+
+        let result = self.send_socket.send_to(&buffer, dest).await;
+        *self.send_buffer.lock().unwrap() = buffer;
+        result.map_err(MdnsError::NetworkError)?;
 
         Ok(())
     }
 
+    /// Builds a PTR query packet for the given service type.
+    pub fn create_query_packet(service_type: &str) -> Result<DnsPacket, MdnsError> {
+        let qname = DnsName::new(service_type).map_err(MdnsError::Generic)?;
+        let mut packet = DnsPacket::new();
+        packet.set_dns_flags(DnsFlags::query());
+        packet
+            .questions
+            .push(DnsQuestion::new(qname, DnsType::PTR, DnsClass::IN));
+        Ok(packet)
+    }
+
+    /// Builds the next `periodic_query` packet. The first one after the service starts,
+    /// or after `request_unicast_query`, asks for unicast responses (QU bit) so peers
+    /// answer directly without waiting on the multicast schedule; later ones are
+    /// plain multicast queries.
+    pub fn create_periodic_query_packet(&self, service_type: &str) -> Result<DnsPacket, MdnsError> {
+        let mut packet = Self::create_query_packet(service_type)?;
+        if self.unicast_query_pending.swap(false, Ordering::Relaxed) {
+            for question in &mut packet.questions {
+                question.set_unicast_response(true);
+            }
+        }
+        Ok(packet)
+    }
+
+    /// Makes the next periodic query ask for unicast responses again, e.g. after the
+    /// host's network interfaces changed.
+    pub fn request_unicast_query(&self) {
+        self.unicast_query_pending.store(true, Ordering::Relaxed);
+    }
+
     /// Periodically sends a PTR query for the given service type.
+    ///
+    /// `interval_secs` is the base interval; it doubles while queries turn up nothing
+    /// new (see `BackoffState`). The first query sets the QU bit (see
+    /// `create_periodic_query_packet`).
     pub async fn periodic_query(&self, service_type: &str, interval_secs: u64) {
-        let mut ticker = time::interval(Duration::from_secs(interval_secs));
+        self.backoff_state
+            .lock()
+            .unwrap()
+            .set_base_interval(Duration::from_secs(interval_secs));
         loop {
-            ticker.tick().await;
-            let mut packet = DnsPacket::new();
-            packet.flags = 0x0000;
-            packet.questions.push(crate::DnsQuestion {
-                qname: DnsName::new(service_type).unwrap(),
-                qtype: 12, // PTR
-                qclass: 1,
-            });
-
-            if let Err(err) = self.send_packet(&packet).await {
-                eprintln!("(QUERY) Failed to send periodic query: {:?}", err);
-            } else {
-                println!("(QUERY) Periodic query sent for service type: {}", service_type);
+            match self.create_periodic_query_packet(service_type) {
+                Ok(packet) => {
+                    if let Err(err) = self.send_packet(&packet).await {
+                        eprintln!("(QUERY) Failed to send periodic query: {:?}", err);
+                    } else {
+                        self.metrics.record_query_sent();
+                        println!("(QUERY) Periodic query sent for service type: {}", service_type);
+                    }
+                }
+                Err(err) => eprintln!("(QUERY) Invalid service type '{}': {:?}", service_type, err),
             }
+
+            time::sleep(self.next_query_interval()).await;
+            let saw_new_records = self.discovered_since_query.swap(false, Ordering::Relaxed);
+            self.adjust_backoff_state(saw_new_records);
+        }
+    }
+
+    /// Sends a single PTR query for `service_type`, waits `timeout` for responses to be
+    /// processed by the listen loop, and returns the known services of that type.
+    ///
+    /// Discovered records only reach the registry while `run` (or `listen`) is active;
+    /// locally registered services are always included.
+    ///
+    /// If a query for `service_type` found nothing within the last
+    /// `negative_cache_ttl`, the empty result is returned at once without querying.
+    pub async fn discover(
+        &self,
+        service_type: &str,
+        timeout: Duration,
+    ) -> Result<Vec<ServiceRecord>, MdnsError> {
+        self.discover_with(service_type, timeout, false).await
+    }
+
+    /// Like `discover`, but with `force` the negative cache is bypassed and the
+    /// network is always queried.
+    pub async fn discover_with(
+        &self,
+        service_type: &str,
+        timeout: Duration,
+        force: bool,
+    ) -> Result<Vec<ServiceRecord>, MdnsError> {
+        let cache_key = normalize_service_type(service_type);
+        if !force && self.is_known_empty(&cache_key) {
+            println!("(DISCOVER) No {} services seen recently; skipping query", service_type);
+            return Ok(Vec::new());
+        }
+
+        self.send_query(service_type).await?;
+        println!("(DISCOVER) Query sent for service type: {}", service_type);
+
+        time::sleep(timeout).await;
+
+        let services: Vec<ServiceRecord> = self
+            .registry
+            .list_services()
+            .await
+            .into_iter()
+            .filter(|service| normalize_service_type(&service.service_type) == cache_key)
+            .collect();
+
+        let now_ms = self.now_ms();
+        let mut negative_cache = self.negative_cache.lock().unwrap();
+        if services.is_empty() {
+            negative_cache.insert(cache_key, now_ms);
+        } else {
+            negative_cache.remove(&cache_key);
         }
+        Ok(services)
+    }
+
+    /// Starts browsing for `service_type`: the returned stream keeps a PTR query going
+    /// at the backoff interval and yields `Added`/`Updated`/`Removed` events for
+    /// services of that type (see `ServiceStream`).
+    pub fn subscribe_service_type(&self, service_type: &str) -> ServiceStream<'_> {
+        ServiceStream::new(self, service_type)
+    }
+
+    /// Sends one multicast PTR query for `service_type` and counts it.
+    pub(crate) async fn send_query(&self, service_type: &str) -> Result<(), MdnsError> {
+        let packet = Self::create_query_packet(service_type)?;
+        self.send_packet(&packet).await?;
+        self.metrics.record_query_sent();
+        Ok(())
+    }
+
+    /// Returns true if `discover` found no instances of the normalized `service_type`
+    /// within the negative cache TTL. An entry stamped later than the clock's current
+    /// time (the clock stepped backwards) is treated as stale rather than fresh.
+    fn is_known_empty(&self, service_type: &str) -> bool {
+        let ttl_ms = self.negative_cache_ttl().as_millis() as u64;
+        let now_ms = self.now_ms();
+        self.negative_cache
+            .lock()
+            .unwrap()
+            .get(service_type)
+            .and_then(|checked_at| now_ms.checked_sub(*checked_at))
+            .is_some_and(|age| age < ttl_ms)
     }
 
     /// Advertises all local services (including the default service) as unsolicited mDNS responses.
     pub async fn advertise_services(&self) -> Result<(), MdnsError> {
-        let packet = self.create_advertise_packet().await?;
-        if packet.answers.is_empty() {
-            println!("(ADVERTISE) No answers in the mDNS packet.");
-        } else {
-            println!(
-                "(ADVERTISE) Sending mDNS packet with {} answers.",
-                packet.answers.len()
-            );
+        let packets = self.create_advertise_packets().await?;
+        for packet in &packets {
+            if packet.answers.is_empty() {
+                println!("(ADVERTISE) No answers in the mDNS packet.");
+            } else {
+                println!(
+                    "(ADVERTISE) Sending mDNS packet with {} answers.",
+                    packet.answers.len()
+                );
+            }
+            self.send_packet(packet).await?;
         }
-        self.send_packet(&packet).await
+        self.metrics.record_advertisement_sent();
+        Ok(())
     }
 
     /// Core loop listening for incoming mDNS packets and processing them.
     pub async fn listen(&self) -> Result<(), MdnsError> {
-        let mut buf = [0; 4096];
         loop {
-            let (len, src) = self
-                .socket
-                .recv_from(&mut buf)
-                .await
-                .map_err(MdnsError::NetworkError)?;
+            if let Some((packet, src)) = self.recv_packet().await? {
+                self.handle_packet(&packet, &src).await;
+            }
+        }
+    }
 
-            if let Ok(packet) = DnsPacket::parse(&buf[..len]) {
-                let is_response = (packet.flags & 0x8000) != 0;
-                if is_response {
-                    self.process_response(&packet, &src).await;
-                } else {
-                    self.process_query(&packet, &src).await;
-                }
-            } else {
+    /// Receives one datagram from the multicast socket, or from the send socket when a
+    /// source port is pinned. Returns `Ok(None)` if it could not be parsed as a DNS packet.
+    pub async fn recv_packet(&self) -> Result<Option<(DnsPacket, SocketAddr)>, MdnsError> {
+        let mut buf = [0; 4096];
+        let (len, src) = if Arc::ptr_eq(&self.socket, &self.send_socket) {
+            self.socket.recv_from(&mut buf).await
+        } else {
+            let mut send_buf = [0; 4096];
+            tokio::select! {
+                received = self.socket.recv_from(&mut buf) => received,
+                received = self.send_socket.recv_from(&mut send_buf) => received.map(|(len, src)| {
+                    buf[..len].copy_from_slice(&send_buf[..len]);
+                    (len, src)
+                }),
+            }
+        }
+        .map_err(MdnsError::NetworkError)?;
+        self.metrics.record_packet_received();
+
+        let _ = self.check_strict(&buf[..len], &src);
+        match DnsPacket::parse(&buf[..len]) {
+            Ok(packet) => Ok(Some((packet, src))),
+            Err(_) => {
                 eprintln!("(LISTEN) Failed to parse packet from {}", src);
+                Ok(None)
             }
         }
     }
 
-    /// Periodically logs all nodes in the registry (debugging).
+    /// In strict mode, parses `data` strictly and logs a warning with the raw bytes if
+    /// any part of it is malformed or of an unknown type. Always `Ok` when not strict.
+    fn check_strict(&self, data: &[u8], src: &SocketAddr) -> Result<(), MdnsError> {
+        if !self.is_strict() {
+            return Ok(());
+        }
+        DnsPacket::parse_strict(data).map(|_| ()).map_err(|err| {
+            eprintln!(
+                "(STRICT) Malformed packet from {}: {}; raw bytes: {}",
+                src,
+                err,
+                hex_bytes(data)
+            );
+            MdnsError::PacketError(err.to_string())
+        })
+    }
+
+    /// Parses and processes a raw response. Unlike the listen loop, a packet rejected by
+    /// strict parsing is not processed and the parse error is returned, which lets
+    /// tests and diagnostics observe why a peer's records were dropped.
+    pub async fn process_response_strict(&self, data: &[u8], src: &SocketAddr) -> Result<(), MdnsError> {
+        self.check_strict(data, src)?;
+        let packet = DnsPacket::parse(data).map_err(|err| MdnsError::PacketError(err.to_string()))?;
+        self.process_response(&packet, src).await;
+        Ok(())
+    }
+
+    /// Dispatches a received packet to the response or query handler.
+    pub async fn handle_packet(&self, packet: &DnsPacket, src: &SocketAddr) {
+        let is_response = packet.dns_flags().response;
+        if is_response {
+            self.process_response(packet, src).await;
+        } else {
+            self.process_query(packet, src).await;
+        }
+    }
+
+    /// Periodically logs all nodes in the registry (debugging), every
+    /// `registry_print_interval` or `DEFAULT_REGISTRY_PRINT_INTERVAL` if unset.
     pub async fn print_node_registry(&self) {
+        let interval = self.registry_print_interval().unwrap_or(DEFAULT_REGISTRY_PRINT_INTERVAL);
         loop {
-            time::sleep(Duration::from_secs(10)).await;
+            time::sleep(interval).await;
             let nodes = self.registry.list_nodes().await;
             println!("(NODE REGISTRY) Nodes: {:?}", nodes);
         }
     }
 
-    /// Spawns tasks: (1) periodically advertise, (2) periodically query, (3) listen, (4) debug-print.
+    /// Spawns tasks: (1) periodically advertise, (2) periodically query, (3) listen, and
+    /// (4) debug-print the registry if `set_registry_print_interval` enabled it.
     pub async fn run(
         self: &Arc<Self>,
         query_service_type: String,
@@ -344,89 +1272,201 @@ impl MdnsService {
             }
         });
 
-        // Print registry
+        // Print registry, only when opted in
+        if self.registry_print_interval().is_some() {
+            let task = tokio::spawn(async move {
+                registry_service.print_node_registry().await;
+            });
+            if let Some(previous) = self.registry_printer.lock().unwrap().replace(task) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// Receives one packet and answers it if it is a query for a local service.
+    /// Responses from other nodes are dropped without touching the registry.
+    pub async fn respond_once(&self) -> Result<(), MdnsError> {
+        if let Some((packet, src)) = self.recv_packet().await? {
+            if !packet.dns_flags().response {
+                self.process_query(&packet, &src).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lightweight alternative to `run` for devices that only need to be found:
+    /// spawns a single task that loops on `respond_once`. Nothing is advertised
+    /// unprompted, no queries are sent and peers are never learned.
+    pub async fn run_responder_only(self: &Arc<Self>) {
+        let responder = Arc::clone(self);
         tokio::spawn(async move {
-            registry_service.print_node_registry().await;
+            loop {
+                if let Err(err) = responder.respond_once().await {
+                    eprintln!("(RESPONDER) Error: {:?}", err);
+                    break;
+                }
+            }
         });
     }
 
     /// Process a response packet: see if it has A/SRV records, update registry accordingly.
     pub async fn process_response(&self, packet: &DnsPacket, src: &SocketAddr) {
+        if !self.source_permitted(src, "response") {
+            return;
+        }
+        // Multicast loopback stays on so several nodes can share a host, which means
+        // our own advertisements come back to us here.
+        if self.is_self_originated(packet, src).await {
+            println!("(SELF) Ignoring our own packet from {}", src);
+            return;
+        }
+        let origin = self.origin().await;
+
         println!("Packet : {:?}", packet);
+        self.metrics.record_response_processed();
+
+        // With an interest filter, only SRV records of wanted types and the A records
+        // of the nodes they point at are processed.
+        let interest_filter = self.interest_filter.read().await.clone();
+        let wants_srv = |name: &DnsName| match &interest_filter {
+            Some(filter) => filter.contains(&normalize_service_type(&extract_service_type(&name.to_string()))),
+            None => true,
+        };
+        let interesting_targets: HashSet<String> = packet
+            .answers
+            .iter()
+            .filter(|record| wants_srv(record.name()))
+            .filter_map(|record| record.as_srv())
+            .map(|srv| normalize_service_type(&srv.target.to_string()))
+            .collect();
+        let is_interesting = |record: &DnsRecord| match record {
+            _ if interest_filter.is_none() => true,
+            DnsRecord::A { .. } | DnsRecord::AAAA { .. } => {
+                interesting_targets.contains(&normalize_service_type(&record.name().to_string()))
+            }
+            DnsRecord::SRV { .. } => wants_srv(record.name()),
+            _ => false,
+        };
 
-        // If it's IPv4
-        if let SocketAddr::V4(src_addr) = src {
-            for answer in &packet.answers {
-                match answer {
-                    // If there's an A record => we discover a node's IP
-                    DnsRecord::A { name, ip, ttl } => {
-                        let ip_address = Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]);
-                        println!(
-                            "(DISCOVERY) Discovered node: {} -> {} <=> {}",
-                            name,
-                            ip_address,
-                            src_addr.ip()
-                        );
-
-                        // Add/Update node
-                        if let Err(e) =
-                            self.add_node_to_registry(&name.to_string(), &src_addr.ip().to_string(), Some(*ttl)).await
-                        {
-                            eprintln!("(DISCOVERY) Failed to add node: {:?}", e);
-                        }
+        // Responders usually put the address records of SRV targets in the additional
+        // section to save the querier a round trip.
+        let additional_addresses = packet
+            .additionals
+            .iter()
+            .filter(|record| matches!(record, DnsRecord::A { .. } | DnsRecord::AAAA { .. }));
+
+        // TXT records carry service metadata and signatures, keyed by service name.
+        let txt_by_service: HashMap<String, &[u8]> = packet
+            .answers
+            .iter()
+            .chain(&packet.additionals)
+            .filter_map(|record| Some((normalize_service_type(&record.name().to_string()), record.as_txt()?)))
+            .collect();
+        let signature_verifier = self.signature_verifier.lock().unwrap().clone();
+        let require_signatures = self.require_signatures();
+
+        // Dual-stack sockets report IPv4 peers as IPv4-mapped IPv6 addresses.
+        let src_ip = normalize_source_ip(src.ip());
+        for answer in packet.answers.iter().chain(additional_addresses).filter(|record| is_interesting(record)) {
+            match answer {
+                // If there's an A record => we discover a node's IP
+                DnsRecord::A { name, ip, .. } => {
+                    let Some(node) = NodeRecord::from_a(answer, *src) else {
+                        continue;
+                    };
+                    // Another host claiming our name: count the conflict, but keep our
+                    // own node record pointing at our addresses.
+                    if node.id.eq_ignore_ascii_case(&origin) {
+                        self.metrics.record_conflict_detected();
+                        eprintln!("(CONFLICT) {} claims our name {} at {}", src_ip, name, Ipv4Addr::from(*ip));
+                        continue;
+                    }
+                    println!(
+                        "(DISCOVERY) Discovered node: {} -> {} <=> {}",
+                        name,
+                        Ipv4Addr::from(*ip),
+                        src_ip
+                    );
 
-                        // Send an event
-                        let _ = self.event_sender.send(MdnsEvent::Discovered(answer.clone()));
+                    // Add/Update node
+                    if let Err(e) = self.add_node_to_registry(&node.id, &node.ip_address, node.ttl).await {
+                        eprintln!("(DISCOVERY) Failed to add node: {:?}", e);
                     }
 
-                    // [NEW] If there's an SRV record => we discover a node's service
-                    DnsRecord::SRV {
-                        name,
-                        ttl,
-                        port,
-                        priority,
-                        weight,
-                        target,
-                    } => {
-                        println!(
-                            "(DISCOVERY) Discovered service: {} => node: {}, port: {}",
-                            name, target, port
-                        );
-                        // Example: name = "MyLaptop.local._myDefault._tcp.local."
-                        // target = "MyLaptop.local."
-
-                        // We'll create a ServiceRecord that matches this SRV
-                        let srv_id = name.to_string();
-                        let srv_origin = target.to_string().trim_end_matches('.').to_string();
-
-                        let service_record = ServiceRecord {
-                            id: srv_id.clone(),
-                            service_type: extract_service_type(&srv_id), // see helper below
-                            port: *port,
-                            ttl: Some(*ttl),
-                            origin: srv_origin.clone(),
-                            priority: Some(*priority),
-                            weight: Some(*weight),
-                            node_id: srv_origin.clone(),
-                        };
-
-                        // Add that to our registry
-                        if let Err(e) = self.registry.add_service(service_record.clone()).await {
-                            eprintln!("(DISCOVERY) Failed to add service: {:?}", e);
-                        } else {
-                            // Link it to the node
-                            if let Err(e) = self.link_service_to_node(&service_record).await {
-                                eprintln!("(DISCOVERY) Failed to link service to node: {:?}", e);
-                            }
-                        }
+                    // Send an event
+                    self.emit_event(MdnsEvent::Discovered(answer.clone()));
+                }
+
+                // An AAAA record only gives an address to nodes without an IPv4 one
+                DnsRecord::AAAA { name, ip, ttl } => {
+                    let ip_address = Ipv6Addr::from(*ip);
+                    let node_id = name.to_string().trim_end_matches('.').to_string();
+                    let has_ipv4 = self.registry.get_node(&node_id).await.is_some_and(|node| {
+                        node.ip_address.parse::<Ipv4Addr>().is_ok_and(|ip| !ip.is_unspecified())
+                    });
+                    if has_ipv4 {
+                        continue;
+                    }
+                    println!("(DISCOVERY) Discovered node: {} -> {}", name, ip_address);
+
+                    if let Err(e) =
+                        self.add_node_to_registry(&node_id, &ip_address.to_string(), Some(*ttl)).await
+                    {
+                        eprintln!("(DISCOVERY) Failed to add node: {:?}", e);
+                    }
+                    self.emit_event(MdnsEvent::Discovered(answer.clone()));
+                }
 
-                        // Optional event
-                        let _ = self.event_sender.send(MdnsEvent::Discovered(answer.clone()));
+                // [NEW] If there's an SRV record => we discover a node's service
+                DnsRecord::SRV { name, port, target, .. } => {
+                    let Some(mut service_record) = ServiceRecord::from_srv(answer) else {
+                        continue;
+                    };
+                    println!(
+                        "(DISCOVERY) Discovered service: {} => node: {}, port: {}",
+                        name, target, port
+                    );
+                    let srv_id = service_record.id.clone();
+
+                    if let Some(txt_data) = txt_by_service.get(&normalize_service_type(&srv_id)) {
+                        let (txt, signature) = decode_service_txt(txt_data);
+                        service_record.txt = txt;
+                        service_record.signature = signature;
+                    }
+                    service_record.authenticated = match (&service_record.signature, &signature_verifier) {
+                        (Some(signature), Some(verifier)) => verifier.verify(&service_record.signing_bytes(), signature),
+                        _ => false,
+                    };
+                    if require_signatures && !service_record.authenticated {
+                        eprintln!("(AUTH) Dropping service {} without a valid signature", srv_id);
+                        continue;
                     }
 
-                    // Others (e.g. PTR, AAAA, etc.)
-                    _ => {}
+                    // Add that to our registry
+                    let is_new = self.registry.get_service(&srv_id).await.is_none();
+                    if let Err(e) = self.registry.add_service(service_record.clone()).await {
+                        eprintln!("(DISCOVERY) Failed to add service: {:?}", e);
+                    } else {
+                        // The type is no longer known to be empty.
+                        self.negative_cache
+                            .lock()
+                            .unwrap()
+                            .remove(&normalize_service_type(&service_record.service_type));
+                        if is_new {
+                            self.discovered_since_query.store(true, Ordering::Relaxed);
+                        }
+                        // Link it to the node
+                        if let Err(e) = self.link_service_to_node(&service_record).await {
+                            eprintln!("(DISCOVERY) Failed to link service to node: {:?}", e);
+                        }
+                    }
+
+                    // Optional event
+                    self.emit_event(MdnsEvent::Discovered(answer.clone()));
                 }
+
+                // Others (e.g. PTR, AAAA, etc.)
+                _ => {}
             }
         }
 
@@ -435,71 +1475,179 @@ impl MdnsService {
         println!("(REGISTRY) Current nodes: {:?}", updated_nodes);
     }
 
+    /// Returns true if the packet is our own advertisement looped back: it was sent
+    /// from one of our local addresses and announces this node, with an A record for
+    /// our origin or an SRV record targeting it. The same records from any other
+    /// source are a peer's claim on our name and are left to conflict detection.
+    async fn is_self_originated(&self, packet: &DnsPacket, src: &SocketAddr) -> bool {
+        let src_ip = normalize_source_ip(src.ip());
+        if !self.address_provider.local_addresses().contains(&src_ip) {
+            return false;
+        }
+        let origin = self.origin().await;
+        let is_origin = |name: &DnsName| name.to_string().trim_end_matches('.').eq_ignore_ascii_case(&origin);
+
+        packet.answers.iter().any(|record| match record {
+            DnsRecord::A { .. } => is_origin(record.name()),
+            _ => record.as_srv().is_some_and(|srv| is_origin(&srv.target)),
+        })
+    }
+
     /// Process a query packet: see if we have a matching service type, respond accordingly.
     async fn process_query(&self, packet: &DnsPacket, src: &SocketAddr) {
+        if !self.source_permitted(src, "query") {
+            return;
+        }
+        self.metrics.record_query_received();
+        if src.port() != MDNS_PORT {
+            if let Some(response_packet) = self.create_legacy_unicast_response(packet).await {
+                match self.send_unicast(&response_packet, src).await {
+                    Ok(()) => self.metrics.record_query_answered(),
+                    Err(err) => eprintln!("(QUERY->UNICAST) Failed to send legacy response to {}: {:?}", src, err),
+                }
+            }
+            return;
+        }
+
         for question in &packet.questions {
-            if question.qtype == 12 && question.qclass == 1 {
-                let requested_service = question.qname.labels.join(".");
-                let all_services = self.registry.list_services().await;
-
-                println!("Requested Service : {}", requested_service);
-
-                // Find all services whose `id` ends with the requested service
-                let matching_services: Vec<_> = all_services
-                    .into_iter()
-                    .filter(|s| {
-                        s.id.trim_end_matches('.')
-                            .ends_with(&requested_service.trim_end_matches('.'))
-                    })
-                    .collect();
-
-                if matching_services.is_empty() {
-                    println!("(QUERY) No matching service for '{}'", requested_service);
-                    continue;
+            if let Some(response_packet) = self.create_query_response(question).await {
+                // Send the response
+                match self.send_packet(&response_packet).await {
+                    Ok(()) => self.metrics.record_query_answered(),
+                    Err(err) => eprintln!("(QUERY->RESP) Failed to send response: {:?}", err),
                 }
+            }
+        }
+    }
 
-                let mut response_packet = DnsPacket::new();
-                response_packet.flags = 0x8400;
+    /// Builds a single response to a legacy (non-5353 source port) query.
+    ///
+    /// The response echoes the query id and questions, and caps every TTL at
+    /// `LEGACY_UNICAST_MAX_TTL` as required by RFC 6762 §6.7.
+    pub async fn create_legacy_unicast_response(&self, packet: &DnsPacket) -> Option<DnsPacket> {
+        let mut response_packet = DnsPacket::new();
+        response_packet.id = packet.id;
+        response_packet.set_dns_flags(DnsFlags::authoritative_response());
 
-                let origin = {
-                    let origin_lock = self.origin.read().await;
-                    origin_lock
-                        .clone()
-                        .unwrap_or_else(|| "UnknownOrigin.local".to_string())
-                };
+        for question in &packet.questions {
+            if let Some(answer) = self.create_query_response(question).await {
+                response_packet.questions.push(question.clone());
+                response_packet.answers.extend(answer.answers);
+            }
+        }
 
-                // Build answers
-                for service in matching_services {
-                    response_packet.answers.push(DnsRecord::PTR {
-                        name: DnsName::new(&service.service_type).unwrap(),
-                        ttl: service.ttl.unwrap_or(120),
-                        ptr_name: DnsName::new(&service.id).unwrap(),
-                    });
+        if response_packet.answers.is_empty() {
+            return None;
+        }
 
-                    response_packet.answers.push(DnsRecord::SRV {
-                        name: DnsName::new(&service.id).unwrap(),
-                        ttl: service.ttl.unwrap_or(120),
-                        priority: service.priority.unwrap_or(0),
-                        weight: service.weight.unwrap_or(0),
-                        port: service.port,
-                        target: DnsName::new(&origin).unwrap(),
-                    });
+        for record in &mut response_packet.answers {
+            cap_ttl(record, LEGACY_UNICAST_MAX_TTL);
+        }
+        Some(response_packet)
+    }
 
-                    if let SocketAddr::V4(addr) = src {
-                        response_packet.answers.push(DnsRecord::A {
-                            name: DnsName::new(&origin).unwrap(),
-                            ttl: service.ttl.unwrap_or(120),
-                            ip: addr.ip().octets(),
-                        });
-                    }
-                }
+    /// Builds the response to a single question, or `None` if nothing matches.
+    ///
+    /// PTR and ANY questions are answered with the PTR, SRV, TXT and A records of
+    /// every matching service, plus SVCB for services registered with ALPN protocols.
+    /// A question for one specific type (SRV, TXT, A or SVCB) gets
+    /// only records of that type. An A or ANY question may also name a host, in which
+    /// case the services it targets match. Class IN and ANY are accepted.
+    ///
+    /// Proxied services are answered with the proxied node as SRV target and its stored
+    /// address in the A record; other services with our advertised addresses.
+    pub async fn create_query_response(&self, question: &DnsQuestion) -> Option<DnsPacket> {
+        if !matches!(question.dns_class(), Some(DnsClass::IN | DnsClass::ANY)) {
+            return None;
+        }
+        // `None` answers with every record type.
+        let wanted_type = match question.dns_type()? {
+            DnsType::PTR | DnsType::ANY => None,
+            dns_type @ (DnsType::SRV | DnsType::TXT | DnsType::A | DnsType::SVCB) => Some(dns_type),
+            DnsType::AAAA => return None,
+        };
+        let matches_host = matches!(question.dns_type(), Some(DnsType::A | DnsType::ANY));
+
+        let requested_service = question.qname.labels.join(".");
+        let all_services = self.registry.list_services().await;
+
+        println!("Requested Service : {}", requested_service);
+
+        let origin = self.origin().await;
+
+        // Find all services whose `id` ends with the requested service, or for host
+        // questions, whose SRV target is the requested host.
+        let requested = requested_service.trim_end_matches('.');
+        let matching_services: Vec<_> = all_services
+            .into_iter()
+            .filter(|s| {
+                let host = if s.proxy { &s.node_id } else { &origin };
+                s.id.trim_end_matches('.').ends_with(requested)
+                    || (matches_host && host.trim_end_matches('.').eq_ignore_ascii_case(requested))
+            })
+            .collect();
+
+        if matching_services.is_empty() {
+            println!("(QUERY) No matching service for '{}'", requested_service);
+            return None;
+        }
 
-                // Send the response
-                if let Err(err) = self.send_packet(&response_packet).await {
-                    eprintln!("(QUERY->RESP) Failed to send response: {:?}", err);
-                }
+        let mut response_packet = DnsPacket::new();
+        response_packet.set_dns_flags(DnsFlags::authoritative_response());
+
+        // Build answers
+        let local_ips = self.advertised_addresses();
+        for service in matching_services {
+            let (target, addresses) = if service.proxy {
+                let address = self
+                    .registry
+                    .get_node(&service.node_id)
+                    .await
+                    .and_then(|node| node.ip_address.parse::<Ipv4Addr>().ok());
+                (service.node_id.clone(), address.into_iter().collect())
+            } else {
+                (origin.clone(), local_ips.clone())
+            };
+
+            let Some((service_type, id, target)) = service_names(&service, &target) else {
+                continue;
+            };
+
+            response_packet.answers.push(DnsRecord::PTR {
+                name: service_type,
+                ttl: service.ttl.unwrap_or(120),
+                ptr_name: id.clone(),
+            });
+
+            response_packet
+                .answers
+                .extend(self.service_svcb_record(&service, &id, &target, &addresses));
+            response_packet.answers.push(DnsRecord::SRV {
+                name: id,
+                ttl: service.ttl.unwrap_or(120),
+                priority: service.priority.unwrap_or(0),
+                weight: service.weight.unwrap_or(0),
+                port: service.port,
+                target: target.clone(),
+            });
+            response_packet.answers.extend(service_txt_record(&service));
+
+            for ip in addresses {
+                response_packet.answers.push(DnsRecord::A {
+                    name: target.clone(),
+                    ttl: service.ttl.unwrap_or(120),
+                    ip: ip.octets(),
+                });
             }
         }
+
+        if let Some(wanted_type) = wanted_type {
+            response_packet.answers.retain(|record| record.dns_type() == wanted_type);
+        }
+        if response_packet.answers.is_empty() {
+            return None;
+        }
+        Some(response_packet)
     }
 
     /// Adds or updates a NodeRecord in the registry. (Mostly used for discovered A records.)
@@ -516,6 +1664,7 @@ impl MdnsService {
 
         // If there's a conflict
         if let Some(conflict) = nodes.iter().find(|n| n.ip_address == ip_address && n.id != normalized_id) {
+            self.metrics.record_conflict_detected();
             return Err(MdnsError::Generic(format!(
                 "IP conflict: {} is already assigned to {}",
                 ip_address, conflict.id
@@ -527,12 +1676,10 @@ impl MdnsService {
             if existing_node.ip_address != ip_address {
                 existing_node.ip_address = ip_address.clone();
                 existing_node.ttl = ttl;
-                // re-save
-                self.registry
-                    .add_node(existing_node.clone())
-                    .await
-                    .map_err(|e| MdnsError::Generic(e.to_string()))?;
             }
+            // re-save so the node counts as recently seen
+            existing_node.last_seen = SystemTime::now();
+            self.store_node(existing_node.clone()).await?;
         } else {
             // Create new node
             println!("(DISCOVERY) Adding new node: {} with IP {}", normalized_id, ip_address);
@@ -542,42 +1689,150 @@ impl MdnsService {
                 ip_address,
                 ttl,
                 services: Vec::new(),
+                last_seen: SystemTime::now(),
             };
-            self.registry
-                .add_node(new_node)
-                .await
-                .map_err(|e| MdnsError::Generic(e.to_string()))?;
+            self.store_node(new_node).await?;
+            self.metrics.record_node_discovered();
+            self.discovered_since_query.store(true, Ordering::Relaxed);
         }
 
         Ok(())
     }
+
+    /// Stores a node and reports any node evicted to make room for it as `Expired`.
+    async fn store_node(&self, node: NodeRecord) -> Result<(), MdnsError> {
+        for evicted in self.registry.add_node(node).await? {
+            println!("(REGISTRY) Node limit reached, evicted {}", evicted.id);
+            if let Ok(name) = DnsName::new(&evicted.id) {
+                let ip = evicted.ip_address.parse().unwrap_or(Ipv4Addr::UNSPECIFIED);
+                self.emit_event(MdnsEvent::Expired(DnsRecord::A {
+                    name,
+                    ttl: 0,
+                    ip: ip.octets(),
+                }));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates a node origin and returns it in canonical form (no trailing dot, the
+/// same form node ids are stored in). Origins without a `.local` suffix are accepted
+/// with a warning.
+fn normalize_origin(origin: &str) -> Result<String, MdnsError> {
+    let trimmed = origin.trim().trim_end_matches('.');
+    if trimmed.is_empty() {
+        return Err(MdnsError::InvalidName("origin is empty".to_string()));
+    }
+    if trimmed.len() > 253 {
+        return Err(MdnsError::InvalidName(format!(
+            "origin is {} characters, the maximum is 253",
+            trimmed.len()
+        )));
+    }
+
+    for label in trimmed.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(MdnsError::InvalidName(format!(
+                "label '{}' in origin '{}' must be 1-63 characters",
+                label, trimmed
+            )));
+        }
+        if label.starts_with('-')
+            || label.ends_with('-')
+            || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(MdnsError::InvalidName(format!(
+                "label '{}' in origin '{}' contains illegal characters",
+                label, trimmed
+            )));
+        }
+    }
+
+    if !trimmed.to_ascii_lowercase().ends_with(".local") {
+        eprintln!("(INIT) Origin '{}' does not end with .local", trimmed);
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Checks that `service_type` has the DNS-SD form `_app._tcp.local` or
+/// `_app._udp.local` (trailing dot optional), where `app` is letters, digits and
+/// inner hyphens with at least one letter.
+fn validate_service_type(service_type: &str) -> Result<(), MdnsError> {
+    let invalid = |reason: &str| {
+        MdnsError::InvalidName(format!(
+            "service type '{}' {}; expected the form _app._tcp.local.",
+            service_type, reason
+        ))
+    };
+    let labels: Vec<&str> = service_type.trim_end_matches('.').split('.').collect();
+    let [app, proto, domain] = labels.as_slice() else {
+        return Err(invalid("must have exactly three labels"));
+    };
+
+    let app = app.strip_prefix('_').ok_or_else(|| invalid("must start with an underscore"))?;
+    if app.is_empty()
+        || app.starts_with('-')
+        || app.ends_with('-')
+        || !app.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        || !app.chars().any(|c| c.is_ascii_alphabetic())
+    {
+        return Err(invalid("has an invalid application name"));
+    }
+    if !proto.eq_ignore_ascii_case("_tcp") && !proto.eq_ignore_ascii_case("_udp") {
+        return Err(invalid("must use _tcp or _udp as the protocol"));
+    }
+    if !domain.eq_ignore_ascii_case("local") {
+        return Err(invalid("must be in the .local domain"));
+    }
+    Ok(())
 }
 
-/// Helper to get the local IPv4 address, e.g. 192.168.x.x
-fn get_local_ipv4() -> Option<Ipv4Addr> {
-    use std::net::{IpAddr, UdpSocket};
+/// Lowers a record's TTL to `max_ttl` if it is above it.
+fn cap_ttl(record: &mut DnsRecord, max_ttl: u32) {
+    record.set_ttl(record.ttl().min(max_ttl));
+}
 
-    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
-    socket.connect("8.8.8.8:80").ok()?;
-    if let Ok(local_addr) = socket.local_addr() {
-        if let IpAddr::V4(ip) = local_addr.ip() {
-            return Some(ip);
+/// The PTR owner, service instance and SRV target names for `service`, or `None`
+/// (with a warning) if any of them is not a valid DNS name.
+fn service_names(service: &ServiceRecord, target: &str) -> Option<(DnsName, DnsName, DnsName)> {
+    let names = DnsName::try_new(&service.service_type)
+        .and_then(|service_type| Ok((service_type, DnsName::try_new(&service.id)?, DnsName::try_new(target)?)));
+    match names {
+        Ok(names) => Some(names),
+        Err(err) => {
+            eprintln!("(RECORDS) Skipping service {}: {}", service.id, err);
+            None
         }
     }
-    None
 }
 
-/// [NEW] Example function to derive "service type" from an SRV record's name, e.g.
-/// If `srv_id = "MyLaptop.local._myDefault._tcp.local."`,
-/// we parse out `_myDefault._tcp.local.` as the service type.
-fn extract_service_type(srv_id: &str) -> String {
-    // A simple approach: find the first dot from the left after the node portion.
-    // But many ways to do it. This is just an example logic.
-    if let Some(pos) = srv_id.find("._") {
-        // return everything from that '.' onward
-        // e.g. "._myDefault._tcp.local."
-        return srv_id[pos+1..].to_string(); 
-    }
-    // fallback
-    srv_id.to_string()
+/// Builds the TXT record advertising a service's metadata and signature, if it has any.
+fn service_txt_record(service: &ServiceRecord) -> Option<DnsRecord> {
+    if service.txt.is_empty() && service.signature.is_none() {
+        return None;
+    }
+    Some(DnsRecord::TXT {
+        name: DnsName::new(&service.id).ok()?,
+        ttl: service.ttl.unwrap_or(120),
+        txt_data: encode_service_txt(&service.txt, service.signature.as_ref()),
+    })
 }
+
+/// Maps an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) back to IPv4.
+pub(crate) fn normalize_source_ip(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(IpAddr::V6(ipv6), IpAddr::V4),
+        ip => ip,
+    }
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Canonical form used when comparing DNS names: no trailing dot, lowercase.
+pub(crate) fn normalize_service_type(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
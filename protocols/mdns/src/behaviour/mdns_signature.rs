@@ -0,0 +1,288 @@
+// protocols\mdns\src\behaviour\mdns_signature.rs
+use identity::{PKIError, PKITraits, VerificationBudget};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// TXT key carrying the signing algorithm (`PKITraits::key_type`).
+const ALGORITHM_KEY: &str = "_alg";
+/// TXT key prefix for the hex-encoded public key chunks (`_pk0`, `_pk1`, ...).
+const PUBLIC_KEY_PREFIX: &str = "_pk";
+/// TXT key prefix for the hex-encoded signature chunks (`_sig0`, `_sig1`, ...).
+const SIGNATURE_PREFIX: &str = "_sig";
+/// Hex characters per chunk, keeping each `key=value` string under the 255-byte
+/// TXT string limit.
+const CHUNK_LEN: usize = 200;
+
+/// Signature checks per second a `SignatureVerifier` allows unless given a budget.
+pub const DEFAULT_VERIFICATIONS_PER_SECOND: u32 = 50;
+
+/// `VerificationBudget::verify_any` for one algorithm: (budget, message, signature,
+/// candidate keys).
+type VerifyAnyFn = fn(&VerificationBudget, &[u8], &[u8], &[&[u8]]) -> Result<Option<usize>, PKIError>;
+
+/// A signature over `ServiceRecord::signing_bytes` and the key that made it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceSignature {
+    /// The signer's algorithm, as returned by `PKITraits::key_type`.
+    pub algorithm: String,
+    /// The signer's public key, as returned by `get_public_key_raw_bytes`.
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+/// Authenticates service signatures made with one algorithm by a set of trusted keys.
+///
+/// The public key advertised alongside a signature is only used to pick which trusted
+/// key to check against: anyone can sign a forged record with a key of their own, so
+/// a signature by a key outside `trusted_keys` is never valid.
+///
+/// Every check is charged to a `VerificationBudget`, shared by clones of the verifier,
+/// so a flood of signed advertisements cannot monopolise the CPU. Signatures arriving
+/// once the budget is spent are treated as invalid.
+#[derive(Debug, Clone)]
+pub struct SignatureVerifier {
+    algorithm: String,
+    trusted_keys: Vec<Vec<u8>>,
+    verify_any: VerifyAnyFn,
+    budget: Arc<VerificationBudget>,
+}
+
+impl SignatureVerifier {
+    /// Creates a verifier for signatures made with key pairs of type `K` whose public
+    /// keys, as returned by `get_public_key_raw_bytes`, are in `trusted_keys`.
+    ///
+    /// Checks are limited to `DEFAULT_VERIFICATIONS_PER_SECOND`; see `with_budget`.
+    pub fn of<K: PKITraits<Error = PKIError>>(trusted_keys: Vec<Vec<u8>>) -> Self {
+        Self {
+            algorithm: K::key_type(),
+            trusted_keys,
+            verify_any: VerificationBudget::verify_any::<K>,
+            budget: Arc::new(VerificationBudget::new(DEFAULT_VERIFICATIONS_PER_SECOND)),
+        }
+    }
+
+    /// Charges checks to `budget`, which may be shared with other verifiers.
+    pub fn with_budget(mut self, budget: Arc<VerificationBudget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// The algorithm this verifier accepts.
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    /// The public keys whose signatures authenticate a service.
+    pub fn trusted_keys(&self) -> &[Vec<u8>] {
+        &self.trusted_keys
+    }
+
+    /// Returns true if `signature` was made with this verifier's algorithm by a trusted
+    /// key and verifies over `message`. Malformed keys or signatures, and checks over
+    /// budget, count as invalid.
+    pub fn verify(&self, message: &[u8], signature: &ServiceSignature) -> bool {
+        if signature.algorithm != self.algorithm {
+            return false;
+        }
+        let candidates: Vec<&[u8]> = self
+            .trusted_keys
+            .iter()
+            .filter(|key| **key == signature.public_key)
+            .map(Vec::as_slice)
+            .collect();
+        if candidates.is_empty() {
+            return false;
+        }
+        match (self.verify_any)(&self.budget, message, &signature.signature, &candidates) {
+            Ok(found) => found.is_some(),
+            Err(PKIError::RateLimited(reason)) => {
+                eprintln!("(AUTH) Signature not checked: {}", reason);
+                false
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Encodes TXT entries and an optional signature as DNS-SD TXT rdata: a sequence of
+/// length-prefixed `key=value` strings. Entries too long for one string are skipped.
+pub(crate) fn encode_service_txt(txt: &HashMap<String, Vec<u8>>, signature: Option<&ServiceSignature>) -> Vec<u8> {
+    let mut strings: Vec<Vec<u8>> = txt
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(key, value)| [key.as_bytes(), b"=", value].concat())
+        .collect();
+
+    if let Some(signature) = signature {
+        strings.push(format!("{}={}", ALGORITHM_KEY, signature.algorithm).into_bytes());
+        push_chunks(&mut strings, PUBLIC_KEY_PREFIX, &signature.public_key);
+        push_chunks(&mut strings, SIGNATURE_PREFIX, &signature.signature);
+    }
+
+    let mut data = Vec::new();
+    for string in strings {
+        if string.len() > u8::MAX as usize {
+            eprintln!("(TXT) Skipping entry longer than 255 bytes: {}", string.escape_ascii());
+            continue;
+        }
+        data.push(string.len() as u8);
+        data.extend_from_slice(&string);
+    }
+    data
+}
+
+/// Splits DNS-SD TXT rdata into the service's own entries and, if all of its parts
+/// are present and well formed, the signature.
+///
+/// Keys must be printable ASCII without `=` (RFC 6763 §6.4); entries with any other
+/// key are dropped. Values are kept as raw bytes.
+pub(crate) fn decode_service_txt(data: &[u8]) -> (HashMap<String, Vec<u8>>, Option<ServiceSignature>) {
+    let mut txt = HashMap::new();
+    let mut algorithm = None;
+    let mut public_key_chunks = BTreeMap::new();
+    let mut signature_chunks = BTreeMap::new();
+
+    let mut rest = data;
+    while let Some((&len, tail)) = rest.split_first() {
+        let len = (len as usize).min(tail.len());
+        let (string, tail) = tail.split_at(len);
+        rest = tail;
+
+        let (key, value) = match string.iter().position(|&byte| byte == b'=') {
+            Some(at) => (&string[..at], &string[at + 1..]),
+            None => (string, &[][..]),
+        };
+        if key.is_empty() {
+            continue;
+        }
+        let Some(key) = valid_txt_key(key) else {
+            eprintln!("(TXT) Dropping entry with invalid key: {}", string.escape_ascii());
+            continue;
+        };
+        if key == ALGORITHM_KEY {
+            algorithm = Some(String::from_utf8_lossy(value).into_owned());
+        } else if let Some(index) = chunk_index(key, PUBLIC_KEY_PREFIX) {
+            public_key_chunks.insert(index, String::from_utf8_lossy(value).into_owned());
+        } else if let Some(index) = chunk_index(key, SIGNATURE_PREFIX) {
+            signature_chunks.insert(index, String::from_utf8_lossy(value).into_owned());
+        } else {
+            txt.insert(key.to_string(), value.to_vec());
+        }
+    }
+
+    let signature = algorithm.and_then(|algorithm| {
+        Some(ServiceSignature {
+            algorithm,
+            public_key: join_chunks(public_key_chunks)?,
+            signature: join_chunks(signature_chunks)?,
+        })
+    });
+    (txt, signature)
+}
+
+fn push_chunks(strings: &mut Vec<Vec<u8>>, prefix: &str, bytes: &[u8]) {
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    for (index, chunk) in hex.as_bytes().chunks(CHUNK_LEN).enumerate() {
+        strings.push(format!("{}{}={}", prefix, index, String::from_utf8_lossy(chunk)).into_bytes());
+    }
+}
+
+/// Returns `key` as a string if it is printable ASCII (0x20-0x7E) without `=`.
+fn valid_txt_key(key: &[u8]) -> Option<&str> {
+    if key.iter().all(|&byte| (0x20..=0x7E).contains(&byte) && byte != b'=') {
+        std::str::from_utf8(key).ok()
+    } else {
+        None
+    }
+}
+
+/// Returns the chunk number of keys like `_sig3`.
+fn chunk_index(key: &str, prefix: &str) -> Option<usize> {
+    key.strip_prefix(prefix)?.parse().ok()
+}
+
+/// Reassembles hex chunks numbered `0..n`; `None` if one is missing or not hex.
+fn join_chunks(chunks: BTreeMap<usize, String>) -> Option<Vec<u8>> {
+    if chunks.is_empty() || chunks.keys().copied().ne(0..chunks.len()) {
+        return None;
+    }
+    let hex: String = chunks.into_values().collect();
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_txt_round_trip() {
+        let mut txt = HashMap::new();
+        txt.insert("path".to_string(), b"/api".to_vec());
+        txt.insert("flag".to_string(), Vec::new());
+        let signature = ServiceSignature {
+            algorithm: "RSA".to_string(),
+            public_key: (0..=255).collect(),
+            signature: vec![0xAB; 300],
+        };
+
+        let data = encode_service_txt(&txt, Some(&signature));
+        let (decoded_txt, decoded_signature) = decode_service_txt(&data);
+        assert_eq!(decoded_txt, txt);
+        assert_eq!(decoded_signature, Some(signature));
+
+        let (_, unsigned) = decode_service_txt(&encode_service_txt(&txt, None));
+        assert_eq!(unsigned, None);
+    }
+
+    #[test]
+    fn test_txt_keys_are_validated_and_values_kept_raw() {
+        let mut data = Vec::new();
+        for entry in [&b"ok=line\nbreak\xff"[..], b"bad\nkey=1", b"bell\x07=2", b"path=/api"] {
+            data.push(entry.len() as u8);
+            data.extend_from_slice(entry);
+        }
+
+        let (txt, signature) = decode_service_txt(&data);
+        assert_eq!(signature, None);
+        assert_eq!(txt.len(), 2, "entries with control characters in the key are dropped");
+        assert_eq!(txt["ok"], b"line\nbreak\xff");
+        assert_eq!(txt["path"], b"/api");
+
+        let record = crate::ServiceRecord::builder("node.local._http._tcp.local", "_http._tcp.local")
+            .txt("ok", txt["ok"].clone())
+            .build();
+        assert_eq!(record.txt_value("ok").unwrap(), "line\nbreak\u{FFFD}");
+        assert_eq!(record.txt_value("ok").unwrap().escape_debug().to_string(), "line\\nbreak\u{FFFD}");
+    }
+
+    #[test]
+    fn test_txt_rdata_is_framed_once_on_the_wire() {
+        use crate::{DnsName, DnsPacket, DnsRecord};
+
+        // TXT rdata as a Bonjour or Avahi responder sends it.
+        let rdata = b"\x09txtvers=1\x06path=/\x0bnote=a b c".to_vec();
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::TXT {
+            name: DnsName::new("printer._ipp._tcp.local").unwrap(),
+            ttl: 120,
+            txt_data: rdata.clone(),
+        });
+        let wire = packet.serialize();
+        assert!(wire.ends_with(&[&(rdata.len() as u16).to_be_bytes()[..], &rdata[..]].concat()));
+
+        let parsed = DnsPacket::parse(&wire).unwrap();
+        assert_eq!(parsed.answers[0].as_txt(), Some(&rdata[..]));
+        let (txt, _) = decode_service_txt(parsed.answers[0].as_txt().unwrap());
+        assert_eq!(txt["txtvers"], b"1");
+        assert_eq!(txt["path"], b"/");
+        assert_eq!(txt["note"], b"a b c");
+    }
+}
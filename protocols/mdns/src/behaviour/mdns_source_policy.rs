@@ -0,0 +1,123 @@
+use crate::MdnsError;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// A block of addresses in CIDR notation, such as `192.168.1.0/24` or `fd00::/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNet {
+    /// Creates a network from an address and prefix length. Host bits in `addr` are
+    /// ignored when matching.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self, MdnsError> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max {
+            return Err(MdnsError::Generic(format!(
+                "Prefix length {} is too long for {}",
+                prefix_len, addr
+            )));
+        }
+        Ok(Self { addr, prefix_len })
+    }
+
+    /// Returns true if `ip` lies in this network. IPv4 and IPv6 never match each other.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(net).into(), u32::from(ip).into(), 32, self.prefix_len)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = MdnsError;
+
+    /// Parses `addr/prefix`; a bare address is a single-host network.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MdnsError::Generic(format!("Invalid network: {}", s));
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let prefix_len = prefix_len.unwrap_or(if addr.is_ipv4() { 32 } else { 128 });
+        Self::new(addr, prefix_len)
+    }
+}
+
+/// Compares the top `prefix_len` of `bits` bits of two addresses.
+fn prefix_matches(net: u128, ip: u128, bits: u32, prefix_len: u8) -> bool {
+    let host_bits = bits - u32::from(prefix_len);
+    (net ^ ip).checked_shr(host_bits).unwrap_or(0) == 0
+}
+
+/// Decides which peers' packets `MdnsService` processes, by source address.
+///
+/// A source matching any `deny` network is dropped. Otherwise it is accepted if the
+/// allowlist is empty or one of its networks matches. IPv4-mapped IPv6 sources are
+/// checked as IPv4.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourcePolicy {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl SourcePolicy {
+    /// A policy that accepts every source.
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Adds a network to the allowlist; once it is non-empty, only listed sources pass.
+    pub fn allow(mut self, net: IpNet) -> Self {
+        self.allow.push(net);
+        self
+    }
+
+    /// Adds a network to the denylist, which takes precedence over the allowlist.
+    pub fn deny(mut self, net: IpNet) -> Self {
+        self.deny.push(net);
+        self
+    }
+
+    /// Returns true if packets from `ip` should be processed.
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            ip => ip,
+        };
+        if self.deny.iter().any(|net| net.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|net| net.contains(ip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deny_overrides_allow() {
+        let policy = SourcePolicy::allow_all()
+            .allow("192.168.1.0/24".parse().unwrap())
+            .deny("192.168.1.66".parse().unwrap());
+
+        assert!(policy.permits("192.168.1.20".parse().unwrap()));
+        assert!(policy.permits("::ffff:192.168.1.20".parse().unwrap()));
+        assert!(!policy.permits("192.168.1.66".parse().unwrap()));
+        assert!(!policy.permits("10.0.0.5".parse().unwrap()));
+        assert!(!policy.permits("fe80::1".parse().unwrap()));
+        assert!(SourcePolicy::allow_all().permits("fe80::1".parse().unwrap()));
+        assert!("0.0.0.0/0".parse::<IpNet>().unwrap().contains("8.8.8.8".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+    }
+}
@@ -0,0 +1,30 @@
+// protocols\mdns\src\behaviour\mdns_status.rs
+use crate::behaviour::mdns_backoff::BackoffState;
+use crate::behaviour::mdns_metrics::MdnsMetricsSnapshot;
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+/// A health snapshot of an `MdnsService`, returned by `MdnsService::status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdnsStatus {
+    /// Address the multicast socket is bound to; `None` if it no longer reports one.
+    pub local_addr: Option<SocketAddr>,
+    /// Services in the registry, local and discovered.
+    pub services: usize,
+    /// Services this node offers itself.
+    pub local_services: usize,
+    /// Nodes in the registry, including this one.
+    pub nodes: usize,
+    pub last_advertisement: Option<SystemTime>,
+    pub last_query: Option<SystemTime>,
+    pub last_packet_received: Option<SystemTime>,
+    pub backoff_state: BackoffState,
+    pub metrics: MdnsMetricsSnapshot,
+}
+
+impl MdnsStatus {
+    /// Whether the multicast socket is bound, which is what a readiness probe needs.
+    pub fn is_socket_bound(&self) -> bool {
+        self.local_addr.is_some()
+    }
+}
@@ -1,4 +1,11 @@
+use crate::behaviour::mdns_service::normalize_source_ip;
+use crate::behaviour::mdns_signature::ServiceSignature;
+use crate::DnsRecord;
+use identity::{PKIError, PKITraits};
 use serde::{Serialize, Deserialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
 use std::time::{SystemTime, Duration};
 use registry::Record;
 use std::fmt;
@@ -14,6 +21,234 @@ pub struct ServiceRecord {
     pub priority: Option<u16>,
     pub weight: Option<u16>,
     pub node_id: String, // New field linking the service to the node
+    /// Set when this node answers for the service on behalf of `node_id` (sleep proxy).
+    #[serde(default)]
+    pub proxy: bool,
+    /// DNS-SD TXT key/value metadata. Values are raw bytes as received; use
+    /// `txt_value` for a printable form.
+    #[serde(default)]
+    pub txt: HashMap<String, Vec<u8>>,
+    /// Signature over `signing_bytes`, advertised in reserved TXT entries.
+    #[serde(default)]
+    pub signature: Option<ServiceSignature>,
+    /// Set on discovered services whose signature verified against a trusted key.
+    #[serde(default)]
+    pub authenticated: bool,
+}
+
+/// Tag opening `ServiceRecord::canonical_bytes`, versioning the encoding.
+const CANONICAL_TAG: &[u8] = b"NAUTILUS-SERVICE-RECORD-V1";
+
+impl ServiceRecord {
+    /// Starts building a `ServiceRecord` with the given id and service type.
+    pub fn builder(id: impl Into<String>, service_type: impl Into<String>) -> ServiceRecordBuilder {
+        ServiceRecordBuilder::new(id.into(), service_type.into())
+    }
+
+    /// Builds the record a discovered SRV answer describes: the SRV name is the id,
+    /// the service type is taken from it by `extract_service_type`, and the target
+    /// (without its trailing dot) is the origin. Returns `None` for other records.
+    ///
+    /// TXT metadata, the signature and `authenticated` are left for the caller.
+    pub fn from_srv(record: &DnsRecord) -> Option<ServiceRecord> {
+        let srv = record.as_srv()?;
+        let id = record.name().to_string();
+        Some(
+            ServiceRecord::builder(id.clone(), extract_service_type(&id))
+                .port(srv.port)
+                .ttl(Some(record.ttl()))
+                .origin(srv.target.to_string().trim_end_matches('.'))
+                .priority(srv.priority)
+                .weight(srv.weight)
+                .build(),
+        )
+    }
+
+    /// Returns the TXT value for `key`, with invalid UTF-8 replaced by U+FFFD.
+    ///
+    /// Discovered values come from untrusted peers and may still contain control
+    /// characters; escape them (e.g. with `escape_debug`) before writing to a terminal.
+    pub fn txt_value(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.txt.get(key).map(|value| String::from_utf8_lossy(value))
+    }
+
+    /// Returns a deterministic encoding of every field except `signature` and
+    /// `authenticated`.
+    ///
+    /// After `CANONICAL_TAG`, fields follow in declaration order: strings as a
+    /// big-endian `u32` length and their UTF-8 bytes, integers big-endian, options as
+    /// a `0`/`1` presence byte followed by the value, and `proxy` as one byte. TXT
+    /// entries come last as a `u32` count and then key/value pairs sorted by key,
+    /// each length-prefixed like a string, so the `HashMap` iteration order does not
+    /// matter.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        fn put_str(out: &mut Vec<u8>, value: &str) {
+            put_bytes(out, value.as_bytes());
+        }
+        fn put_bytes(out: &mut Vec<u8>, value: &[u8]) {
+            out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+            out.extend_from_slice(value);
+        }
+
+        let mut out = CANONICAL_TAG.to_vec();
+        put_str(&mut out, &self.id);
+        put_str(&mut out, &self.service_type);
+        out.extend_from_slice(&self.port.to_be_bytes());
+        match self.ttl {
+            Some(ttl) => {
+                out.push(1);
+                out.extend_from_slice(&ttl.to_be_bytes());
+            }
+            None => out.push(0),
+        }
+        put_str(&mut out, &self.origin);
+        for value in [self.priority, self.weight] {
+            match value {
+                Some(value) => {
+                    out.push(1);
+                    out.extend_from_slice(&value.to_be_bytes());
+                }
+                None => out.push(0),
+            }
+        }
+        put_str(&mut out, &self.node_id);
+        out.push(u8::from(self.proxy));
+
+        let txt: BTreeMap<&String, &Vec<u8>> = self.txt.iter().collect();
+        out.extend_from_slice(&(txt.len() as u32).to_be_bytes());
+        for (key, value) in txt {
+            put_str(&mut out, key);
+            put_bytes(&mut out, value);
+        }
+        out
+    }
+
+    /// Returns the message signed for an advertisement: `canonical_bytes` of only what
+    /// a peer can rebuild from the SRV and TXT records, with trailing dots trimmed.
+    /// The TTL, `node_id` and `proxy` are not covered.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let trim = |name: &str| name.trim_end_matches('.').to_string();
+        ServiceRecord {
+            id: trim(&self.id),
+            service_type: trim(&self.service_type),
+            port: self.port,
+            ttl: None,
+            origin: trim(&self.origin),
+            priority: Some(self.priority.unwrap_or(0)),
+            weight: Some(self.weight.unwrap_or(0)),
+            node_id: trim(&self.origin),
+            proxy: false,
+            txt: self.txt.clone(),
+            signature: None,
+            authenticated: false,
+        }
+        .canonical_bytes()
+    }
+
+    /// Signs `signing_bytes` with `keypair` and stores the result in `signature`.
+    pub fn sign<K: PKITraits<Error = PKIError>>(&mut self, keypair: &K) -> Result<(), PKIError> {
+        self.signature = Some(ServiceSignature {
+            algorithm: K::key_type(),
+            public_key: keypair.get_public_key_raw_bytes(),
+            signature: keypair.sign(&self.signing_bytes())?,
+        });
+        Ok(())
+    }
+}
+
+/// Builder for `ServiceRecord`.
+///
+/// Priority and weight default to `0`, and `node_id` follows `origin` unless set explicitly.
+#[derive(Debug, Clone)]
+pub struct ServiceRecordBuilder {
+    id: String,
+    service_type: String,
+    port: u16,
+    ttl: Option<u32>,
+    origin: String,
+    priority: u16,
+    weight: u16,
+    node_id: Option<String>,
+    proxy: bool,
+    txt: HashMap<String, Vec<u8>>,
+}
+
+impl ServiceRecordBuilder {
+    fn new(id: String, service_type: String) -> Self {
+        Self {
+            id,
+            service_type,
+            port: 0,
+            ttl: None,
+            origin: String::new(),
+            priority: 0,
+            weight: 0,
+            node_id: None,
+            proxy: false,
+            txt: HashMap::new(),
+        }
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn ttl(mut self, ttl: Option<u32>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    pub fn priority(mut self, priority: u16) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn weight(mut self, weight: u16) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Overrides the node this service is linked to (defaults to `origin`).
+    pub fn node_id(mut self, node_id: impl Into<String>) -> Self {
+        self.node_id = Some(node_id.into());
+        self
+    }
+
+    /// Marks the service as proxied on behalf of `node_id`.
+    pub fn proxy(mut self, proxy: bool) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Adds a TXT entry, replacing any earlier value for `key`.
+    pub fn txt(mut self, key: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.txt.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn build(self) -> ServiceRecord {
+        let node_id = self.node_id.unwrap_or_else(|| self.origin.clone());
+        ServiceRecord {
+            id: self.id,
+            service_type: self.service_type,
+            port: self.port,
+            ttl: self.ttl,
+            origin: self.origin,
+            priority: Some(self.priority),
+            weight: Some(self.weight),
+            node_id,
+            proxy: self.proxy,
+            txt: self.txt,
+            signature: None,
+            authenticated: false,
+        }
+    }
 }
 
 impl Record for ServiceRecord {
@@ -29,7 +264,7 @@ impl fmt::Display for ServiceRecord {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "ServiceRecord {{ id: {}, service_type: {}, port: {}, ttl: {:?}, origin: {}, priority: {:?}, weight: {:?}, node_id: {} }}",
+            "ServiceRecord {{ id: {}, service_type: {}, port: {}, ttl: {:?}, origin: {}, priority: {:?}, weight: {:?}, node_id: {}, proxy: {} }}",
             self.id,
             self.service_type,
             self.port,
@@ -37,7 +272,8 @@ impl fmt::Display for ServiceRecord {
             self.origin,
             self.priority,
             self.weight,
-            self.node_id
+            self.node_id,
+            self.proxy
         )
     }
 }
@@ -48,6 +284,33 @@ pub struct NodeRecord {
     pub ip_address: String,
     pub ttl: Option<u32>,
     pub services: Vec<String>, // New field listing services offered by the node
+    /// When the node was last stored or refreshed; the least recently seen node is
+    /// evicted first once the registry reaches its node limit.
+    #[serde(default = "SystemTime::now")]
+    pub last_seen: SystemTime,
+}
+
+impl NodeRecord {
+    /// Builds the node a discovered A answer describes, received from `src`.
+    ///
+    /// The record's own address is the one advertised, which for a proxied or
+    /// additional-section record need not be the sender's. Only an unspecified
+    /// address falls back to an IPv4 source (including an IPv4-mapped IPv6 one).
+    /// Returns `None` for other records.
+    pub fn from_a(record: &DnsRecord, src: SocketAddr) -> Option<NodeRecord> {
+        let record_ip = record.as_a()?;
+        let ip_address = match normalize_source_ip(src.ip()) {
+            IpAddr::V4(src_ipv4) if record_ip.is_unspecified() => src_ipv4,
+            _ => record_ip,
+        };
+        Some(NodeRecord {
+            id: record.name().to_string().trim_end_matches('.').to_string(),
+            ip_address: ip_address.to_string(),
+            ttl: Some(record.ttl()),
+            services: Vec::new(),
+            last_seen: SystemTime::now(),
+        })
+    }
 }
 
 impl Record for NodeRecord {
@@ -60,3 +323,137 @@ impl Record for NodeRecord {
             .map(|ttl_secs| SystemTime::now() + Duration::from_secs(ttl_secs.into()))
     }
 }
+
+/// Derives the service type from a service instance name: everything after the
+/// instance label, e.g. `_myDefault._tcp.local.` from
+/// `MyLaptop.local._myDefault._tcp.local.`. A name without a `._` is returned whole.
+pub(crate) fn extract_service_type(srv_id: &str) -> String {
+    match srv_id.find("._") {
+        Some(pos) => srv_id[pos + 1..].to_string(),
+        None => srv_id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults_match_hand_constructed() {
+        let built = ServiceRecord::builder("node.local._http._tcp.local.", "_http._tcp.local.")
+            .port(8080)
+            .ttl(Some(120))
+            .origin("node.local")
+            .build();
+
+        let manual = ServiceRecord {
+            id: "node.local._http._tcp.local.".to_string(),
+            service_type: "_http._tcp.local.".to_string(),
+            port: 8080,
+            ttl: Some(120),
+            origin: "node.local".to_string(),
+            priority: Some(0),
+            weight: Some(0),
+            node_id: "node.local".to_string(),
+            proxy: false,
+            txt: HashMap::new(),
+            signature: None,
+            authenticated: false,
+        };
+
+        assert_eq!(built.id, manual.id);
+        assert_eq!(built.service_type, manual.service_type);
+        assert_eq!(built.port, manual.port);
+        assert_eq!(built.ttl, manual.ttl);
+        assert_eq!(built.origin, manual.origin);
+        assert_eq!(built.priority, manual.priority);
+        assert_eq!(built.weight, manual.weight);
+        assert_eq!(built.node_id, manual.node_id);
+        assert_eq!(built.proxy, manual.proxy);
+    }
+
+    #[test]
+    fn test_builder_node_id_override() {
+        let built = ServiceRecord::builder("svc", "_http._tcp.local.")
+            .origin("proxy.local")
+            .node_id("device.local")
+            .priority(10)
+            .weight(5)
+            .build();
+
+        assert_eq!(built.origin, "proxy.local");
+        assert_eq!(built.node_id, "device.local");
+        assert_eq!(built.priority, Some(10));
+        assert_eq!(built.weight, Some(5));
+    }
+
+    #[test]
+    fn test_canonical_bytes_ignore_txt_order() {
+        let entries = [("path", "/api"), ("version", "2"), ("auth", "token"), ("zone", "lab")];
+        let base = || ServiceRecord::builder("node.local._http._tcp.local.", "_http._tcp.local.").port(8080);
+
+        let forward = entries
+            .iter()
+            .fold(base(), |builder, (key, value)| builder.txt(*key, *value))
+            .build();
+        let mut reversed = base().build();
+        for (key, value) in entries.iter().rev() {
+            reversed.txt.insert(key.to_string(), value.as_bytes().to_vec());
+        }
+        assert_eq!(forward.canonical_bytes(), reversed.canonical_bytes());
+
+        let mut changed = reversed.clone();
+        changed.txt.insert("version".to_string(), b"3".to_vec());
+        assert_ne!(forward.canonical_bytes(), changed.canonical_bytes());
+
+        // Shifting bytes between adjacent fields must change the encoding.
+        let a = ServiceRecord::builder("ab", "c").build();
+        let b = ServiceRecord::builder("a", "bc").build();
+        assert_ne!(a.canonical_bytes(), b.canonical_bytes());
+    }
+
+    #[test]
+    fn test_from_srv_maps_fields() {
+        let srv = DnsRecord::SRV {
+            name: crate::DnsName::new("MyLaptop.local._http._tcp.local").unwrap(),
+            ttl: 120,
+            priority: 10,
+            weight: 5,
+            port: 8080,
+            target: crate::DnsName::new("MyLaptop.local.").unwrap(),
+        };
+
+        let service = ServiceRecord::from_srv(&srv).expect("SRV record not converted");
+        assert_eq!(service.id, "MyLaptop.local._http._tcp.local");
+        assert_eq!(service.service_type, "_http._tcp.local");
+        assert_eq!(service.port, 8080);
+        assert_eq!(service.ttl, Some(120));
+        assert_eq!(service.origin, "MyLaptop.local");
+        assert_eq!(service.node_id, "MyLaptop.local");
+        assert_eq!(service.priority, Some(10));
+        assert_eq!(service.weight, Some(5));
+        assert!(!service.authenticated);
+
+        let a = DnsRecord::A { name: crate::DnsName::new("MyLaptop.local").unwrap(), ttl: 120, ip: [10, 0, 0, 7] };
+        assert!(ServiceRecord::from_srv(&a).is_none());
+    }
+
+    #[test]
+    fn test_from_a_prefers_record_address() {
+        let a = DnsRecord::A { name: crate::DnsName::new("MyLaptop.local.").unwrap(), ttl: 60, ip: [10, 0, 0, 7] };
+
+        let node = NodeRecord::from_a(&a, "192.168.1.20:5353".parse().unwrap()).expect("A record not converted");
+        assert_eq!(node.id, "MyLaptop.local");
+        assert_eq!(node.ip_address, "10.0.0.7");
+        assert_eq!(node.ttl, Some(60));
+        assert!(node.services.is_empty());
+
+        let node = NodeRecord::from_a(&a, "[fe80::1]:5353".parse().unwrap()).unwrap();
+        assert_eq!(node.ip_address, "10.0.0.7");
+
+        // An unspecified address falls back to the IPv4 source
+        let a = DnsRecord::A { name: crate::DnsName::new("MyLaptop.local.").unwrap(), ttl: 60, ip: [0, 0, 0, 0] };
+        let node = NodeRecord::from_a(&a, "[::ffff:192.168.1.20]:5353".parse().unwrap()).unwrap();
+        assert_eq!(node.ip_address, "192.168.1.20");
+    }
+}
@@ -1,52 +1,231 @@
 // protocols\mdns\src\behaviour\records\mdns_registry.rs
 use crate::behaviour::records::mdns_records::{NodeRecord, ServiceRecord};
-use registry::{InMemoryRegistry, Registry, RegistryError};
-use std::sync::Arc;
+use registry::{InMemoryRegistry, RecordStore, RegistryError};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 use crate::MdnsError;
+
+/// Default upper bound on the number of nodes the registry keeps.
+pub const DEFAULT_MAX_NODES: usize = 4096;
+
 /// Represents the mDNS registry for managing service and node records.
+///
+/// Records live in two `RecordStore`s, in memory by default; `with_stores` puts them
+/// in any other backend, such as Redis.
+///
+/// Getters never return a record whose TTL has run out, whether or not the store has
+/// swept it yet; an expired record found at read time is removed from the store.
 pub struct MdnsRegistry {
-    service_registry: Arc<InMemoryRegistry<ServiceRecord>>,
-    node_registry: Arc<InMemoryRegistry<NodeRecord>>,
+    service_registry: Arc<dyn RecordStore<ServiceRecord>>,
+    node_registry: Arc<dyn RecordStore<NodeRecord>>,
+    max_nodes: AtomicUsize,
+    /// Nodes added through `add_node`, in the order they are evicted. Its lock is
+    /// held for the whole of `add_node`, so the limit check and eviction are atomic.
+    node_order: tokio::sync::Mutex<NodeOrder>,
+    /// When each service added through this registry expires, with the `service_adds`
+    /// value it was added at. Service records carry no timestamp of their own, so the
+    /// deadline is taken from `add_service`; `list_services` drops the entries of
+    /// services the store has evicted on its own.
+    service_deadlines: Mutex<HashMap<String, (SystemTime, u64)>>,
+    /// Counts `add_service` calls, so `list_services` can tell a service missing
+    /// from its listing from one added after the listing was taken.
+    service_adds: AtomicU64,
+    /// Bumped whenever a service is removed or dropped as expired.
+    service_removals: AtomicU64,
 }
 
 impl MdnsRegistry {
-    /// Creates a new `MdnsRegistry` with default configurations.
     /// Creates a new `MdnsRegistry` with default configurations.
     pub fn new() -> Arc<Self> {
+        Self::with_max_nodes(DEFAULT_MAX_NODES)
+    }
+
+    /// Creates a new `MdnsRegistry` that keeps at most `max_nodes` nodes.
+    pub fn with_max_nodes(max_nodes: usize) -> Arc<Self> {
+        let registry = Self::with_stores(
+            Arc::new(InMemoryRegistry::new(50)),
+            // Node eviction is handled by `add_node`, so the inner registry is unbounded.
+            Arc::new(InMemoryRegistry::new(usize::MAX)),
+        );
+        registry.set_max_nodes(max_nodes);
+        registry
+    }
+
+    /// Creates a new `MdnsRegistry` backed by the given stores, keeping at most
+    /// `DEFAULT_MAX_NODES` nodes. The node store should not evict on its own, since
+    /// `add_node` enforces the node limit.
+    pub fn with_stores(
+        service_store: Arc<dyn RecordStore<ServiceRecord>>,
+        node_store: Arc<dyn RecordStore<NodeRecord>>,
+    ) -> Arc<Self> {
         Arc::new(Self {
-            service_registry: Arc::new(InMemoryRegistry::new(50)),
-            node_registry: Arc::new(InMemoryRegistry::new(50)),
+            service_registry: service_store,
+            node_registry: node_store,
+            max_nodes: AtomicUsize::new(DEFAULT_MAX_NODES),
+            node_order: tokio::sync::Mutex::new(NodeOrder::default()),
+            service_deadlines: Mutex::new(HashMap::new()),
+            service_removals: AtomicU64::new(0),
+            service_adds: AtomicU64::new(0),
         })
     }
 
+    /// Returns the maximum number of nodes kept in the registry.
+    pub fn max_nodes(&self) -> usize {
+        self.max_nodes.load(Ordering::Relaxed)
+    }
+
+    /// Changes the node limit. It applies to subsequent insertions; nodes already
+    /// stored above the new limit are evicted as new nodes arrive.
+    pub fn set_max_nodes(&self, max_nodes: usize) {
+        self.max_nodes.store(max_nodes.max(1), Ordering::Relaxed);
+    }
+
     /// Adds a service record to the service registry.
     pub async fn add_service(&self, record: ServiceRecord) -> Result<(), RegistryError> {
-        self.service_registry.add(record).await
+        let id = record.id.clone();
+        let deadline = expiry(SystemTime::now(), record.ttl);
+        self.service_registry.add(record).await?;
+        let added = self.service_adds.fetch_add(1, Ordering::SeqCst);
+        let mut deadlines = self.service_deadlines.lock().unwrap();
+        match deadline {
+            Some(deadline) => deadlines.insert(id, (deadline, added)),
+            None => deadlines.remove(&id),
+        };
+        Ok(())
     }
 
-    /// Retrieves a service record by its ID.
+    /// Retrieves a service record by its ID, or `None` if it has expired.
     pub async fn get_service(&self, id: &str) -> Option<ServiceRecord> {
-        self.service_registry.get(id).await
+        let service = log_store_error(self.service_registry.get(id).await).flatten()?;
+        if self.service_expired(id) {
+            self.remove_expired_service(id).await;
+            return None;
+        }
+        Some(service)
     }
 
-    /// Lists all service records in the registry.
+    /// Lists all unexpired service records in the registry.
+    ///
+    /// Deadlines of services the store no longer holds, e.g. after its own sweep
+    /// evicted them, are dropped here.
     pub async fn list_services(&self) -> Vec<ServiceRecord> {
-        self.service_registry.list().await
+        let listed_at = self.service_adds.load(Ordering::SeqCst);
+        let Some(services) = log_store_error(self.service_registry.list().await) else {
+            return Vec::new();
+        };
+        self.prune_deadlines(&services, listed_at);
+        let mut live = Vec::with_capacity(services.len());
+        for service in services {
+            if self.service_expired(&service.id) {
+                self.remove_expired_service(&service.id).await;
+            } else {
+                live.push(service);
+            }
+        }
+        live
+    }
+
+    /// Drops the deadlines of services missing from `listed`, a listing of the store
+    /// taken when `service_adds` was `listed_at`. Services added since may be missing
+    /// from the listing and keep theirs.
+    fn prune_deadlines(&self, listed: &[ServiceRecord], listed_at: u64) {
+        let listed: HashSet<&str> = listed.iter().map(|service| service.id.as_str()).collect();
+        let mut deadlines = self.service_deadlines.lock().unwrap();
+        let before = deadlines.len();
+        deadlines.retain(|id, (_, added)| *added >= listed_at || listed.contains(id.as_str()));
+        if deadlines.len() < before {
+            self.service_removals.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// When the service added under `id` expires, if it was given a TTL.
+    pub fn service_deadline(&self, id: &str) -> Option<SystemTime> {
+        self.service_deadlines.lock().unwrap().get(id).map(|(deadline, _)| *deadline)
+    }
+
+    /// Counts service removals, including expired services dropped on read. Anything
+    /// built from the current services is stale once this moves.
+    pub fn service_removals(&self) -> u64 {
+        self.service_removals.load(Ordering::SeqCst)
+    }
+
+    fn service_expired(&self, id: &str) -> bool {
+        let deadlines = self.service_deadlines.lock().unwrap();
+        deadlines.get(id).is_some_and(|(deadline, _)| *deadline <= SystemTime::now())
+    }
+
+    async fn remove_expired_service(&self, id: &str) {
+        self.service_deadlines.lock().unwrap().remove(id);
+        log_store_error(self.service_registry.remove(id).await);
+        self.service_removals.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Removes a service record, whether or not it has expired.
+    pub async fn remove_service(&self, id: &str) -> Result<(), RegistryError> {
+        self.service_deadlines.lock().unwrap().remove(id);
+        let result = self.service_registry.remove(id).await;
+        self.service_removals.fetch_add(1, Ordering::SeqCst);
+        result
+    }
+
+    /// Removes a node record, whether or not it has expired.
+    pub async fn remove_node(&self, id: &str) -> Result<(), RegistryError> {
+        self.node_order.lock().await.remove(id);
+        self.node_registry.remove(id).await
     }
 
     /// Adds a node record to the node registry.
-    pub async fn add_node(&self, record: NodeRecord) -> Result<(), RegistryError> {
-        self.node_registry.add(record).await
+    ///
+    /// Inserting a new node while the registry holds `max_nodes` nodes first evicts
+    /// the least recently seen ones; the evicted records are returned. Only nodes
+    /// added through this method count towards the limit.
+    pub async fn add_node(&self, record: NodeRecord) -> Result<Vec<NodeRecord>, RegistryError> {
+        let mut order = self.node_order.lock().await;
+        let mut evicted = Vec::new();
+        let is_new = !order.contains(&record.id) && self.node_registry.get(&record.id).await?.is_none();
+        if is_new {
+            while order.len() >= self.max_nodes() {
+                let Some(oldest) = order.pop_oldest() else {
+                    break;
+                };
+                // The store may already have dropped it, e.g. when its TTL ran out.
+                if let Some(node) = self.node_registry.get(&oldest).await? {
+                    self.node_registry.remove(&oldest).await?;
+                    evicted.push(node);
+                }
+            }
+        }
+        order.touch(&record.id, record.last_seen);
+        self.node_registry.add(record).await?;
+        Ok(evicted)
     }
 
-    /// Retrieves a node record by its ID.
+    /// Retrieves a node record by its ID, or `None` if its `last_seen + ttl` has passed.
     pub async fn get_node(&self, id: &str) -> Option<NodeRecord> {
-        self.node_registry.get(id).await
+        let node = log_store_error(self.node_registry.get(id).await).flatten()?;
+        if node_expired(&node) {
+            self.node_order.lock().await.remove(id);
+            log_store_error(self.node_registry.remove(id).await);
+            return None;
+        }
+        Some(node)
     }
 
-    /// Lists all node records in the registry.
+    /// Lists all unexpired node records in the registry.
     pub async fn list_nodes(&self) -> Vec<NodeRecord> {
-        self.node_registry.list().await
+        let nodes = log_store_error(self.node_registry.list().await).unwrap_or_default();
+        let mut live = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            if node_expired(&node) {
+                self.node_order.lock().await.remove(&node.id);
+                log_store_error(self.node_registry.remove(&node.id).await);
+            } else {
+                live.push(node);
+            }
+        }
+        live
     }
 
 
@@ -58,8 +237,127 @@ impl MdnsRegistry {
             .collect()
     }
 
+    /// Lists the distinct service types offered by the local node, sorted.
+    pub async fn local_service_types(&self, local_origin: &str) -> Vec<String> {
+        let local_origin = local_origin.trim_end_matches('.');
+        let types: BTreeSet<String> = self
+            .list_services()
+            .await
+            .into_iter()
+            .filter(|service| service.node_id.trim_end_matches('.') == local_origin)
+            .map(|service| service.service_type)
+            .collect();
+        types.into_iter().collect()
+    }
+
+    /// Groups all unexpired services by their `service_type`, each group in
+    /// `list_services` order.
+    pub async fn services_by_type(&self) -> HashMap<String, Vec<ServiceRecord>> {
+        let mut groups: HashMap<String, Vec<ServiceRecord>> = HashMap::new();
+        for service in self.list_services().await {
+            groups.entry(service.service_type.clone()).or_default().push(service);
+        }
+        groups
+    }
+
+    /// Returns true if an unexpired service with this id is known. Ids match ignoring
+    /// ASCII case and a trailing dot.
+    ///
+    /// The id as given, with and without its trailing dot, is looked up directly;
+    /// only ids stored with different letter case need a scan of the store.
+    pub async fn contains_service(&self, id: &str) -> bool {
+        for candidate in name_spellings(id) {
+            if self.get_service(&candidate).await.is_some() {
+                return true;
+            }
+        }
+        log_store_error(self.service_registry.list().await)
+            .unwrap_or_default()
+            .iter()
+            .any(|service| same_name(&service.id, id) && !self.service_expired(&service.id))
+    }
+
+    /// Returns true if an unexpired node with this id is known, matching like
+    /// `contains_service`.
+    pub async fn contains_node(&self, id: &str) -> bool {
+        for candidate in name_spellings(id) {
+            if self.get_node(&candidate).await.is_some() {
+                return true;
+            }
+        }
+        log_store_error(self.node_registry.list().await)
+            .unwrap_or_default()
+            .iter()
+            .any(|node| same_name(&node.id, id) && !node_expired(node))
+    }
+}
+
+/// Node ids ordered by when each node was last seen, oldest first.
+#[derive(Default)]
+struct NodeOrder {
+    by_last_seen: BTreeSet<(SystemTime, String)>,
+    last_seen: HashMap<String, SystemTime>,
+}
+
+impl NodeOrder {
+    fn len(&self) -> usize {
+        self.last_seen.len()
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.last_seen.contains_key(id)
+    }
+
+    /// Records that `id` was last seen at `seen`, adding it if it is new.
+    fn touch(&mut self, id: &str, seen: SystemTime) {
+        if let Some(previous) = self.last_seen.insert(id.to_string(), seen) {
+            self.by_last_seen.remove(&(previous, id.to_string()));
+        }
+        self.by_last_seen.insert((seen, id.to_string()));
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(seen) = self.last_seen.remove(id) {
+            self.by_last_seen.remove(&(seen, id.to_string()));
+        }
+    }
+
+    /// Removes and returns the least recently seen id.
+    fn pop_oldest(&mut self) -> Option<String> {
+        let (_, id) = self.by_last_seen.pop_first()?;
+        self.last_seen.remove(&id);
+        Some(id)
+    }
+}
+
+/// `name` without and with a trailing dot.
+fn name_spellings(name: &str) -> [String; 2] {
+    let bare = name.trim_end_matches('.');
+    [bare.to_string(), format!("{}.", bare)]
 }
 
+/// Compares two DNS names ignoring ASCII case and a trailing dot.
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim_end_matches('.').eq_ignore_ascii_case(b.trim_end_matches('.'))
+}
+
+
+/// The instant a record seen at `seen` with the given TTL expires; `None` if it never does.
+fn expiry(seen: SystemTime, ttl: Option<u32>) -> Option<SystemTime> {
+    ttl.and_then(|ttl| seen.checked_add(Duration::from_secs(ttl.into())))
+}
+
+fn node_expired(node: &NodeRecord) -> bool {
+    expiry(node.last_seen, node.ttl).is_some_and(|deadline| deadline <= SystemTime::now())
+}
+
+/// Lookups treat an unreachable store like an empty one, so discovery keeps running;
+/// the failure is still logged.
+fn log_store_error<T>(result: Result<T, RegistryError>) -> Option<T> {
+    result
+        .map_err(|err| eprintln!("(REGISTRY) Record store error: {}", err))
+        .ok()
+}
 
 impl From<RegistryError> for MdnsError {
     fn from(error: RegistryError) -> Self {
@@ -70,7 +368,7 @@ impl From<RegistryError> for MdnsError {
 mod tests {
     use super::*;
     use crate::behaviour::records::mdns_records::{NodeRecord, ServiceRecord};
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
 
     #[tokio::test]
     async fn test_add_and_retrieve_service() {
@@ -85,6 +383,10 @@ mod tests {
             priority: Some(10),
             weight: Some(5),
             node_id: "node1".to_string(),
+            proxy: false,
+            txt: Default::default(),
+            signature: None,
+            authenticated: false,
         };
 
         registry.add_service(service.clone()).await.unwrap();
@@ -111,6 +413,10 @@ mod tests {
             priority: Some(10),
             weight: Some(5),
             node_id: "node2".to_string(),
+            proxy: false,
+            txt: Default::default(),
+            signature: None,
+            authenticated: false,
         };
 
         registry.add_service(service).await.unwrap();
@@ -120,6 +426,88 @@ mod tests {
         assert!(retrieved.is_none(), "Expired service should not be retrievable");
     }
 
+    #[tokio::test]
+    async fn test_local_service_types() {
+        let registry = MdnsRegistry::new();
+
+        let services = [
+            ("web1", "_http._tcp.local.", "local"),
+            ("web2", "_http._tcp.local.", "local"),
+            ("printer", "_ipp._tcp.local.", "local"),
+            ("remote", "_ssh._tcp.local.", "remote"),
+        ];
+        for (id, service_type, node_id) in services {
+            registry
+                .add_service(ServiceRecord {
+                    id: id.to_string(),
+                    service_type: service_type.to_string(),
+                    port: 8080,
+                    ttl: None,
+                    origin: node_id.to_string(),
+                    priority: Some(0),
+                    weight: Some(0),
+                    node_id: node_id.to_string(),
+                    proxy: false,
+                    txt: Default::default(),
+                    signature: None,
+                    authenticated: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let types = registry.local_service_types("local").await;
+        assert_eq!(types, vec!["_http._tcp.local.".to_string(), "_ipp._tcp.local.".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_services_by_type() {
+        let registry = MdnsRegistry::new();
+
+        for (id, service_type) in [("web1", "_http._tcp.local."), ("printer", "_ipp._tcp.local."), ("web2", "_http._tcp.local.")] {
+            let service = ServiceRecord::builder(id, service_type).port(8080).origin("node").build();
+            registry.add_service(service).await.unwrap();
+        }
+
+        let groups = registry.services_by_type().await;
+        assert_eq!(groups.len(), 2);
+        let mut web: Vec<&str> = groups["_http._tcp.local."].iter().map(|service| service.id.as_str()).collect();
+        web.sort();
+        assert_eq!(web, ["web1", "web2"]);
+        assert_eq!(groups["_ipp._tcp.local."].len(), 1);
+        assert_eq!(groups["_ipp._tcp.local."][0].id, "printer");
+    }
+
+    #[tokio::test]
+    async fn test_contains_ignores_case_and_trailing_dot() {
+        let registry = MdnsRegistry::new();
+        let service = ServiceRecord::builder("Printer._ipp._tcp.local.", "_ipp._tcp.local.")
+            .port(631)
+            .origin("Office.local")
+            .build();
+        registry.add_service(service).await.unwrap();
+        registry
+            .add_node(NodeRecord {
+                id: "Office.local".to_string(),
+                ip_address: "192.168.1.20".to_string(),
+                ttl: Some(120),
+                services: vec![],
+                last_seen: SystemTime::now(),
+            })
+            .await
+            .unwrap();
+
+        for id in ["Printer._ipp._tcp.local.", "Printer._ipp._tcp.local", "printer._IPP._tcp.LOCAL", "PRINTER._ipp._tcp.local."] {
+            assert!(registry.contains_service(id).await, "{} should match", id);
+        }
+        for id in ["Office.local", "Office.local.", "office.LOCAL."] {
+            assert!(registry.contains_node(id).await, "{} should match", id);
+        }
+        assert!(!registry.contains_service("Printer._ipp._tcp").await);
+        assert!(!registry.contains_service("Scanner._ipp._tcp.local.").await);
+        assert!(!registry.contains_node("Office").await);
+    }
+
     #[tokio::test]
     async fn test_add_and_retrieve_node() {
         let registry = MdnsRegistry::new();
@@ -129,6 +517,7 @@ mod tests {
             ip_address: "192.168.1.1".to_string(),
             ttl: Some(10),
             services: vec!["service1".to_string()],
+            last_seen: SystemTime::now(),
         };
 
         registry.add_node(node.clone()).await.unwrap();
@@ -147,6 +536,7 @@ mod tests {
             ip_address: "192.168.1.2".to_string(),
             ttl: Some(1),
             services: vec![],
+            last_seen: SystemTime::now(),
         };
 
         registry.add_node(node).await.unwrap();
@@ -170,6 +560,10 @@ mod tests {
                 priority: Some(10),
                 weight: Some(5),
                 node_id: format!("node{}", i),
+                proxy: false,
+                txt: Default::default(),
+                signature: None,
+                authenticated: false,
             };
             registry.add_service(service).await.unwrap();
         }
@@ -187,6 +581,7 @@ mod tests {
             ip_address: "192.168.1.100".to_string(),
             ttl: Some(1),
             services: vec!["service_evict".to_string()],
+            last_seen: SystemTime::now(),
         };
 
         let new_node = NodeRecord {
@@ -194,6 +589,7 @@ mod tests {
             ip_address: "192.168.1.101".to_string(),
             ttl: None,
             services: vec![],
+            last_seen: SystemTime::now(),
         };
 
         registry.add_node(evictable_node).await.unwrap();
@@ -220,6 +616,10 @@ mod tests {
                 priority: Some(10),
                 weight: Some(5),
                 node_id: format!("node{}", i),
+                proxy: false,
+                txt: Default::default(),
+                signature: None,
+                authenticated: false,
             };
             registry.add_service(service).await.unwrap();
         }
@@ -234,6 +634,10 @@ mod tests {
             priority: Some(10),
             weight: Some(5),
             node_id: "new_node".to_string(),
+            proxy: false,
+            txt: Default::default(),
+            signature: None,
+            authenticated: false,
         };
         registry.add_service(new_service.clone()).await.unwrap();
 
@@ -247,4 +651,163 @@ mod tests {
         // Check that the oldest record was evicted
         assert!(!services.iter().any(|s| s.id == "service0"), "Oldest service should be evicted from the registry");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_node_limit_evicts_least_recently_seen() {
+        let registry = MdnsRegistry::with_max_nodes(3);
+        let start = SystemTime::now();
+
+        for i in 0..5u64 {
+            let node = NodeRecord {
+                id: format!("node{}", i),
+                ip_address: format!("192.168.1.{}", i + 1),
+                ttl: None,
+                services: vec![],
+                last_seen: start + Duration::from_secs(i),
+            };
+            let evicted = registry.add_node(node).await.unwrap();
+            if i < 3 {
+                assert!(evicted.is_empty(), "No eviction expected below the limit");
+            } else {
+                let ids: Vec<_> = evicted.iter().map(|n| n.id.clone()).collect();
+                assert_eq!(ids, vec![format!("node{}", i - 3)]);
+            }
+        }
+
+        let nodes = registry.list_nodes().await;
+        assert_eq!(nodes.len(), 3, "Node count should stay at the limit");
+        assert!(!nodes.iter().any(|n| n.id == "node0"), "Oldest node should be evicted");
+        assert!(nodes.iter().any(|n| n.id == "node4"), "Newest node should be present");
+
+        // Refreshing an existing node never evicts.
+        let refreshed = registry.get_node("node2").await.unwrap();
+        assert!(registry.add_node(refreshed).await.unwrap().is_empty());
+        assert_eq!(registry.list_nodes().await.len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_node_adds_respect_limit() {
+        let registry = MdnsRegistry::with_max_nodes(10);
+
+        let mut tasks = Vec::new();
+        for worker in 0..8 {
+            let registry = registry.clone();
+            tasks.push(tokio::spawn(async move {
+                let mut evicted = 0;
+                for i in 0..50 {
+                    let node = NodeRecord {
+                        id: format!("node{}-{}", worker, i),
+                        ip_address: "192.168.1.1".to_string(),
+                        ttl: None,
+                        services: vec![],
+                        last_seen: SystemTime::now(),
+                    };
+                    evicted += registry.add_node(node).await.unwrap().len();
+                }
+                evicted
+            }));
+        }
+        let mut evicted = 0;
+        for task in tasks {
+            evicted += task.await.unwrap();
+        }
+
+        assert_eq!(registry.list_nodes().await.len(), 10);
+        assert_eq!(evicted, 8 * 50 - 10);
+    }
+
+    /// A store that keeps every record until it is removed, so expiry is only ever
+    /// observed through the registry's own read-time checks.
+    struct NonSweepingStore<R>(std::sync::Mutex<HashMap<String, R>>);
+
+    impl<R> NonSweepingStore<R> {
+        fn new() -> Self {
+            Self(std::sync::Mutex::new(HashMap::new()))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl<R: registry::Record> RecordStore<R> for NonSweepingStore<R> {
+        async fn add(&self, record: R) -> Result<(), RegistryError> {
+            self.0.lock().unwrap().insert(record.identifier(), record);
+            Ok(())
+        }
+
+        async fn get(&self, identifier: &str) -> Result<Option<R>, RegistryError> {
+            Ok(self.0.lock().unwrap().get(identifier).cloned())
+        }
+
+        async fn remove(&self, identifier: &str) -> Result<(), RegistryError> {
+            self.0.lock().unwrap().remove(identifier);
+            Ok(())
+        }
+
+        async fn list(&self) -> Result<Vec<R>, RegistryError> {
+            Ok(self.0.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_records_are_absent_without_sweeper() {
+        let services = Arc::new(NonSweepingStore::<ServiceRecord>::new());
+        let nodes = Arc::new(NonSweepingStore::<NodeRecord>::new());
+        let registry = MdnsRegistry::with_stores(services.clone(), nodes.clone());
+
+        let service = ServiceRecord {
+            id: "short_lived".to_string(),
+            service_type: "http".to_string(),
+            port: 8080,
+            ttl: Some(1),
+            origin: "local".to_string(),
+            priority: Some(0),
+            weight: Some(0),
+            node_id: "node1".to_string(),
+            proxy: false,
+            txt: Default::default(),
+            signature: None,
+            authenticated: false,
+        };
+        registry.add_service(service).await.unwrap();
+        registry
+            .add_node(NodeRecord {
+                id: "node1".to_string(),
+                ip_address: "192.168.1.1".to_string(),
+                ttl: Some(1),
+                services: vec!["short_lived".to_string()],
+                last_seen: SystemTime::now(),
+            })
+            .await
+            .unwrap();
+        assert!(registry.get_service("short_lived").await.is_some());
+
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        assert!(registry.get_service("short_lived").await.is_none());
+        assert!(registry.list_services().await.is_empty());
+        assert!(registry.get_node("node1").await.is_none());
+        assert!(registry.list_nodes().await.is_empty());
+        // The expired records were dropped from the stores on read.
+        assert!(services.0.lock().unwrap().is_empty());
+        assert!(nodes.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deadlines_of_store_evicted_services_are_pruned() {
+        let services = Arc::new(NonSweepingStore::<ServiceRecord>::new());
+        let registry = MdnsRegistry::with_stores(services.clone(), Arc::new(NonSweepingStore::<NodeRecord>::new()));
+
+        for id in ["kept", "evicted"] {
+            let service = ServiceRecord::builder(id, "_http._tcp.local.").ttl(Some(300)).origin("local").build();
+            registry.add_service(service).await.unwrap();
+        }
+        // The store drops a record without going through the registry.
+        services.0.lock().unwrap().remove("evicted");
+        let removals = registry.service_removals();
+
+        let listed = registry.list_services().await;
+        assert_eq!(listed.len(), 1);
+        assert!(registry.service_deadline("kept").is_some());
+        assert!(registry.service_deadline("evicted").is_none());
+        assert!(registry.service_removals() > removals);
+    }
+}
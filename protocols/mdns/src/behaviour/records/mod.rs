@@ -2,6 +2,7 @@
 mod mdns_registry;
 mod mdns_records;
 
-pub use mdns_registry::MdnsRegistry;
-pub use mdns_records::{ServiceRecord,NodeRecord};
+pub use mdns_registry::{MdnsRegistry,DEFAULT_MAX_NODES};
+pub use mdns_records::{ServiceRecord,ServiceRecordBuilder,NodeRecord};
+pub(crate) use mdns_records::extract_service_type;
 
@@ -4,10 +4,12 @@
 mod record;
 mod packet;
 mod name;
+mod types;
 
-pub use record::DnsRecord;
+pub use record::{DnsRecord,SrvData,SvcParam};
 pub use name::DnsName;
 pub use packet::{DnsPacket,DnsQuestion};
+pub use types::{DnsType,DnsClass,DnsFlags};
 
 // =================================================
 
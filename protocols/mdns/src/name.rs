@@ -4,6 +4,8 @@ use bytes::Buf;
 use serde::Serialize;
 use std::fmt;
 
+use crate::MdnsError;
+
 /// Represents a DNS name, composed of multiple labels.
 ///
 /// A `DnsName` provides methods for creating, writing, and parsing DNS names,
@@ -39,6 +41,14 @@ impl DnsName {
         Ok(DnsName { labels })
     }
 
+    /// Creates a new `DnsName`, reporting an invalid name as `MdnsError::InvalidName`.
+    ///
+    /// Use this for names taken from the registry or the network, where a bad name
+    /// should skip the affected record rather than abort the caller.
+    pub fn try_new(name: &str) -> Result<Self, MdnsError> {
+        Self::new(name).map_err(MdnsError::InvalidName)
+    }
+
     /// Writes the DNS name into a buffer in DNS wire format.
     ///
     /// # Arguments
@@ -51,6 +61,41 @@ impl DnsName {
         buffer.push(0x00); // End of the domain name
     }
 
+    /// Compares two names the way DNS does: label by label, ignoring ASCII case.
+    pub fn eq_ignore_case(&self, other: &DnsName) -> bool {
+        self.labels.len() == other.labels.len()
+            && self
+                .labels
+                .iter()
+                .zip(&other.labels)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Returns the number of labels; the root name has none.
+    pub fn labels_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns true if this name is `other` or lies below it, comparing labels
+    /// case-insensitively. Every name is a subdomain of the root.
+    pub fn is_subdomain_of(&self, other: &DnsName) -> bool {
+        self.labels.len() >= other.labels.len()
+            && self.labels[self.labels.len() - other.labels.len()..]
+                .iter()
+                .zip(&other.labels)
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+
+    /// Returns the name with its first label removed, or `None` for the root.
+    pub fn parent(&self) -> Option<DnsName> {
+        self.labels.split_first().map(|(_, rest)| DnsName { labels: rest.to_vec() })
+    }
+
+    /// Returns the number of bytes `write` produces for this name.
+    pub fn wire_len(&self) -> usize {
+        self.labels.iter().map(|label| label.len() + 1).sum::<usize>() + 1
+    }
+
     /// Parses a `DnsName` from a cursor containing DNS wire format data.
     ///
     /// # Arguments
@@ -62,10 +107,16 @@ impl DnsName {
     pub fn parse(cursor: &mut std::io::Cursor<&[u8]>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let mut labels = Vec::new();
         loop {
-            let len = cursor.get_u8();
+            let len = cursor.try_get_u8()?;
             if len == 0 {
                 break;
             }
+            if len & 0xC0 == 0xC0 {
+                return Err("compressed names are not supported".into());
+            }
+            if len > 63 {
+                return Err(format!("label length {} exceeds 63", len).into());
+            }
             let mut label = vec![0; len as usize];
             cursor.read_exact(&mut label)?;
             labels.push(String::from_utf8(label)?);
@@ -1,5 +1,5 @@
 // protocols\mdns\src\packet.rs
-use crate::{record::DnsRecord,name::DnsName};
+use crate::{record::DnsRecord,name::DnsName,types::{DnsClass,DnsFlags,DnsType}};
 use bytes::Buf;
 
 /// Represents a DNS packet in the mDNS protocol.
@@ -24,7 +24,7 @@ impl DnsPacket {
     pub fn new() -> Self {
         DnsPacket {
             id: 0,
-            flags: 0x8400, // Standard Query Response, Authoritative Answer
+            flags: DnsFlags::authoritative_response().to_u16(),
             questions: Vec::new(),
             answers: Vec::new(),
             authorities: Vec::new(),
@@ -32,12 +32,31 @@ impl DnsPacket {
         }
     }
 
+    /// Returns the header flags as named fields.
+    pub fn dns_flags(&self) -> DnsFlags {
+        DnsFlags::from_u16(self.flags)
+    }
+
+    /// Replaces the header flags.
+    pub fn set_dns_flags(&mut self, flags: DnsFlags) {
+        self.flags = flags.to_u16();
+    }
+
     /// Serializes the `DnsPacket` into a byte buffer suitable for transmission.
     ///
     /// # Returns
     /// * `Vec<u8>` - The serialized byte representation of the DNS packet.
     pub fn serialize(&self) -> Vec<u8> {
-        let mut buffer = Vec::new();
+        let mut buffer = Vec::with_capacity(self.estimated_size());
+        self.serialize_into(&mut buffer);
+        buffer
+    }
+
+    /// Appends the serialized packet to `buffer`, leaving any existing contents in
+    /// place. Clearing and reusing one buffer across sends avoids an allocation per
+    /// packet; the packet's length is `buffer.len()` minus the length beforehand.
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) {
+        buffer.reserve(self.estimated_size());
         // Serialize header
         buffer.extend_from_slice(&self.id.to_be_bytes());
         buffer.extend_from_slice(&self.flags.to_be_bytes());
@@ -48,25 +67,58 @@ impl DnsPacket {
 
         // Serialize questions
         for question in &self.questions {
-            question.write(&mut buffer);
+            question.write(buffer);
         }
 
         // Serialize records
         for record in &self.answers {
-            record.write(&mut buffer);
+            record.write(buffer);
         }
         for record in &self.authorities {
-            record.write(&mut buffer);
+            record.write(buffer);
         }
         for record in &self.additionals {
-            record.write(&mut buffer);
+            record.write(buffer);
         }
+    }
 
-        buffer
+    /// Appends `other`'s answers, authorities and additionals to this packet, skipping
+    /// any record already present in the same section (see `DnsRecord::same_record`).
+    /// Questions and header fields of `other` are dropped.
+    pub fn merge(&mut self, other: DnsPacket) {
+        fn append(section: &mut Vec<DnsRecord>, records: Vec<DnsRecord>) {
+            for record in records {
+                if !section.iter().any(|existing| existing.same_record(&record)) {
+                    section.push(record);
+                }
+            }
+        }
+
+        append(&mut self.answers, other.answers);
+        append(&mut self.authorities, other.authorities);
+        append(&mut self.additionals, other.additionals);
+    }
+
+    /// Returns the serialized size of the packet without serializing it.
+    pub fn estimated_size(&self) -> usize {
+        const HEADER_LEN: usize = 12;
+        HEADER_LEN
+            + self.questions.iter().map(DnsQuestion::wire_len).sum::<usize>()
+            + self
+                .answers
+                .iter()
+                .chain(&self.authorities)
+                .chain(&self.additionals)
+                .map(DnsRecord::wire_len)
+                .sum::<usize>()
     }
 
     /// Parses a `DnsPacket` from a byte buffer.
     ///
+    /// `data` is untrusted network input: truncated fields, bad lengths and
+    /// unsupported names are reported as errors and never panic. This is the entry
+    /// point used by the `parse_packet` fuzz target.
+    ///
     /// # Arguments
     /// * `data` - A byte slice containing the serialized DNS packet.
     ///
@@ -74,55 +126,44 @@ impl DnsPacket {
     /// * `Ok(DnsPacket)` - If parsing succeeds.
     /// * `Err(Box<dyn std::error::Error>)` - If parsing fails.
     pub fn parse(data: &[u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Self::parse_sections(data, false)
+    }
+
+    /// Parses a `DnsPacket` like `parse`, but fails on a malformed question or on a
+    /// malformed or unknown-type record instead of skipping it.
+    pub fn parse_strict(data: &[u8]) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if data.len() < 12 {
+            return Err(format!("packet of {} bytes is shorter than a DNS header", data.len()).into());
+        }
+        Self::parse_sections(data, true)
+    }
+
+    fn parse_sections(data: &[u8], strict: bool) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let mut cursor = std::io::Cursor::new(data);
     
         // Parse the header
-        let id = cursor.get_u16();
-        let flags = cursor.get_u16();
-        let qdcount = cursor.get_u16();
-        let ancount = cursor.get_u16();
-        let nscount = cursor.get_u16();
-        let arcount = cursor.get_u16();
+        let id = cursor.try_get_u16()?;
+        let flags = cursor.try_get_u16()?;
+        let qdcount = cursor.try_get_u16()?;
+        let ancount = cursor.try_get_u16()?;
+        let nscount = cursor.try_get_u16()?;
+        let arcount = cursor.try_get_u16()?;
     
         let mut questions = Vec::new();
-        for _ in 0..qdcount {
-            if let Ok(question) = DnsQuestion::parse(&mut cursor) {
-                questions.push(question);
-            } else {
-                eprintln!("Failed to parse a question section");
-                break; // Exit the loop gracefully if parsing fails
+        for index in 0..qdcount {
+            match DnsQuestion::parse(&mut cursor) {
+                Ok(question) => questions.push(question),
+                Err(err) if strict => return Err(format!("question {}: {}", index, err).into()),
+                Err(_) => {
+                    eprintln!("Failed to parse a question section");
+                    break; // Exit the loop gracefully if parsing fails
+                }
             }
         }
     
-        let mut answers = Vec::new();
-        for _ in 0..ancount {
-            if let Ok(record) = DnsRecord::parse(&mut cursor) {
-                answers.push(record);
-            } else {
-                eprintln!("Failed to parse an answer section");
-                break;
-            }
-        }
-    
-        let mut authorities = Vec::new();
-        for _ in 0..nscount {
-            if let Ok(record) = DnsRecord::parse(&mut cursor) {
-                authorities.push(record);
-            } else {
-                eprintln!("Failed to parse an authority section");
-                break;
-            }
-        }
-    
-        let mut additionals = Vec::new();
-        for _ in 0..arcount {
-            if let Ok(record) = DnsRecord::parse(&mut cursor) {
-                additionals.push(record);
-            } else {
-                eprintln!("Failed to parse an additional section");
-                break;
-            }
-        }
+        let answers = Self::parse_records(&mut cursor, ancount, "answer", strict)?;
+        let authorities = Self::parse_records(&mut cursor, nscount, "authority", strict)?;
+        let additionals = Self::parse_records(&mut cursor, arcount, "additional", strict)?;
     
         Ok(DnsPacket {
             id,
@@ -133,6 +174,33 @@ impl DnsPacket {
             additionals,
         })
     }
+
+    fn parse_records(
+        cursor: &mut std::io::Cursor<&[u8]>,
+        count: u16,
+        section: &str,
+        strict: bool,
+    ) -> Result<Vec<DnsRecord>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut records = Vec::new();
+        for index in 0..count {
+            let start = cursor.position();
+            match DnsRecord::parse(cursor) {
+                Ok(record) => records.push(record),
+                Err(err) if strict => return Err(format!("{} {}: {}", section, index, err).into()),
+                Err(err) => {
+                    // An unknown type or bad RDATA only costs that record; a broken
+                    // header leaves no way to find the next one.
+                    cursor.set_position(start);
+                    if DnsRecord::skip(cursor).is_err() {
+                        eprintln!("Failed to parse an {} section", section);
+                        break;
+                    }
+                    eprintln!("Skipping {} {}: {}", section, index, err);
+                }
+            }
+        }
+        Ok(records)
+    }
 }
 
 /// Represents a DNS question in the mDNS protocol.
@@ -147,6 +215,48 @@ pub struct DnsQuestion {
 }
 
 impl DnsQuestion {
+    /// The top bit of QCLASS in an mDNS question, asking for a unicast response (the
+    /// "QU" bit, RFC 6762 §5.4). It is not part of the class itself.
+    pub const UNICAST_RESPONSE_BIT: u16 = 0x8000;
+
+    /// Creates a question for `qname` with a typed record type and class.
+    pub fn new(qname: DnsName, qtype: DnsType, qclass: DnsClass) -> Self {
+        DnsQuestion {
+            qname,
+            qtype: qtype.to_u16(),
+            qclass: qclass.to_u16(),
+        }
+    }
+
+    /// Returns the number of bytes `write` produces for this question.
+    pub fn wire_len(&self) -> usize {
+        self.qname.wire_len() + 4
+    }
+
+    /// Returns the question's record type, if it is one we know.
+    pub fn dns_type(&self) -> Option<DnsType> {
+        DnsType::from_u16(self.qtype)
+    }
+
+    /// Returns the question's class, if it is one we know. The QU bit is ignored.
+    pub fn dns_class(&self) -> Option<DnsClass> {
+        DnsClass::from_u16(self.qclass & !Self::UNICAST_RESPONSE_BIT)
+    }
+
+    /// Returns true if the question asks for a unicast response.
+    pub fn unicast_response(&self) -> bool {
+        self.qclass & Self::UNICAST_RESPONSE_BIT != 0
+    }
+
+    /// Sets or clears the QU bit.
+    pub fn set_unicast_response(&mut self, unicast: bool) {
+        if unicast {
+            self.qclass |= Self::UNICAST_RESPONSE_BIT;
+        } else {
+            self.qclass &= !Self::UNICAST_RESPONSE_BIT;
+        }
+    }
+
     /// Parses a `DnsQuestion` from a cursor containing DNS wire format data.
     ///
     /// # Arguments
@@ -157,8 +267,8 @@ impl DnsQuestion {
     /// * `Err(Box<dyn std::error::Error>)` - If parsing fails.
     pub fn parse(cursor: &mut std::io::Cursor<&[u8]>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let qname = DnsName::parse(cursor)?;
-        let qtype = cursor.get_u16();
-        let qclass = cursor.get_u16();
+        let qtype = cursor.try_get_u16()?;
+        let qclass = cursor.try_get_u16()?;
         Ok(DnsQuestion { qname, qtype, qclass })
     }
 
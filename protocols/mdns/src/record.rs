@@ -2,12 +2,14 @@
 
 // /protocols/mdns/record.rs
 use crate::name::DnsName;
+use crate::types::{DnsClass, DnsType};
 use std::io::Read;
 use bytes::Buf;
 use serde::Serialize;
+use std::net::{Ipv4Addr, Ipv6Addr};
 /// Represents DNS resource records (RR) used in the mDNS protocol.
 ///
-/// `DnsRecord` supports multiple record types such as A, PTR, SRV, and TXT.
+/// `DnsRecord` supports multiple record types such as A, PTR, SRV, TXT and SVCB.
 #[derive(Debug, Clone,Serialize)]
 pub enum DnsRecord {
     /// A Record - Maps a name to an IPv4 address.
@@ -31,15 +33,135 @@ pub enum DnsRecord {
         port: u16,
         target: DnsName,
     },
-    /// TXT Record - Contains text data.
+    /// TXT Record - `txt_data` is the rdata as sent on the wire: a sequence of
+    /// length-prefixed character strings (RFC 1035 §3.3.14).
     TXT {
         name: DnsName,
         ttl: u32,
         txt_data: Vec<u8>,
     },
+    /// AAAA Record - Maps a name to an IPv6 address.
+    AAAA {
+        name: DnsName,
+        ttl: u32,
+        ip: [u8; 16],
+    },
+    /// SVCB Record - Binds a service to an endpoint with its parameters (RFC 9460).
+    SVCB {
+        name: DnsName,
+        ttl: u32,
+        /// 0 for alias mode, otherwise the service priority.
+        priority: u16,
+        target: DnsName,
+        params: Vec<SvcParam>,
+    },
     // Additional record types can be added as needed.
 }
 
+/// A key=value parameter of an SVCB record.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum SvcParam {
+    /// Supported application protocols, e.g. `h2` (key 1).
+    Alpn(Vec<String>),
+    /// Alternative port (key 3).
+    Port(u16),
+    /// IPv4 addresses of the target (key 4).
+    Ipv4Hint(Vec<Ipv4Addr>),
+    /// Any other key, kept as raw bytes.
+    Unknown { key: u16, value: Vec<u8> },
+}
+
+impl SvcParam {
+    /// Returns the parameter's SvcParamKey.
+    pub fn key(&self) -> u16 {
+        match self {
+            SvcParam::Alpn(_) => 1,
+            SvcParam::Port(_) => 3,
+            SvcParam::Ipv4Hint(_) => 4,
+            SvcParam::Unknown { key, .. } => *key,
+        }
+    }
+
+    /// Writes the key, value length and value in wire format.
+    fn write(&self, buffer: &mut Vec<u8>) {
+        let mut value = Vec::new();
+        match self {
+            SvcParam::Alpn(protocols) => {
+                for protocol in protocols {
+                    value.push(protocol.len() as u8);
+                    value.extend_from_slice(protocol.as_bytes());
+                }
+            }
+            SvcParam::Port(port) => value.extend_from_slice(&port.to_be_bytes()),
+            SvcParam::Ipv4Hint(addresses) => {
+                for address in addresses {
+                    value.extend_from_slice(&address.octets());
+                }
+            }
+            SvcParam::Unknown { value: raw, .. } => value.extend_from_slice(raw),
+        }
+        buffer.extend_from_slice(&self.key().to_be_bytes());
+        buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        buffer.extend_from_slice(&value);
+    }
+
+    /// Parses one parameter from the remaining SVCB RDATA.
+    fn parse(cursor: &mut std::io::Cursor<&[u8]>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let key = cursor.try_get_u16()?;
+        let len = cursor.try_get_u16()? as usize;
+        let mut value = vec![0u8; len];
+        cursor.read_exact(&mut value)?;
+
+        match key {
+            1 => {
+                let mut protocols = Vec::new();
+                let mut rest = value.as_slice();
+                while let Some((&len, tail)) = rest.split_first() {
+                    if tail.len() < len as usize {
+                        return Err("truncated alpn value".into());
+                    }
+                    let (protocol, tail) = tail.split_at(len as usize);
+                    protocols.push(String::from_utf8(protocol.to_vec())?);
+                    rest = tail;
+                }
+                Ok(SvcParam::Alpn(protocols))
+            }
+            3 => {
+                let port: [u8; 2] = value.as_slice().try_into().map_err(|_| "port value must be 2 bytes")?;
+                Ok(SvcParam::Port(u16::from_be_bytes(port)))
+            }
+            4 => {
+                if value.is_empty() || !value.len().is_multiple_of(4) {
+                    return Err("ipv4hint value must be a non-empty multiple of 4 bytes".into());
+                }
+                let addresses = value
+                    .chunks_exact(4)
+                    .map(|octets| Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+                    .collect();
+                Ok(SvcParam::Ipv4Hint(addresses))
+            }
+            _ => Ok(SvcParam::Unknown { key, value }),
+        }
+    }
+}
+
+/// The RDATA of an SRV record, as returned by [`DnsRecord::as_srv`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrvData {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: DnsName,
+}
+
+/// SVCB parameters in wire (key) order, so records differing only in the order the
+/// parameters were given compare equal.
+fn sorted_params(params: &[SvcParam]) -> Vec<&SvcParam> {
+    let mut sorted: Vec<&SvcParam> = params.iter().collect();
+    sorted.sort_by_key(|param| param.key());
+    sorted
+}
+
 impl DnsRecord {
     /// Writes the DNS record to a buffer in DNS wire format.
     ///
@@ -49,16 +171,16 @@ impl DnsRecord {
         match self {
             DnsRecord::A { name, ttl, ip } => {
                 name.write(buffer);
-                buffer.extend_from_slice(&1u16.to_be_bytes()); // TYPE A
-                buffer.extend_from_slice(&1u16.to_be_bytes()); // CLASS IN
+                buffer.extend_from_slice(&DnsType::A.to_u16().to_be_bytes()); // TYPE A
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes()); // CLASS IN
                 buffer.extend_from_slice(&ttl.to_be_bytes());  // TTL
                 buffer.extend_from_slice(&4u16.to_be_bytes()); // RDLENGTH
                 buffer.extend_from_slice(ip);                 // RDATA (IPv4 address)
             }
             DnsRecord::PTR { name, ttl, ptr_name } => {
                 name.write(buffer);
-                buffer.extend_from_slice(&12u16.to_be_bytes()); // TYPE PTR
-                buffer.extend_from_slice(&1u16.to_be_bytes());  // CLASS IN
+                buffer.extend_from_slice(&DnsType::PTR.to_u16().to_be_bytes()); // TYPE PTR
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes());  // CLASS IN
                 buffer.extend_from_slice(&ttl.to_be_bytes());   // TTL
                 let mut rdata = Vec::new();
                 ptr_name.write(&mut rdata);
@@ -74,8 +196,8 @@ impl DnsRecord {
                 target,
             } => {
                 name.write(buffer);
-                buffer.extend_from_slice(&33u16.to_be_bytes()); // TYPE SRV
-                buffer.extend_from_slice(&1u16.to_be_bytes());  // CLASS IN
+                buffer.extend_from_slice(&DnsType::SRV.to_u16().to_be_bytes()); // TYPE SRV
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes());  // CLASS IN
                 buffer.extend_from_slice(&ttl.to_be_bytes());   // TTL
                 let mut rdata = Vec::new();
                 rdata.extend_from_slice(&priority.to_be_bytes());
@@ -85,16 +207,35 @@ impl DnsRecord {
                 buffer.extend_from_slice(&(rdata.len() as u16).to_be_bytes()); // RDLENGTH
                 buffer.extend_from_slice(&rdata);                              // RDATA
             }
+            DnsRecord::AAAA { name, ttl, ip } => {
+                name.write(buffer);
+                buffer.extend_from_slice(&DnsType::AAAA.to_u16().to_be_bytes()); // TYPE AAAA
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes());   // CLASS IN
+                buffer.extend_from_slice(&ttl.to_be_bytes());   // TTL
+                buffer.extend_from_slice(&16u16.to_be_bytes()); // RDLENGTH
+                buffer.extend_from_slice(ip);                   // RDATA (IPv6 address)
+            }
             DnsRecord::TXT { name, ttl, txt_data } => {
                 name.write(buffer);
-                buffer.extend_from_slice(&16u16.to_be_bytes()); // TYPE TXT
-                buffer.extend_from_slice(&1u16.to_be_bytes());  // CLASS IN
+                buffer.extend_from_slice(&DnsType::TXT.to_u16().to_be_bytes()); // TYPE TXT
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes());  // CLASS IN
+                buffer.extend_from_slice(&ttl.to_be_bytes());   // TTL
+
+                buffer.extend_from_slice(&(txt_data.len() as u16).to_be_bytes()); // RDLENGTH
+                buffer.extend_from_slice(txt_data);                             // RDATA
+            }
+            DnsRecord::SVCB { name, ttl, priority, target, params } => {
+                name.write(buffer);
+                buffer.extend_from_slice(&DnsType::SVCB.to_u16().to_be_bytes()); // TYPE SVCB
+                buffer.extend_from_slice(&DnsClass::IN.to_u16().to_be_bytes());   // CLASS IN
                 buffer.extend_from_slice(&ttl.to_be_bytes());   // TTL
 
                 let mut rdata = Vec::new();
-                for txt_segment in txt_data.chunks(255) {
-                    rdata.push(txt_segment.len() as u8);
-                    rdata.extend_from_slice(txt_segment);
+                rdata.extend_from_slice(&priority.to_be_bytes());
+                target.write(&mut rdata);
+                // Parameters must appear in increasing key order.
+                for param in sorted_params(params) {
+                    param.write(&mut rdata);
                 }
 
                 buffer.extend_from_slice(&(rdata.len() as u16).to_be_bytes()); // RDLENGTH
@@ -103,6 +244,165 @@ impl DnsRecord {
         }
     }
 
+    /// Returns the record's type.
+    pub fn dns_type(&self) -> DnsType {
+        match self {
+            DnsRecord::A { .. } => DnsType::A,
+            DnsRecord::PTR { .. } => DnsType::PTR,
+            DnsRecord::SRV { .. } => DnsType::SRV,
+            DnsRecord::TXT { .. } => DnsType::TXT,
+            DnsRecord::AAAA { .. } => DnsType::AAAA,
+            DnsRecord::SVCB { .. } => DnsType::SVCB,
+        }
+    }
+
+    /// Returns true if both records are the same resource record: same type, owner
+    /// name and RDATA, with names compared case-insensitively. The TTL is ignored.
+    pub fn same_record(&self, other: &DnsRecord) -> bool {
+        match (self, other) {
+            (DnsRecord::A { name: a, ip: ip_a, .. }, DnsRecord::A { name: b, ip: ip_b, .. }) => {
+                a.eq_ignore_case(b) && ip_a == ip_b
+            }
+            (DnsRecord::AAAA { name: a, ip: ip_a, .. }, DnsRecord::AAAA { name: b, ip: ip_b, .. }) => {
+                a.eq_ignore_case(b) && ip_a == ip_b
+            }
+            (
+                DnsRecord::PTR { name: a, ptr_name: ptr_a, .. },
+                DnsRecord::PTR { name: b, ptr_name: ptr_b, .. },
+            ) => a.eq_ignore_case(b) && ptr_a.eq_ignore_case(ptr_b),
+            (
+                DnsRecord::SRV { name: a, priority: pa, weight: wa, port: port_a, target: ta, .. },
+                DnsRecord::SRV { name: b, priority: pb, weight: wb, port: port_b, target: tb, .. },
+            ) => a.eq_ignore_case(b) && pa == pb && wa == wb && port_a == port_b && ta.eq_ignore_case(tb),
+            (
+                DnsRecord::TXT { name: a, txt_data: data_a, .. },
+                DnsRecord::TXT { name: b, txt_data: data_b, .. },
+            ) => a.eq_ignore_case(b) && data_a == data_b,
+            (
+                DnsRecord::SVCB { name: a, priority: pa, target: ta, params: params_a, .. },
+                DnsRecord::SVCB { name: b, priority: pb, target: tb, params: params_b, .. },
+            ) => a.eq_ignore_case(b) && pa == pb && ta.eq_ignore_case(tb) && sorted_params(params_a) == sorted_params(params_b),
+            _ => false,
+        }
+    }
+
+    /// Returns the owner name of the record.
+    pub fn name(&self) -> &DnsName {
+        match self {
+            DnsRecord::A { name, .. }
+            | DnsRecord::PTR { name, .. }
+            | DnsRecord::SRV { name, .. }
+            | DnsRecord::TXT { name, .. }
+            | DnsRecord::AAAA { name, .. }
+            | DnsRecord::SVCB { name, .. } => name,
+        }
+    }
+
+    /// Returns the record's TTL in seconds.
+    pub fn ttl(&self) -> u32 {
+        match self {
+            DnsRecord::A { ttl, .. }
+            | DnsRecord::PTR { ttl, .. }
+            | DnsRecord::SRV { ttl, .. }
+            | DnsRecord::TXT { ttl, .. }
+            | DnsRecord::AAAA { ttl, .. }
+            | DnsRecord::SVCB { ttl, .. } => *ttl,
+        }
+    }
+
+    /// Replaces the record's TTL.
+    pub fn set_ttl(&mut self, new_ttl: u32) {
+        match self {
+            DnsRecord::A { ttl, .. }
+            | DnsRecord::PTR { ttl, .. }
+            | DnsRecord::SRV { ttl, .. }
+            | DnsRecord::TXT { ttl, .. }
+            | DnsRecord::AAAA { ttl, .. }
+            | DnsRecord::SVCB { ttl, .. } => *ttl = new_ttl,
+        }
+    }
+
+    /// Returns the address of an A record.
+    pub fn as_a(&self) -> Option<Ipv4Addr> {
+        match self {
+            DnsRecord::A { ip, .. } => Some(Ipv4Addr::from(*ip)),
+            _ => None,
+        }
+    }
+
+    /// Returns the address of an AAAA record.
+    pub fn as_aaaa(&self) -> Option<Ipv6Addr> {
+        match self {
+            DnsRecord::AAAA { ip, .. } => Some(Ipv6Addr::from(*ip)),
+            _ => None,
+        }
+    }
+
+    /// Returns the pointed-to name of a PTR record.
+    pub fn as_ptr(&self) -> Option<&DnsName> {
+        match self {
+            DnsRecord::PTR { ptr_name, .. } => Some(ptr_name),
+            _ => None,
+        }
+    }
+
+    /// Returns the RDATA of an SRV record.
+    pub fn as_srv(&self) -> Option<SrvData> {
+        match self {
+            DnsRecord::SRV { priority, weight, port, target, .. } => Some(SrvData {
+                priority: *priority,
+                weight: *weight,
+                port: *port,
+                target: target.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the rdata of a TXT record, its character strings still length-prefixed.
+    pub fn as_txt(&self) -> Option<&[u8]> {
+        match self {
+            DnsRecord::TXT { txt_data, .. } => Some(txt_data),
+            _ => None,
+        }
+    }
+
+    /// Returns the parameters of an SVCB record.
+    pub fn as_svcb(&self) -> Option<&[SvcParam]> {
+        match self {
+            DnsRecord::SVCB { params, .. } => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bytes `write` produces for this record.
+    pub fn wire_len(&self) -> usize {
+        // TYPE + CLASS + TTL + RDLENGTH
+        const FIXED_FIELDS: usize = 10;
+        let (name, rdata) = match self {
+            DnsRecord::A { name, .. } => (name, 4),
+            DnsRecord::PTR { name, ptr_name, .. } => (name, ptr_name.wire_len()),
+            DnsRecord::SRV { name, target, .. } => (name, 6 + target.wire_len()),
+            DnsRecord::TXT { name, txt_data, .. } => (name, txt_data.len()),
+            DnsRecord::AAAA { name, .. } => (name, 16),
+            DnsRecord::SVCB { name, target, params, .. } => {
+                let params_len: usize = params
+                    .iter()
+                    .map(|param| {
+                        4 + match param {
+                            SvcParam::Alpn(protocols) => protocols.iter().map(|p| p.len() + 1).sum(),
+                            SvcParam::Port(_) => 2,
+                            SvcParam::Ipv4Hint(addresses) => addresses.len() * 4,
+                            SvcParam::Unknown { value, .. } => value.len(),
+                        }
+                    })
+                    .sum();
+                (name, 2 + target.wire_len() + params_len)
+            }
+        };
+        name.wire_len() + FIXED_FIELDS + rdata
+    }
+
     /// Parses a `DnsRecord` from a cursor containing DNS wire format data.
     ///
     /// # Arguments
@@ -113,37 +413,76 @@ impl DnsRecord {
     /// * `Err(Box<dyn std::error::Error>)` - If parsing fails.
     pub fn parse(cursor: &mut std::io::Cursor<&[u8]>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let name = DnsName::parse(cursor)?;
-        let rtype = cursor.get_u16();
-        let _rclass = cursor.get_u16();
-        let ttl = cursor.get_u32();
-        let rdlength = cursor.get_u16();
+        let rtype = cursor.try_get_u16()?;
+        let _rclass = cursor.try_get_u16()?;
+        let ttl = cursor.try_get_u32()?;
+        let rdlength = cursor.try_get_u16()? as usize;
+
+        // RDATA is parsed from its own slice so a record can never read past
+        // RDLENGTH into the next one.
+        if cursor.remaining() < rdlength {
+            return Err(format!(
+                "RDLENGTH {} exceeds the {} bytes left in the packet",
+                rdlength,
+                cursor.remaining()
+            )
+            .into());
+        }
+        let start = cursor.position() as usize;
+        let rdata = &cursor.get_ref()[start..start + rdlength];
+        cursor.advance(rdlength);
+        let mut rdata_cursor = std::io::Cursor::new(rdata);
 
-        match rtype {
-            1 => { // A Record
+        match DnsType::from_u16(rtype) {
+            Some(DnsType::A) => {
                 let mut ip = [0u8; 4];
-                cursor.read_exact(&mut ip)?;
+                rdata_cursor.read_exact(&mut ip)?;
                 Ok(DnsRecord::A { name, ttl, ip })
             }
-            12 => { // PTR Record
-                let ptr_name = DnsName::parse(cursor)?;
+            Some(DnsType::PTR) => {
+                let ptr_name = DnsName::parse(&mut rdata_cursor)?;
                 Ok(DnsRecord::PTR { name, ttl, ptr_name })
             }
-            33 => { // SRV Record
-                let priority = cursor.get_u16();
-                let weight = cursor.get_u16();
-                let port = cursor.get_u16();
-                let target = DnsName::parse(cursor)?;
+            Some(DnsType::SRV) => {
+                let priority = rdata_cursor.try_get_u16()?;
+                let weight = rdata_cursor.try_get_u16()?;
+                let port = rdata_cursor.try_get_u16()?;
+                let target = DnsName::parse(&mut rdata_cursor)?;
                 Ok(DnsRecord::SRV { name, ttl, priority, weight, port, target })
             }
-            16 => { // TXT Record
-                let mut txt_data = vec![0; rdlength as usize];
-                cursor.read_exact(&mut txt_data)?;
-                Ok(DnsRecord::TXT { name, ttl, txt_data })
+            Some(DnsType::TXT) => Ok(DnsRecord::TXT { name, ttl, txt_data: rdata.to_vec() }),
+            Some(DnsType::AAAA) => {
+                let mut ip = [0u8; 16];
+                rdata_cursor.read_exact(&mut ip)?;
+                Ok(DnsRecord::AAAA { name, ttl, ip })
             }
-            _ => {
-                cursor.advance(rdlength as usize);
-                Err("Unknown record type".into())
+            Some(DnsType::SVCB) => {
+                let priority = rdata_cursor.try_get_u16()?;
+                let target = DnsName::parse(&mut rdata_cursor)?;
+                let mut params = Vec::new();
+                while rdata_cursor.has_remaining() {
+                    params.push(SvcParam::parse(&mut rdata_cursor)?);
+                }
+                Ok(DnsRecord::SVCB { name, ttl, priority, target, params })
             }
+            _ => Err("Unknown record type".into()),
+        }
+    }
+
+    /// Advances `cursor` past one record without interpreting its RDATA. Fails if the
+    /// record header itself is malformed, in which case the next record cannot be found.
+    pub(crate) fn skip(cursor: &mut std::io::Cursor<&[u8]>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        DnsName::parse(cursor)?;
+        // TYPE, CLASS and TTL
+        if cursor.remaining() < 8 {
+            return Err("truncated record header".into());
+        }
+        cursor.advance(8);
+        let rdlength = cursor.try_get_u16()? as usize;
+        if cursor.remaining() < rdlength {
+            return Err("RDLENGTH exceeds the packet".into());
         }
+        cursor.advance(rdlength);
+        Ok(())
     }
 }
@@ -0,0 +1,194 @@
+// protocols\mdns\src\types.rs
+
+/// DNS resource record types used by mDNS / DNS-SD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DnsType {
+    /// IPv4 host address.
+    A,
+    /// Domain name pointer (service enumeration).
+    PTR,
+    /// Text strings (service metadata).
+    TXT,
+    /// IPv6 host address.
+    AAAA,
+    /// Service locator.
+    SRV,
+    /// Service binding (RFC 9460).
+    SVCB,
+    /// Request for all record types.
+    ANY,
+}
+
+impl DnsType {
+    /// Returns the wire value of the type.
+    pub fn to_u16(self) -> u16 {
+        match self {
+            DnsType::A => 1,
+            DnsType::PTR => 12,
+            DnsType::TXT => 16,
+            DnsType::AAAA => 28,
+            DnsType::SRV => 33,
+            DnsType::SVCB => 64,
+            DnsType::ANY => 255,
+        }
+    }
+
+    /// Maps a wire value back to a known type.
+    pub fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            1 => Some(DnsType::A),
+            12 => Some(DnsType::PTR),
+            16 => Some(DnsType::TXT),
+            28 => Some(DnsType::AAAA),
+            33 => Some(DnsType::SRV),
+            64 => Some(DnsType::SVCB),
+            255 => Some(DnsType::ANY),
+            _ => None,
+        }
+    }
+}
+
+/// DNS classes used by mDNS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DnsClass {
+    /// Internet class.
+    IN,
+    /// Request for any class.
+    ANY,
+}
+
+impl DnsClass {
+    /// Returns the wire value of the class.
+    pub fn to_u16(self) -> u16 {
+        match self {
+            DnsClass::IN => 1,
+            DnsClass::ANY => 255,
+        }
+    }
+
+    /// Maps a wire value back to a known class.
+    pub fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            1 => Some(DnsClass::IN),
+            255 => Some(DnsClass::ANY),
+            _ => None,
+        }
+    }
+}
+
+/// The DNS header flags field, one named field per bit group.
+///
+/// `DnsPacket::flags` stays a raw `u16` on the wire; convert with `from_u16`/`to_u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DnsFlags {
+    /// QR: set on responses, clear on queries.
+    pub response: bool,
+    /// OPCODE (4 bits); always 0 (standard query) in mDNS.
+    pub opcode: u8,
+    /// AA: the responder is authoritative for the answers.
+    pub authoritative: bool,
+    /// TC: more known answers or records follow in further packets.
+    pub truncated: bool,
+    /// RD: recursion desired.
+    pub recursion_desired: bool,
+    /// RA: recursion available.
+    pub recursion_available: bool,
+    /// The Z, AD and CD bits (3 bits), kept so unknown flags round-trip.
+    pub reserved: u8,
+    /// RCODE (4 bits); always 0 (no error) in mDNS.
+    pub response_code: u8,
+}
+
+impl DnsFlags {
+    const RESPONSE: u16 = 0x8000;
+    const AUTHORITATIVE: u16 = 0x0400;
+    const TRUNCATED: u16 = 0x0200;
+    const RECURSION_DESIRED: u16 = 0x0100;
+    const RECURSION_AVAILABLE: u16 = 0x0080;
+
+    /// Flags of an mDNS query: everything clear.
+    pub fn query() -> Self {
+        Self::default()
+    }
+
+    /// Flags of an mDNS response: QR and AA set.
+    pub fn authoritative_response() -> Self {
+        Self {
+            response: true,
+            authoritative: true,
+            ..Self::default()
+        }
+    }
+
+    /// Splits a wire flags value into its fields.
+    pub fn from_u16(value: u16) -> Self {
+        Self {
+            response: value & Self::RESPONSE != 0,
+            opcode: ((value >> 11) & 0x0F) as u8,
+            authoritative: value & Self::AUTHORITATIVE != 0,
+            truncated: value & Self::TRUNCATED != 0,
+            recursion_desired: value & Self::RECURSION_DESIRED != 0,
+            recursion_available: value & Self::RECURSION_AVAILABLE != 0,
+            reserved: ((value >> 4) & 0x07) as u8,
+            response_code: (value & 0x0F) as u8,
+        }
+    }
+
+    /// Returns the wire flags value. Out-of-range `opcode`, `reserved` and
+    /// `response_code` bits are masked off.
+    pub fn to_u16(self) -> u16 {
+        let bit = |set: bool, mask: u16| if set { mask } else { 0 };
+        bit(self.response, Self::RESPONSE)
+            | (u16::from(self.opcode & 0x0F) << 11)
+            | bit(self.authoritative, Self::AUTHORITATIVE)
+            | bit(self.truncated, Self::TRUNCATED)
+            | bit(self.recursion_desired, Self::RECURSION_DESIRED)
+            | bit(self.recursion_available, Self::RECURSION_AVAILABLE)
+            | (u16::from(self.reserved & 0x07) << 4)
+            | u16::from(self.response_code & 0x0F)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_type_round_trip() {
+        let types = [DnsType::A, DnsType::PTR, DnsType::TXT, DnsType::AAAA, DnsType::SRV, DnsType::ANY];
+        for dns_type in types {
+            assert_eq!(DnsType::from_u16(dns_type.to_u16()), Some(dns_type));
+        }
+        assert_eq!(DnsType::PTR.to_u16(), 12);
+        assert_eq!(DnsType::from_u16(99), None);
+    }
+
+    #[test]
+    fn test_dns_class_round_trip() {
+        for dns_class in [DnsClass::IN, DnsClass::ANY] {
+            assert_eq!(DnsClass::from_u16(dns_class.to_u16()), Some(dns_class));
+        }
+        assert_eq!(DnsClass::IN.to_u16(), 1);
+    }
+
+    #[test]
+    fn test_dns_flags_round_trip_each_bit() {
+        for bit in 0..16 {
+            let value = 1u16 << bit;
+            assert_eq!(DnsFlags::from_u16(value).to_u16(), value, "bit {} did not round-trip", bit);
+        }
+
+        let flags = DnsFlags::from_u16(0x8400);
+        assert!(flags.response && flags.authoritative);
+        assert!(!flags.truncated && !flags.recursion_desired && !flags.recursion_available);
+        assert_eq!(flags, DnsFlags::authoritative_response());
+
+        assert!(DnsFlags::from_u16(0x0200).truncated);
+        assert!(DnsFlags::from_u16(0x0100).recursion_desired);
+        assert!(DnsFlags::from_u16(0x0080).recursion_available);
+        assert_eq!(DnsFlags::from_u16(0x7800).opcode, 0x0F);
+        assert_eq!(DnsFlags::from_u16(0x000F).response_code, 0x0F);
+        assert_eq!(DnsFlags::query().to_u16(), 0x0000);
+        assert_eq!(DnsFlags::from_u16(0xFFFF).to_u16(), 0xFFFF);
+    }
+}
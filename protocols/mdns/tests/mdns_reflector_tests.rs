@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use mdns::{DnsClass, DnsName, DnsPacket, DnsQuestion, DnsType, MdnsError, MdnsReflector, PacketSink, ReflectorSide};
+    use std::future::Future;
+    use std::sync::{Arc, Mutex};
+
+    /// Sink that records packets instead of putting them on a socket.
+    #[derive(Default)]
+    struct MockSink {
+        sent: Mutex<Vec<DnsPacket>>,
+    }
+
+    impl PacketSink for MockSink {
+        fn send_packet(&self, packet: &DnsPacket) -> impl Future<Output = Result<(), MdnsError>> + Send {
+            self.sent.lock().unwrap().push(packet.clone());
+            async { Ok(()) }
+        }
+    }
+
+    fn query() -> DnsPacket {
+        let mut packet = DnsPacket::new();
+        packet.flags = 0x0000;
+        packet.questions.push(DnsQuestion::new(DnsName::new("_http._tcp.local.").unwrap(), DnsType::PTR, DnsClass::IN));
+        packet
+    }
+
+    #[tokio::test]
+    async fn test_query_forwarded_to_other_side_only() {
+        let side_a = Arc::new(MockSink::default());
+        let side_b = Arc::new(MockSink::default());
+        let reflector = MdnsReflector::new(side_a.clone(), side_b.clone());
+
+        assert!(reflector.forward(&query(), ReflectorSide::A).await.unwrap());
+
+        let forwarded = side_b.sent.lock().unwrap().clone();
+        assert_eq!(forwarded.len(), 1);
+        assert_eq!(forwarded[0].questions.len(), 1);
+        assert!(side_a.sent.lock().unwrap().is_empty());
+
+        // The forwarded copy shows up on side B's socket again; it must not echo back to A.
+        assert!(!reflector.forward(&forwarded[0], ReflectorSide::B).await.unwrap());
+        assert!(side_a.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_marker_survives_wire_round_trip() {
+        let side_a = Arc::new(MockSink::default());
+        let side_b = Arc::new(MockSink::default());
+        let reflector = MdnsReflector::new(side_a, side_b.clone());
+
+        reflector.forward(&query(), ReflectorSide::A).await.unwrap();
+        let bytes = side_b.sent.lock().unwrap()[0].serialize();
+        let parsed = DnsPacket::parse(&bytes).unwrap();
+
+        assert!(MdnsReflector::<MockSink, MockSink>::is_reflected(&parsed));
+    }
+}
@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
+    use std::net::Ipv4Addr;
     use std::sync::Arc;
-    use mdns::{MdnsService,DnsRecord,DnsName,DnsPacket};
+    use mdns::{MdnsService,MdnsError,BackoffState,StaticAddressProvider,InterfacePolicy,DnsRecord,DnsName,DnsPacket,DnsQuestion,DnsType,DnsClass,SrvData};
     async fn setup_mdns_service() -> Arc<MdnsService> {
         MdnsService::new(Some("TestNode.local".to_string()), "_testservice._tcp.local.")
             .await
@@ -92,4 +93,1399 @@ mod tests {
         let nodes = service.registry.list_nodes().await;
         assert!(!nodes.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_discover_returns_registered_service() {
+        let service = setup_mdns_service().await;
+        service
+            .register_local_service(
+                "Printer.local._ipp._tcp.local.".to_string(),
+                "_ipp._tcp.local.".to_string(),
+                631,
+                Some(300),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let found = service
+            .discover("_ipp._tcp.local.", std::time::Duration::from_millis(50))
+            .await
+            .expect("discover failed");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].port, 631);
+    }
+
+    #[tokio::test]
+    async fn test_proxied_service_uses_stored_address() {
+        let service = setup_mdns_service().await;
+        service
+            .register_proxy_service(
+                "Sleepy.local._smb._tcp.local.".to_string(),
+                "_smb._tcp.local.".to_string(),
+                445,
+                Some(300),
+                "Sleepy.local".to_string(),
+                "10.0.0.42".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let question = DnsQuestion::new(DnsName::new("_smb._tcp.local.").unwrap(), DnsType::PTR, DnsClass::IN);
+        let response = service
+            .create_query_response(&question)
+            .await
+            .expect("proxied service should be answered");
+
+        let a_record = response.answers.iter().find_map(|record| match record {
+            DnsRecord::A { name, ip, .. } => Some((name.to_string(), *ip)),
+            _ => None,
+        });
+        assert_eq!(a_record, Some(("Sleepy.local".to_string(), [10, 0, 0, 42])));
+        assert!(response.answers.iter().any(|record| matches!(
+            record,
+            DnsRecord::SRV { target, port: 445, .. } if target.to_string() == "Sleepy.local"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_receiver_stores_proxied_address() {
+        let gateway = setup_mdns_service().await;
+        gateway
+            .register_proxy_service(
+                "Sleepy.local._smb._tcp.local.".to_string(),
+                "_smb._tcp.local.".to_string(),
+                445,
+                Some(300),
+                "Sleepy.local".to_string(),
+                "10.0.0.42".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        let question = DnsQuestion::new(DnsName::new("_smb._tcp.local.").unwrap(), DnsType::PTR, DnsClass::IN);
+        let response = gateway
+            .create_query_response(&question)
+            .await
+            .expect("proxied service should be answered");
+
+        // The response arrives from the gateway, not from the sleeping device.
+        let receiver = MdnsService::new(Some("Receiver.local".to_string()), "_testservice._tcp.local.")
+            .await
+            .expect("Failed to create MdnsService");
+        let gateway_src = "192.168.1.10:5353".parse().unwrap();
+        receiver.process_response(&response, &gateway_src).await;
+
+        let node = receiver.registry.get_node("Sleepy.local").await.expect("proxied node should be learned");
+        assert_eq!(node.ip_address, "10.0.0.42");
+    }
+
+    #[test]
+    fn test_query_packet_uses_ptr_question() {
+        let packet = MdnsService::create_query_packet("_http._tcp.local.").unwrap();
+        let parsed = DnsPacket::parse(&packet.serialize()).unwrap();
+
+        assert_eq!(parsed.questions.len(), 1);
+        assert_eq!(parsed.questions[0].qtype, 12);
+        assert_eq!(parsed.questions[0].qclass, 1);
+        assert_eq!(parsed.questions[0].dns_type(), Some(DnsType::PTR));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_query_gets_unicast_response() {
+        let service = setup_mdns_service().await;
+        service
+            .register_local_service(
+                "Legacy.local._ipp._tcp.local.".to_string(),
+                "_ipp._tcp.local.".to_string(),
+                631,
+                Some(4500),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .unwrap();
+
+        // A legacy resolver queries from an ephemeral port rather than 5353.
+        let querier = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let src = querier.local_addr().unwrap();
+        let mut query = MdnsService::create_query_packet("_ipp._tcp.local.").unwrap();
+        query.id = 0x1234;
+
+        service.handle_packet(&query, &src).await;
+
+        let mut buf = [0u8; 1500];
+        let (len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            querier.recv_from(&mut buf),
+        )
+        .await
+        .expect("legacy querier should receive a unicast response")
+        .unwrap();
+        let response = DnsPacket::parse(&buf[..len]).unwrap();
+
+        assert_eq!(response.id, 0x1234);
+        assert_eq!(response.questions.len(), 1);
+        assert!(!response.answers.is_empty());
+        assert!(response.answers.iter().all(|record| record.ttl() <= 10));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_queries_and_responses() {
+        let service = setup_mdns_service().await;
+        let before = service.metrics();
+        assert_eq!(before.services_registered, 1); // default node service
+
+        // A legacy query for the default service type gets answered over unicast.
+        let querier = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let query = MdnsService::create_query_packet("_testservice._tcp.local.").unwrap();
+        service.handle_packet(&query, &querier.local_addr().unwrap()).await;
+
+        let mut response = DnsPacket::new();
+        response.answers.push(DnsRecord::A {
+            name: DnsName::new("Peer.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 77],
+        });
+        let peer = "192.168.1.77:5353".parse().unwrap();
+        service.handle_packet(&response, &peer).await;
+
+        let after = service.metrics();
+        assert_eq!(after.queries_received, before.queries_received + 1);
+        assert_eq!(after.queries_answered, before.queries_answered + 1);
+        assert_eq!(after.responses_processed, before.responses_processed + 1);
+        assert_eq!(after.nodes_discovered, before.nodes_discovered + 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_origin_is_rejected() {
+        let with_space = MdnsService::new(Some("My Laptop.local".to_string()), "_testservice._tcp.local.").await;
+        assert!(matches!(with_space, Err(MdnsError::InvalidName(_))));
+
+        let long_label = format!("{}.local", "a".repeat(64));
+        let oversized = MdnsService::new(Some(long_label), "_testservice._tcp.local.").await;
+        assert!(matches!(oversized, Err(MdnsError::InvalidName(_))));
+
+        let empty = MdnsService::new(Some(" ".to_string()), "_testservice._tcp.local.").await;
+        assert!(matches!(empty, Err(MdnsError::InvalidName(_))));
+    }
+
+    #[tokio::test]
+    async fn test_origin_is_normalized() {
+        let service = MdnsService::new(Some("Normalized.local.".to_string()), "_testservice._tcp.local.")
+            .await
+            .expect("valid origin should be accepted");
+        assert!(service.registry.get_node("Normalized.local").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_advertise_uses_address_provider() {
+        let provider = StaticAddressProvider::new(vec!["10.1.2.3".parse().unwrap()]);
+        let service = MdnsService::with_address_provider(
+            Some("Static.local".to_string()),
+            "_testservice._tcp.local.",
+            Ipv4Addr::UNSPECIFIED,
+            Arc::new(provider),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+
+        let packet = service.create_advertise_packet().await.unwrap();
+        let a_records: Vec<[u8; 4]> = packet
+            .answers
+            .iter()
+            .filter_map(|record| match record {
+                DnsRecord::A { ip, .. } => Some(*ip),
+                _ => None,
+            })
+            .collect();
+        assert!(!a_records.is_empty());
+        assert!(a_records.iter().all(|ip| *ip == [10, 1, 2, 3]));
+
+        // Query responses carry the same address, whoever asked.
+        let question = DnsQuestion::new(DnsName::new("_testservice._tcp.local.").unwrap(), DnsType::PTR, DnsClass::IN);
+        let response = service.create_query_response(&question).await.expect("local service should be answered");
+        let answered: Vec<Ipv4Addr> = response.answers.iter().filter_map(DnsRecord::as_a).collect();
+        assert_eq!(answered, vec![Ipv4Addr::new(10, 1, 2, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_own_advertisement_is_ignored() {
+        let service = MdnsService::with_address_provider(
+            Some("TestNode.local".to_string()),
+            "_testservice._tcp.local.",
+            Ipv4Addr::UNSPECIFIED,
+            Arc::new(StaticAddressProvider::new(vec![Ipv4Addr::new(192, 168, 1, 10).into()])),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        let services_before = service.registry.list_services().await.len();
+
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::SRV {
+            name: DnsName::new("TestNode.local._echo._tcp.local.").unwrap(),
+            ttl: 120,
+            priority: 0,
+            weight: 0,
+            port: 7,
+            target: DnsName::new("TestNode.local.").unwrap(),
+        });
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("TestNode.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 10],
+        });
+        let src = "192.168.1.10:5353".parse().unwrap();
+        service.handle_packet(&packet, &src).await;
+
+        assert_eq!(service.registry.list_services().await.len(), services_before);
+        assert_eq!(service.metrics().responses_processed, 0);
+        assert_eq!(service.metrics().conflicts_detected, 0);
+    }
+
+    #[tokio::test]
+    async fn test_foreign_packet_naming_our_origin_is_processed() {
+        let service = MdnsService::with_address_provider(
+            Some("TestNode.local".to_string()),
+            "_testservice._tcp.local.",
+            Ipv4Addr::UNSPECIFIED,
+            Arc::new(StaticAddressProvider::new(vec![Ipv4Addr::new(192, 168, 1, 10).into()])),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        let own_ip = service.registry.get_node("TestNode.local").await.map(|node| node.ip_address);
+
+        // A peer claims our name and, in the same packet, announces itself.
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("TestNode.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 50],
+        });
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("Peer.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 50],
+        });
+        let src = "192.168.1.50:5353".parse().unwrap();
+        service.handle_packet(&packet, &src).await;
+
+        let metrics = service.metrics();
+        assert_eq!(metrics.responses_processed, 1);
+        assert_eq!(metrics.conflicts_detected, 1);
+        let peer = service.registry.get_node("Peer.local").await.expect("peer should be discovered");
+        assert_eq!(peer.ip_address, "192.168.1.50");
+        assert_eq!(service.registry.get_node("TestNode.local").await.map(|node| node.ip_address), own_ip);
+    }
+
+    #[tokio::test]
+    async fn test_interest_filter_drops_unrelated_records() {
+        let service = setup_mdns_service().await;
+        service
+            .set_interest_filter(Some(["_ipp._tcp.local.".to_string()].into_iter().collect()))
+            .await;
+
+        let mut packet = DnsPacket::new();
+        for (node, service_type, ip) in [
+            ("Printer.local", "_ipp._tcp.local.", [192, 168, 1, 30]),
+            ("Speaker.local", "_airplay._tcp.local.", [192, 168, 1, 31]),
+        ] {
+            packet.answers.push(DnsRecord::SRV {
+                name: DnsName::new(&format!("{}.{}", node, service_type)).unwrap(),
+                ttl: 120,
+                priority: 0,
+                weight: 0,
+                port: 631,
+                target: DnsName::new(node).unwrap(),
+            });
+            packet.answers.push(DnsRecord::A {
+                name: DnsName::new(node).unwrap(),
+                ttl: 120,
+                ip,
+            });
+        }
+        let src = "192.168.1.30:5353".parse().unwrap();
+        service.handle_packet(&packet, &src).await;
+
+        let services = service.registry.list_services().await;
+        assert!(services.iter().any(|s| s.service_type == "_ipp._tcp.local"));
+        assert!(!services.iter().any(|s| s.service_type.starts_with("_airplay")));
+        assert!(service.registry.get_node("Printer.local").await.is_some());
+        assert!(service.registry.get_node("Speaker.local").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_backoff_state_override() {
+        let service = setup_mdns_service().await;
+        assert_eq!(service.backoff_state(), BackoffState::Active);
+        let base = service.next_query_interval();
+
+        service.set_backoff_state(BackoffState::Backoff);
+        assert_eq!(service.next_query_interval(), base * 2);
+        assert_eq!(service.next_query_interval(), base * 4);
+        assert_eq!(service.next_query_interval(), base * 8);
+
+        // Pinned: new records do not pull the service back to Active.
+        service.adjust_backoff_state(true);
+        assert_eq!(service.backoff_state(), BackoffState::Backoff);
+
+        service.clear_backoff_override();
+        service.adjust_backoff_state(true);
+        assert_eq!(service.backoff_state(), BackoffState::Active);
+        assert_eq!(service.next_query_interval(), base);
+    }
+
+    #[tokio::test]
+    async fn test_advertisement_split_at_packet_limit() {
+        let service = setup_mdns_service().await;
+        for i in 0..12 {
+            service
+                .register_local_service(
+                    format!("TestNode.local._service-number-{:02}._tcp.local.", i),
+                    format!("_service-number-{:02}._tcp.local.", i),
+                    9000 + i,
+                    Some(120),
+                    "TestNode.local".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let single = service.create_advertise_packet().await.unwrap();
+        assert_eq!(single.estimated_size(), single.serialize().len());
+        assert!(single.estimated_size() > service.max_packet_size());
+
+        let packets = service.create_advertise_packets().await.unwrap();
+        assert_eq!(packets.len(), 2);
+        for packet in &packets {
+            assert!(packet.serialize().len() <= service.max_packet_size());
+        }
+        assert_eq!(packets[0].flags & 0x0200, 0x0200);
+        assert_eq!(packets[1].flags & 0x0200, 0);
+        let answers: usize = packets.iter().map(|packet| packet.answers.len()).sum();
+        assert_eq!(answers, single.answers.len());
+    }
+
+    #[test]
+    fn test_dns_record_accessors() {
+        let a = DnsRecord::A {
+            name: DnsName::new("TestNode.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 10],
+        };
+        let ptr = DnsRecord::PTR {
+            name: DnsName::new("_testservice._tcp.local").unwrap(),
+            ttl: 4500,
+            ptr_name: DnsName::new("TestNode._testservice._tcp.local").unwrap(),
+        };
+        let mut srv = DnsRecord::SRV {
+            name: DnsName::new("TestNode._testservice._tcp.local").unwrap(),
+            ttl: 60,
+            priority: 1,
+            weight: 5,
+            port: 8080,
+            target: DnsName::new("TestNode.local").unwrap(),
+        };
+        let txt = DnsRecord::TXT {
+            name: DnsName::new("TestNode._testservice._tcp.local").unwrap(),
+            ttl: 30,
+            txt_data: b"\x06path=/".to_vec(),
+        };
+
+        assert_eq!(a.name(), &DnsName::new("TestNode.local").unwrap());
+        assert_eq!(a.ttl(), 120);
+        assert_eq!(a.as_a(), Some(Ipv4Addr::new(192, 168, 1, 10)));
+        assert!(a.as_srv().is_none());
+
+        assert_eq!(ptr.ttl(), 4500);
+        assert_eq!(ptr.as_ptr(), Some(&DnsName::new("TestNode._testservice._tcp.local").unwrap()));
+        assert!(ptr.as_a().is_none());
+
+        assert_eq!(
+            srv.as_srv(),
+            Some(SrvData {
+                priority: 1,
+                weight: 5,
+                port: 8080,
+                target: DnsName::new("TestNode.local").unwrap(),
+            })
+        );
+        assert!(srv.as_txt().is_none());
+        srv.set_ttl(10);
+        assert_eq!(srv.ttl(), 10);
+
+        assert_eq!(txt.ttl(), 30);
+        assert_eq!(txt.as_txt(), Some(&b"\x06path=/"[..]));
+        assert!(txt.as_ptr().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interface_policy_only_restricts_advertised_addresses() {
+        let provider = Arc::new(StaticAddressProvider::new(vec![
+            "127.0.0.1".parse().unwrap(),
+            "10.1.2.3".parse().unwrap(),
+            "10.4.5.6".parse().unwrap(),
+        ]));
+        let a_records = |packet: &DnsPacket| -> Vec<Ipv4Addr> {
+            packet.answers.iter().filter_map(DnsRecord::as_a).collect()
+        };
+
+        let only = MdnsService::with_interface_policy(
+            Some("Only.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::Only(vec![Ipv4Addr::new(10, 4, 5, 6)]),
+            provider.clone(),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        assert_eq!(only.advertised_addresses(), vec![Ipv4Addr::new(10, 4, 5, 6)]);
+        let packet = only.create_advertise_packet().await.unwrap();
+        assert!(!a_records(&packet).is_empty());
+        assert!(a_records(&packet).iter().all(|ip| *ip == Ipv4Addr::new(10, 4, 5, 6)));
+
+        let all = MdnsService::with_interface_policy(
+            Some("All.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            provider,
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        let packet = all.create_advertise_packet().await.unwrap();
+        assert_eq!(a_records(&packet), vec![Ipv4Addr::new(10, 1, 2, 3), Ipv4Addr::new(10, 4, 5, 6)]);
+    }
+
+    #[tokio::test]
+    async fn test_process_response_uses_additional_address_records() {
+        let service = setup_mdns_service().await;
+        let src = "192.168.1.77:5353".parse().unwrap();
+
+        let mut packet = DnsPacket::new();
+        packet.flags = 0x8400;
+        packet.answers.push(DnsRecord::SRV {
+            name: DnsName::new("Remote.local._testservice._tcp.local").unwrap(),
+            ttl: 120,
+            priority: 0,
+            weight: 0,
+            port: 9000,
+            target: DnsName::new("Remote.local").unwrap(),
+        });
+        packet.additionals.push(DnsRecord::AAAA {
+            name: DnsName::new("Remote.local").unwrap(),
+            ttl: 120,
+            ip: "fe80::77".parse::<std::net::Ipv6Addr>().unwrap().octets(),
+        });
+        packet.additionals.push(DnsRecord::A {
+            name: DnsName::new("Remote.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 99],
+        });
+
+        // Round-trip through the wire format so the AAAA record must not stop parsing.
+        let parsed = DnsPacket::parse(&packet.serialize()).unwrap();
+        assert_eq!(parsed.additionals.len(), 2);
+
+        service.process_response(&parsed, &src).await;
+        let node = service.registry.get_node("Remote.local").await.expect("node should be learned");
+        // The advertised address wins over the one the packet came from
+        assert_eq!(node.ip_address, "192.168.1.99");
+        assert!(node.services.contains(&"Remote.local._testservice._tcp.local".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_process_response_uses_additional_aaaa_without_ipv4() {
+        let service = setup_mdns_service().await;
+        let src = "192.168.1.78:5353".parse().unwrap();
+
+        let mut packet = DnsPacket::new();
+        packet.flags = 0x8400;
+        packet.answers.push(DnsRecord::SRV {
+            name: DnsName::new("Six.local._testservice._tcp.local").unwrap(),
+            ttl: 120,
+            priority: 0,
+            weight: 0,
+            port: 9000,
+            target: DnsName::new("Six.local").unwrap(),
+        });
+        packet.additionals.push(DnsRecord::AAAA {
+            name: DnsName::new("Six.local").unwrap(),
+            ttl: 120,
+            ip: "fe80::78".parse::<std::net::Ipv6Addr>().unwrap().octets(),
+        });
+
+        service.process_response(&packet, &src).await;
+        let node = service.registry.get_node("Six.local").await.expect("node should be learned");
+        assert_eq!(node.ip_address, "fe80::78");
+    }
+
+    #[tokio::test]
+    async fn test_strict_mode_reports_corrupt_answer() {
+        let service = setup_mdns_service().await;
+        let src = "192.168.1.79:5353".parse().unwrap();
+        assert!(!service.is_strict());
+
+        let name = DnsName::new("Corrupt.local").unwrap();
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A { name: name.clone(), ttl: 120, ip: [192, 168, 1, 79] });
+        packet.answers.push(DnsRecord::SRV {
+            name: DnsName::new("Corrupt.local._echo._tcp.local.").unwrap(),
+            ttl: 120,
+            priority: 0,
+            weight: 0,
+            port: 7,
+            target: name.clone(),
+        });
+        let intact = packet.serialize();
+
+        // Overwrite the first answer's record type with one we do not know.
+        let mut corrupt = intact.clone();
+        let type_offset = 12 + name.wire_len();
+        corrupt[type_offset..type_offset + 2].copy_from_slice(&0xFFFFu16.to_be_bytes());
+
+        // Lenient mode skips only the bad record; the SRV after it still parses.
+        let lenient = DnsPacket::parse(&corrupt).expect("lenient parse failed");
+        assert_eq!(lenient.answers.len(), 1);
+        assert!(lenient.answers[0].as_srv().is_some_and(|srv| srv.port == 7));
+        assert!(service.process_response_strict(&corrupt, &src).await.is_ok());
+        let services = service.registry.list_services().await;
+        assert!(services.iter().any(|s| s.id.starts_with("Corrupt.local._echo._tcp.local")), "{:?}", services);
+
+        service.set_strict(true);
+        assert!(service.process_response_strict(&intact, &src).await.is_ok());
+        match service.process_response_strict(&corrupt, &src).await {
+            Err(MdnsError::PacketError(msg)) => assert!(msg.contains("answer 0"), "unexpected message: {}", msg),
+            other => panic!("expected a packet error, got {:?}", other),
+        }
+    }
+
+    /// Runs discovery through a service whose node records live in `node_store` and
+    /// checks the node lands there.
+    async fn assert_discovery_uses_node_store(
+        node_store: Arc<dyn registry::RecordStore<mdns::NodeRecord>>,
+        node_id: &str,
+    ) {
+        let registry = mdns::MdnsRegistry::with_stores(
+            Arc::new(registry::InMemoryRegistry::new(50)),
+            Arc::clone(&node_store),
+        );
+        let service = MdnsService::with_registry(
+            Some("TestNode.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            Arc::new(StaticAddressProvider::new(vec![Ipv4Addr::new(192, 168, 1, 10).into()])),
+            registry,
+        )
+        .await
+        .expect("Failed to create MdnsService");
+
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new(node_id).unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 80],
+        });
+        service.process_response(&packet, &"192.168.1.80:5353".parse().unwrap()).await;
+
+        let stored = node_store.get(node_id).await.expect("store lookup failed");
+        assert_eq!(stored.map(|node| node.ip_address), Some("192.168.1.80".to_string()));
+        assert!(service.registry.get_node(node_id).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_registry_with_in_memory_record_store() {
+        let node_store: Arc<dyn registry::RecordStore<mdns::NodeRecord>> =
+            Arc::new(registry::InMemoryRegistry::new(usize::MAX));
+        assert_discovery_uses_node_store(node_store, "MemoryStore.local").await;
+    }
+
+    #[cfg(feature = "redis_registry")]
+    #[tokio::test]
+    async fn test_registry_with_redis_record_store() {
+        let node_store: Arc<dyn registry::RecordStore<mdns::NodeRecord>> =
+            match registry::RedisRegistry::new("redis://127.0.0.1:6379/0", usize::MAX, "mdns_expiration").await {
+                Ok(store) => Arc::new(store),
+                Err(err) => {
+                    eprintln!("Skipping Redis record store test: {}", err);
+                    return;
+                }
+            };
+        assert_discovery_uses_node_store(node_store, "RedisStore.local").await;
+    }
+
+    #[tokio::test]
+    async fn test_process_response_accepts_ipv4_mapped_source() {
+        let service = setup_mdns_service().await;
+        let src = "[::ffff:192.168.1.81]:5353".parse().unwrap();
+
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("Mapped.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 81],
+        });
+        service.process_response(&packet, &src).await;
+
+        let node = service.registry.get_node("Mapped.local").await.expect("node should be registered");
+        assert_eq!(node.ip_address, "192.168.1.81");
+    }
+
+    #[tokio::test]
+    async fn test_process_response_accepts_ipv6_source() {
+        let service = setup_mdns_service().await;
+        let src = "[fe80::82]:5353".parse().unwrap();
+
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("DualStack.local").unwrap(),
+            ttl: 120,
+            ip: [192, 168, 1, 82],
+        });
+        packet.answers.push(DnsRecord::AAAA {
+            name: DnsName::new("SixOnly.local").unwrap(),
+            ttl: 120,
+            ip: "fe80::83".parse::<std::net::Ipv6Addr>().unwrap().octets(),
+        });
+        service.process_response(&packet, &src).await;
+
+        let dual = service.registry.get_node("DualStack.local").await.expect("A record should be used");
+        assert_eq!(dual.ip_address, "192.168.1.82");
+        let six = service.registry.get_node("SixOnly.local").await.expect("AAAA record should be used");
+        assert_eq!(six.ip_address, "fe80::83");
+    }
+
+    #[tokio::test]
+    async fn test_discover_reuses_recent_empty_result() {
+        let service = setup_mdns_service().await;
+        let timeout = std::time::Duration::from_millis(20);
+        assert_eq!(service.negative_cache_ttl(), mdns::DEFAULT_NEGATIVE_CACHE_TTL);
+
+        let found = service.discover("_nothing._tcp.local.", timeout).await.expect("discover failed");
+        assert!(found.is_empty());
+        assert_eq!(service.metrics().queries_sent, 1);
+
+        // Within the window no new query goes out.
+        let found = service.discover("_nothing._tcp.local.", timeout).await.expect("discover failed");
+        assert!(found.is_empty());
+        assert_eq!(service.metrics().queries_sent, 1);
+
+        service.discover_with("_nothing._tcp.local.", timeout, true).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 2);
+
+        // A discovered instance clears the negative entry.
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::SRV {
+            name: DnsName::new("Late.local._nothing._tcp.local").unwrap(),
+            ttl: 120,
+            priority: 0,
+            weight: 0,
+            port: 7000,
+            target: DnsName::new("Late.local").unwrap(),
+        });
+        service.process_response(&packet, &"192.168.1.84:5353".parse().unwrap()).await;
+
+        let found = service.discover("_nothing._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 3);
+        assert_eq!(found.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_signed_service_verification_on_discovery() {
+        use identity::{PKITraits, RSAkeyPair};
+        use mdns::SignatureVerifier;
+
+        let keypair = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+        let signer = MdnsService::new(Some("Signer.local".to_string()), "_testservice._tcp.local.")
+            .await
+            .expect("Failed to create MdnsService");
+        signer
+            .register_signed_local_service(
+                "Signer.local._secure._tcp.local.".to_string(),
+                "_secure._tcp.local.".to_string(),
+                9100,
+                Some(120),
+                "Signer.local".to_string(),
+                &keypair,
+            )
+            .await
+            .expect("signed registration failed");
+        let packet = signer.create_advertise_packet().await.unwrap();
+        let packet = DnsPacket::parse(&packet.serialize()).unwrap();
+
+        let receiver = setup_mdns_service().await;
+        receiver.set_signature_verifier(Some(SignatureVerifier::of::<RSAkeyPair>(vec![
+            keypair.get_public_key_raw_bytes(),
+        ])));
+        receiver.set_require_signatures(true);
+        let src = "192.168.1.85:5353".parse().unwrap();
+
+        // Tampering with the advertised port breaks the signature.
+        let mut tampered = packet.clone();
+        for record in &mut tampered.answers {
+            if let DnsRecord::SRV { port, .. } = record {
+                *port = 9101;
+            }
+        }
+        let mut events = receiver.get_event_receiver();
+        receiver.process_response(&tampered, &src).await;
+        assert!(receiver.registry.get_service("Signer.local._secure._tcp.local").await.is_none());
+        while let Ok(event) = events.try_recv() {
+            assert!(
+                !matches!(event, mdns::MdnsEvent::Discovered(DnsRecord::SRV { .. })),
+                "tampered service must not be reported"
+            );
+        }
+
+        receiver.process_response(&packet, &src).await;
+        let service = receiver
+            .registry
+            .get_service("Signer.local._secure._tcp.local")
+            .await
+            .expect("signed service should be accepted");
+        assert!(service.authenticated);
+        assert_eq!(service.signature.unwrap().public_key, keypair.get_public_key_raw_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_signature_checks_beyond_budget_are_unauthenticated() {
+        use identity::{PKITraits, RSAkeyPair, VerificationBudget};
+        use mdns::SignatureVerifier;
+
+        let keypair = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+        let signer = MdnsService::new(Some("Budget.local".to_string()), "_testservice._tcp.local.")
+            .await
+            .expect("Failed to create MdnsService");
+        signer
+            .register_signed_local_service(
+                "Budget.local._secure._tcp.local.".to_string(),
+                "_secure._tcp.local.".to_string(),
+                9100,
+                Some(120),
+                "Budget.local".to_string(),
+                &keypair,
+            )
+            .await
+            .expect("signed registration failed");
+        let packet = DnsPacket::parse(&signer.create_advertise_packet().await.unwrap().serialize()).unwrap();
+
+        let receiver = setup_mdns_service().await;
+        let verifier = SignatureVerifier::of::<RSAkeyPair>(vec![keypair.get_public_key_raw_bytes()])
+            .with_budget(Arc::new(VerificationBudget::new(1)));
+        receiver.set_signature_verifier(Some(verifier));
+        let src = "192.168.1.87:5353".parse().unwrap();
+        let id = "Budget.local._secure._tcp.local";
+
+        receiver.process_response(&packet, &src).await;
+        assert!(receiver.registry.get_service(id).await.unwrap().authenticated);
+
+        // The single check per second is spent, so the repeat is not verified.
+        receiver.process_response(&packet, &src).await;
+        assert!(!receiver.registry.get_service(id).await.unwrap().authenticated);
+    }
+
+    #[tokio::test]
+    async fn test_service_signed_by_untrusted_key_is_rejected() {
+        use identity::{PKITraits, RSAkeyPair};
+        use mdns::SignatureVerifier;
+
+        let trusted = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+        let attacker = RSAkeyPair::generate_key_pair().expect("Key pair generation failed");
+
+        // A forged advertisement, validly signed with the attacker's own key.
+        let forger = MdnsService::new(Some("Forger.local".to_string()), "_testservice._tcp.local.")
+            .await
+            .expect("Failed to create MdnsService");
+        forger
+            .register_signed_local_service(
+                "Forger.local._secure._tcp.local.".to_string(),
+                "_secure._tcp.local.".to_string(),
+                9100,
+                Some(120),
+                "Forger.local".to_string(),
+                &attacker,
+            )
+            .await
+            .expect("signed registration failed");
+        let packet = DnsPacket::parse(&forger.create_advertise_packet().await.unwrap().serialize()).unwrap();
+
+        let receiver = setup_mdns_service().await;
+        receiver.set_signature_verifier(Some(SignatureVerifier::of::<RSAkeyPair>(vec![
+            trusted.get_public_key_raw_bytes(),
+        ])));
+        let src = "192.168.1.86:5353".parse().unwrap();
+
+        receiver.process_response(&packet, &src).await;
+        let service = receiver
+            .registry
+            .get_service("Forger.local._secure._tcp.local")
+            .await
+            .expect("without require_signatures the service is still recorded");
+        assert!(!service.authenticated, "a self-signed record must not be authenticated");
+
+        receiver.set_require_signatures(true);
+        receiver.registry.remove_service("Forger.local._secure._tcp.local").await.unwrap();
+        receiver.process_response(&packet, &src).await;
+        assert!(receiver.registry.get_service("Forger.local._secure._tcp.local").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_registry_name_is_skipped() {
+        let service = setup_mdns_service().await;
+        service
+            .register_local_service(
+                "Good.local._testservice._tcp.local.".to_string(),
+                "_testservice._tcp.local.".to_string(),
+                8080,
+                Some(120),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .unwrap();
+
+        // Bypass registration validation, as a bad record from elsewhere would.
+        let long_label = "x".repeat(64);
+        let bad = mdns::ServiceRecord::builder(format!("{}._testservice._tcp.local.", long_label), "_testservice._tcp.local.")
+            .port(9090)
+            .origin("TestNode.local")
+            .node_id("TestNode.local")
+            .build();
+        service.registry.add_service(bad).await.unwrap();
+
+        let packet = service.create_advertise_packet().await.unwrap();
+        let srv_ports: Vec<u16> = packet.answers.iter().filter_map(|record| record.as_srv().map(|srv| srv.port)).collect();
+        assert!(srv_ports.contains(&8080));
+        assert!(!srv_ports.contains(&9090));
+
+        let question = DnsQuestion::new(DnsName::new("_testservice._tcp.local.").unwrap(), DnsType::PTR, DnsClass::IN);
+        let response = service.create_query_response(&question).await.expect("valid service should be answered");
+        assert!(response.answers.iter().all(|record| record.as_srv().is_none_or(|srv| srv.port != 9090)));
+
+        let rejected = service
+            .register_local_service(
+                format!("{}._testservice._tcp.local.", long_label),
+                "_testservice._tcp.local.".to_string(),
+                9090,
+                Some(120),
+                "TestNode.local".to_string(),
+            )
+            .await;
+        assert!(matches!(rejected, Err(MdnsError::InvalidName(_))));
+    }
+
+    #[tokio::test]
+    async fn test_source_policy_drops_disallowed_response() {
+        let service = setup_mdns_service().await;
+        service.set_source_policy(mdns::SourcePolicy::allow_all().allow("192.168.1.0/24".parse().unwrap()));
+
+        let mut packet = DnsPacket::new();
+        packet.answers.push(DnsRecord::A {
+            name: DnsName::new("Rogue.local").unwrap(),
+            ttl: 120,
+            ip: [10, 0, 0, 9],
+        });
+
+        let outside = "10.0.0.9:5353".parse().unwrap();
+        service.process_response(&packet, &outside).await;
+        assert!(service.registry.get_node("Rogue.local").await.is_none());
+
+        let inside = "192.168.1.9:5353".parse().unwrap();
+        service.process_response(&packet, &inside).await;
+        assert!(service.registry.get_node("Rogue.local").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_default_and_priority_services_lead_split_advertisement() {
+        let service = setup_mdns_service().await;
+        for i in 0..12 {
+            service
+                .register_local_service(
+                    format!("TestNode.local._service-number-{:02}._tcp.local.", i),
+                    format!("_service-number-{:02}._tcp.local.", i),
+                    9000 + i,
+                    Some(120),
+                    "TestNode.local".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+        let urgent = "TestNode.local._service-number-11._tcp.local.".to_string();
+        service.set_priority_services(vec![urgent.clone()]);
+
+        let packets = service.create_advertise_packets().await.unwrap();
+        assert!(packets.len() > 1);
+
+        let ptr_targets: Vec<String> = packets[0]
+            .answers
+            .iter()
+            .filter_map(|record| record.as_ptr().map(|name| name.to_string()))
+            .collect();
+        assert_eq!(ptr_targets[0], "TestNode.local._testservice._tcp.local");
+        assert_eq!(ptr_targets[1], urgent.trim_end_matches('.'));
+    }
+
+    #[tokio::test]
+    async fn test_any_query_returns_every_record_type() {
+        let service = setup_mdns_service().await;
+        let record = mdns::ServiceRecord::builder("TestNode.local._printer._tcp.local.", "_printer._tcp.local.")
+            .port(631)
+            .ttl(Some(120))
+            .origin("TestNode.local")
+            .txt("rp", "queue1")
+            .build();
+        service.registry.add_service(record).await.unwrap();
+
+        let types = |packet: &DnsPacket| {
+            let mut types: Vec<DnsType> = packet.answers.iter().map(DnsRecord::dns_type).collect();
+            types.dedup();
+            types
+        };
+
+        let any = DnsQuestion::new(DnsName::new("_printer._tcp.local.").unwrap(), DnsType::ANY, DnsClass::ANY);
+        let response = service.create_query_response(&any).await.expect("ANY query should be answered");
+        assert_eq!(types(&response), vec![DnsType::PTR, DnsType::SRV, DnsType::TXT, DnsType::A]);
+
+        let srv = DnsQuestion::new(DnsName::new("TestNode.local._printer._tcp.local.").unwrap(), DnsType::SRV, DnsClass::IN);
+        let response = service.create_query_response(&srv).await.expect("SRV query should be answered");
+        assert_eq!(types(&response), vec![DnsType::SRV]);
+        assert_eq!(response.answers[0].as_srv().unwrap().port, 631);
+
+        let host = DnsQuestion::new(DnsName::new("TestNode.local").unwrap(), DnsType::A, DnsClass::IN);
+        let response = service.create_query_response(&host).await.expect("A query should be answered");
+        let advertised = service.advertised_addresses();
+        assert!(!response.answers.is_empty());
+        assert!(response.answers.iter().all(|record| record.as_a().is_some_and(|ip| advertised.contains(&ip))));
+
+        let aaaa = DnsQuestion::new(DnsName::new("TestNode.local").unwrap(), DnsType::AAAA, DnsClass::IN);
+        assert!(service.create_query_response(&aaaa).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_expires_with_mock_clock() {
+        let service = setup_mdns_service().await;
+        let clock = std::sync::Arc::new(mdns::MockClock::new(1_000_000));
+        service.set_clock(clock.clone());
+        service.set_negative_cache_ttl(std::time::Duration::from_secs(60));
+        let timeout = std::time::Duration::ZERO;
+
+        service.discover("_idle._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 1);
+
+        clock.advance(std::time::Duration::from_secs(59));
+        service.discover("_idle._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 1, "still inside the negative cache TTL");
+
+        // A minute of mock time passes without any real waiting.
+        clock.advance(std::time::Duration::from_secs(1));
+        service.discover("_idle._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 2);
+    }
+
+    #[tokio::test]
+    async fn test_clock_stepping_backwards_expires_negative_cache() {
+        let service = setup_mdns_service().await;
+        let clock = std::sync::Arc::new(mdns::MockClock::new(10_000_000));
+        service.set_clock(clock.clone());
+        service.set_negative_cache_ttl(std::time::Duration::from_secs(60));
+        let timeout = std::time::Duration::ZERO;
+
+        service.discover("_idle._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 1);
+
+        // An NTP step back an hour must neither panic nor pin the cached result
+        // until the clock catches up.
+        clock.set_ms(10_000_000 - 3_600_000);
+        service.discover("_idle._tcp.local.", timeout).await.expect("discover failed");
+        assert_eq!(service.metrics().queries_sent, 2);
+
+        let system = mdns::SystemClock;
+        let first = mdns::Clock::now_ms(&system);
+        assert!(mdns::Clock::now_ms(&system) >= first);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_subscriber_does_not_block_processing() {
+        let service = setup_mdns_service().await;
+        // Subscribed but never read from.
+        let _stalled = service.get_event_receiver();
+
+        let src: std::net::SocketAddr = "192.168.1.90:5353".parse().unwrap();
+        let processing = async {
+            for i in 0..150u8 {
+                let mut packet = DnsPacket::new();
+                packet.answers.push(DnsRecord::A {
+                    name: DnsName::new(&format!("Peer{}.local", i)).unwrap(),
+                    ttl: 120,
+                    ip: [10, 0, 1, i],
+                });
+                service.process_response(&packet, &src).await;
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), processing)
+            .await
+            .expect("process_response stalled behind an unread subscriber");
+
+        // The channel holds 100 events; everything past that displaced an unread one.
+        assert_eq!(service.metrics().events_dropped, 50);
+    }
+
+    #[tokio::test]
+    async fn test_first_periodic_query_requests_unicast_response() {
+        let service = setup_mdns_service().await;
+
+        let first = service.create_periodic_query_packet("_testservice._tcp.local.").unwrap();
+        assert!(first.questions.iter().all(|q| q.unicast_response()));
+        assert_eq!(first.questions[0].dns_class(), Some(DnsClass::IN));
+
+        let second = service.create_periodic_query_packet("_testservice._tcp.local.").unwrap();
+        assert!(second.questions.iter().all(|q| !q.unicast_response()));
+
+        // An interface change re-arms the QU bit for one query.
+        service.request_unicast_query();
+        let rearmed = service.create_periodic_query_packet("_testservice._tcp.local.").unwrap();
+        assert!(rearmed.questions[0].unicast_response());
+        let parsed = DnsPacket::parse(&rearmed.serialize()).unwrap();
+        assert!(parsed.questions[0].unicast_response());
+    }
+
+    #[tokio::test]
+    async fn test_subscription_reports_service_registered_later() {
+        let service = setup_mdns_service().await;
+        let mut browse = service.subscribe_service_type("_browse._tcp.local.");
+
+        let register = async {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            service
+                .register_local_service(
+                    "Printer._browse._tcp.local.".to_string(),
+                    "_browse._tcp.local.".to_string(),
+                    631,
+                    Some(120),
+                    "TestNode.local".to_string(),
+                )
+                .await
+                .expect("Failed to register service");
+        };
+        let (event, _) = tokio::join!(
+            tokio::time::timeout(std::time::Duration::from_secs(5), browse.next()),
+            register
+        );
+
+        match event.expect("no event before the timeout") {
+            Some(mdns::ServiceEvent::Added(record)) => {
+                assert_eq!(record.id, "Printer._browse._tcp.local.");
+                assert_eq!(record.port, 631);
+            }
+            other => panic!("Expected Added, got {:?}", other),
+        }
+        // The node's default service is of a different type and never shows up.
+        assert_eq!(browse.service_type(), "_browse._tcp.local.");
+    }
+
+    #[tokio::test]
+    async fn test_pinned_source_port() {
+        let service = MdnsService::with_source_port(
+            Some("PinnedNode.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            Arc::new(StaticAddressProvider::new(vec![])),
+            mdns::MdnsRegistry::new(),
+            53541,
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        assert_eq!(service.source_addr().unwrap().port(), 53541);
+
+        // Without a pinned port, packets go out from the mDNS port itself.
+        let default = setup_mdns_service().await;
+        assert_eq!(default.source_addr().unwrap().port(), 5353);
+    }
+
+    #[tokio::test]
+    async fn test_service_type_validation() {
+        let service = setup_mdns_service().await;
+        assert!(service.validates_service_types());
+
+        service
+            .register_local_service(
+                "Web.local._http._tcp.local.".to_string(),
+                "_http._tcp.local.".to_string(),
+                8080,
+                Some(120),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .expect("A DNS-SD service type should be accepted");
+
+        for bad in ["http", "_http.local.", "_http._sctp.local.", "_-http._tcp.local."] {
+            let result = service
+                .register_local_service(
+                    format!("Bad.local.{}", bad),
+                    bad.to_string(),
+                    8080,
+                    Some(120),
+                    "TestNode.local".to_string(),
+                )
+                .await;
+            assert!(matches!(result, Err(MdnsError::InvalidName(_))), "{} was accepted", bad);
+        }
+        assert!(service.registry.get_service("Bad.local.http").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_service_type_validation_opt_out() {
+        let service = setup_mdns_service().await;
+        service.set_validate_service_types(false);
+
+        service
+            .register_local_service(
+                "Custom.local.http".to_string(),
+                "http".to_string(),
+                8080,
+                Some(120),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .expect("Validation is disabled");
+        assert!(service.registry.get_service("Custom.local.http").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_alpn_service_is_advertised_with_svcb() {
+        let service = setup_mdns_service().await;
+        service
+            .register_local_service_with_alpn(
+                "Web.local._https._tcp.local.".to_string(),
+                "_https._tcp.local.".to_string(),
+                8443,
+                Some(120),
+                "TestNode.local".to_string(),
+                vec!["h2".to_string()],
+            )
+            .await
+            .expect("Failed to register service");
+
+        let packet = service.create_advertise_packet().await.expect("Failed to build advertisement");
+        let svcb: Vec<_> = packet.answers.iter().filter(|record| record.dns_type() == DnsType::SVCB).collect();
+        assert_eq!(svcb.len(), 1, "only the ALPN service gets an SVCB record");
+        assert_eq!(svcb[0].name().to_string(), "Web.local._https._tcp.local");
+        let params = svcb[0].as_svcb().unwrap();
+        assert!(params.contains(&mdns::SvcParam::Alpn(vec!["h2".to_string()])));
+        assert!(params.contains(&mdns::SvcParam::Port(8443)));
+    }
+
+    #[tokio::test]
+    async fn test_registry_printing_is_opt_in() {
+        let service = setup_mdns_service().await;
+        assert_eq!(service.registry_print_interval(), None);
+
+        service.run("_testservice._tcp.local.".to_string(), 60, 60).await;
+        assert!(!service.is_printing_registry(), "run printed the registry without opting in");
+
+        service.set_registry_print_interval(Some(std::time::Duration::from_millis(50)));
+        service.run("_testservice._tcp.local.".to_string(), 60, 60).await;
+        assert!(service.is_printing_registry());
+    }
+
+    /// Address provider whose addresses can be swapped, like a host changing networks.
+    struct SwitchableAddressProvider(std::sync::Mutex<Vec<std::net::IpAddr>>);
+
+    impl mdns::AddressProvider for SwitchableAddressProvider {
+        fn local_addresses(&self) -> Vec<std::net::IpAddr> {
+            self.0.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_network_change_reannounces_with_new_address() {
+        let provider = Arc::new(SwitchableAddressProvider(std::sync::Mutex::new(vec![
+            Ipv4Addr::new(10, 0, 0, 5).into(),
+        ])));
+        let service = MdnsService::with_interface_policy(
+            Some("Roaming.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            provider.clone(),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+
+        // A peer learned on the old network.
+        let peer = mdns::ServiceRecord::builder("Peer.local._testservice._tcp.local", "_testservice._tcp.local")
+            .port(8080)
+            .ttl(Some(120))
+            .origin("Peer.local")
+            .build();
+        service.registry.add_service(peer).await.unwrap();
+        service
+            .registry
+            .add_node(mdns::NodeRecord {
+                id: "Peer.local".to_string(),
+                ip_address: "10.0.0.9".to_string(),
+                ttl: Some(120),
+                services: vec!["Peer.local._testservice._tcp.local".to_string()],
+                last_seen: std::time::SystemTime::now(),
+            })
+            .await
+            .unwrap();
+
+        *provider.0.lock().unwrap() = vec![Ipv4Addr::new(192, 168, 7, 20).into()];
+        let sent_before = service.metrics().advertisements_sent;
+        service.on_network_change().await.expect("Network change handling failed");
+        assert_eq!(service.metrics().advertisements_sent, sent_before + 1);
+
+        let packet = service.create_advertise_packet().await.unwrap();
+        let addresses: Vec<Ipv4Addr> = packet.answers.iter().filter_map(DnsRecord::as_a).collect();
+        assert!(!addresses.is_empty());
+        assert!(addresses.iter().all(|ip| *ip == Ipv4Addr::new(192, 168, 7, 20)));
+
+        assert!(service.registry.get_service("Peer.local._testservice._tcp.local").await.is_none());
+        assert!(service.registry.get_node("Peer.local").await.is_none());
+        let own = service.registry.get_node("Roaming.local").await.expect("Own node was flushed");
+        assert_eq!(own.ip_address, "192.168.7.20");
+        assert!(!service.registry.list_services_by_node("Roaming.local").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_services_and_backoff() {
+        let service = setup_mdns_service().await;
+        let status = service.status().await;
+        assert!(status.is_socket_bound());
+        assert_eq!(status.backoff_state, mdns::BackoffState::Active);
+        assert!(status.last_advertisement.is_none());
+        assert!(status.last_query.is_none());
+
+        service.register_default_node_service().await.unwrap();
+        service.register_local_service("Extra.local".to_string(), "_extra._tcp.local.".to_string(), 9000, Some(120), "TestNode.local".to_string())
+            .await
+            .unwrap();
+        service.set_backoff_state(mdns::BackoffState::Backoff);
+
+        let status = service.status().await;
+        assert_eq!(status.local_services, 2);
+        assert_eq!(status.services, 2);
+        assert_eq!(status.nodes, 1);
+        assert_eq!(status.backoff_state, mdns::BackoffState::Backoff);
+        assert_eq!(status.metrics, service.metrics());
+    }
+
+    #[tokio::test]
+    async fn test_missing_origin_defaults_to_hostname() {
+        let service = MdnsService::new(None, "_testservice._tcp.local.")
+            .await
+            .expect("Failed to create MdnsService");
+        let origin = service.origin().await;
+        assert_ne!(origin, "UnknownOrigin.local");
+        if let Some(hostname) = mdns::local_hostname() {
+            assert_eq!(origin, format!("{}.local", hostname));
+        }
+        assert!(!service.registry.list_services_by_node(&origin).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_advertise_packet_is_cached_until_services_change() {
+        let provider = Arc::new(SwitchableAddressProvider(std::sync::Mutex::new(vec![Ipv4Addr::new(10, 0, 0, 7).into()])));
+        let service = MdnsService::with_interface_policy(
+            Some("Cached.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            provider.clone(),
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        let has_port = |packet: &DnsPacket, port: u16| {
+            packet.answers.iter().any(|record| matches!(record, DnsRecord::SRV { port: p, .. } if *p == port))
+        };
+
+        let first = service.create_advertise_packet().await.unwrap();
+        let second = service.create_advertise_packet().await.unwrap();
+        assert_eq!(first.serialize(), second.serialize());
+
+        // A service added behind the service's back is only picked up on invalidation.
+        let direct = mdns::ServiceRecord::builder("Cached.local._ftp._tcp.local", "_ftp._tcp.local")
+            .port(21)
+            .origin("Cached.local")
+            .build();
+        service.registry.add_service(direct).await.unwrap();
+        assert!(!has_port(&service.create_advertise_packet().await.unwrap(), 21), "packet should be cached");
+
+        service
+            .register_local_service(
+                "Cached.local._http._tcp.local".to_string(),
+                "_http._tcp.local".to_string(),
+                8080,
+                Some(120),
+                "Cached.local".to_string(),
+            )
+            .await
+            .unwrap();
+        let third = service.create_advertise_packet().await.unwrap();
+        assert!(third.answers.len() > first.answers.len());
+        assert!(has_port(&third, 8080), "registering a service should invalidate the cache");
+        assert!(has_port(&third, 21));
+
+        // Removing a local service drops it from the next advertisement.
+        service.registry.remove_service("Cached.local._http._tcp.local").await.unwrap();
+        assert!(!has_port(&service.create_advertise_packet().await.unwrap(), 8080));
+
+        // So does a change of address reported by the provider.
+        *provider.0.lock().unwrap() = vec![Ipv4Addr::new(10, 0, 0, 8).into()];
+        let moved = service.create_advertise_packet().await.unwrap();
+        let addresses: Vec<Ipv4Addr> = moved.answers.iter().filter_map(DnsRecord::as_a).collect();
+        assert!(!addresses.is_empty());
+        assert!(addresses.iter().all(|ip| *ip == Ipv4Addr::new(10, 0, 0, 8)));
+    }
+
+    #[tokio::test]
+    async fn test_advertise_cache_drops_expired_service() {
+        let service = setup_mdns_service().await;
+        service
+            .register_local_service(
+                "TestNode.local._brief._tcp.local".to_string(),
+                "_brief._tcp.local".to_string(),
+                7000,
+                Some(1),
+                "TestNode.local".to_string(),
+            )
+            .await
+            .unwrap();
+        let packet = service.create_advertise_packet().await.unwrap();
+        assert!(packet.answers.iter().any(|record| matches!(record, DnsRecord::SRV { port: 7000, .. })));
+
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+        let packet = service.create_advertise_packet().await.unwrap();
+        assert!(!packet.answers.iter().any(|record| matches!(record, DnsRecord::SRV { port: 7000, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_responder_only_answers_without_querying() {
+        let service = MdnsService::with_source_port(
+            Some("Sensor.local".to_string()),
+            "_testservice._tcp.local.",
+            InterfacePolicy::All,
+            Arc::new(StaticAddressProvider::new(vec![])),
+            mdns::MdnsRegistry::new(),
+            53542,
+        )
+        .await
+        .expect("Failed to create MdnsService");
+        service.run_responder_only().await;
+
+        // A legacy resolver queries the responder directly and gets a unicast answer.
+        let querier = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut query = MdnsService::create_query_packet("_testservice._tcp.local.").unwrap();
+        query.id = 0x4242;
+        querier.send_to(&query.serialize(), "127.0.0.1:53542").await.unwrap();
+
+        let mut buf = [0u8; 1500];
+        let (len, _) = tokio::time::timeout(std::time::Duration::from_secs(2), querier.recv_from(&mut buf))
+            .await
+            .expect("responder should answer the query")
+            .unwrap();
+        let response = DnsPacket::parse(&buf[..len]).unwrap();
+        assert_eq!(response.id, 0x4242);
+        assert!(!response.answers.is_empty());
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let metrics = service.metrics();
+        assert!(metrics.queries_answered >= 1);
+        assert_eq!(metrics.queries_sent, 0, "responder-only mode must never query");
+        assert_eq!(metrics.responses_processed, 0);
+    }
 }
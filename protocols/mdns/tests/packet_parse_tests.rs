@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use mdns::DnsPacket;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn seed_corpus() -> Vec<(String, Vec<u8>)> {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/parse_packet");
+        let mut seeds: Vec<_> = fs::read_dir(&dir)
+            .expect("Failed to read seed corpus")
+            .map(|entry| {
+                let path = entry.expect("Failed to read corpus entry").path();
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, fs::read(&path).expect("Failed to read seed"))
+            })
+            .collect();
+        seeds.sort();
+        assert!(!seeds.is_empty(), "seed corpus is empty");
+        seeds
+    }
+
+    #[test]
+    fn test_seed_corpus_round_trips() {
+        for (name, bytes) in seed_corpus() {
+            let packet = DnsPacket::parse_strict(&bytes).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert_eq!(packet.serialize(), bytes, "{} did not round trip", name);
+        }
+    }
+
+    #[test]
+    fn test_truncated_seeds_do_not_panic() {
+        for (_, bytes) in seed_corpus() {
+            for len in 0..bytes.len() {
+                let _ = DnsPacket::parse(&bytes[..len]);
+                assert!(DnsPacket::parse_strict(&bytes[..len]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_corrupted_seeds_do_not_panic() {
+        for (_, bytes) in seed_corpus() {
+            for index in 0..bytes.len() {
+                for value in [0x00, 0x3F, 0x40, 0xC0, 0xFF] {
+                    let mut corrupted = bytes.clone();
+                    corrupted[index] = value;
+                    let _ = DnsPacket::parse(&corrupted);
+                    let _ = DnsPacket::parse_strict(&corrupted);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_oversized_rdlength_is_an_error() {
+        // Header with one answer, then a record whose RDLENGTH runs past the end.
+        let mut bytes = vec![0, 0, 0x84, 0, 0, 0, 0, 1, 0, 0, 0, 0];
+        bytes.extend_from_slice(&[4, b'n', b'o', b'd', b'e', 0]);
+        bytes.extend_from_slice(&[0, 1, 0, 1, 0, 0, 0, 120, 0xFF, 0xFF, 10, 0, 0, 1]);
+        assert!(DnsPacket::parse_strict(&bytes).is_err());
+        assert!(DnsPacket::parse(&bytes).expect("lenient parse failed").answers.is_empty());
+    }
+
+    fn seed(name: &str) -> DnsPacket {
+        let (_, bytes) = seed_corpus().into_iter().find(|(seed, _)| seed == name).expect("missing seed");
+        DnsPacket::parse_strict(&bytes).expect("seed failed to parse")
+    }
+
+    #[test]
+    fn test_serialize_into_appends_to_existing_contents() {
+        let packet = seed("query");
+        let expected = packet.serialize();
+
+        let mut buffer = b"prefix".to_vec();
+        packet.serialize_into(&mut buffer);
+        assert_eq!(&buffer[..6], b"prefix");
+        assert_eq!(&buffer[6..], expected.as_slice());
+    }
+
+    #[test]
+    fn test_serialize_into_reused_buffer_matches_fresh_output() {
+        let large = seed("advertise");
+        let small = seed("query");
+        assert!(small.serialize().len() < large.serialize().len());
+
+        let mut buffer = Vec::new();
+        large.serialize_into(&mut buffer);
+        assert_eq!(buffer, large.serialize());
+
+        // A shorter packet after a longer one must not keep any of the earlier bytes.
+        buffer.clear();
+        small.serialize_into(&mut buffer);
+        assert_eq!(buffer, small.serialize());
+    }
+
+    #[test]
+    fn test_merge_skips_duplicate_records() {
+        use mdns::{DnsName, DnsRecord};
+
+        let a_record = |owner: &str, ttl| DnsRecord::A {
+            name: DnsName::new(owner).unwrap(),
+            ttl,
+            ip: [10, 0, 0, 1],
+        };
+        let mut first = DnsPacket::new();
+        first.answers.push(a_record("node.local", 120));
+        let mut second = DnsPacket::new();
+        // Same record apart from case and TTL, plus one new record.
+        second.answers.push(a_record("NODE.local", 60));
+        second.answers.push(DnsRecord::PTR {
+            name: DnsName::new("_http._tcp.local").unwrap(),
+            ttl: 120,
+            ptr_name: DnsName::new("web._http._tcp.local").unwrap(),
+        });
+        second.additionals.push(a_record("node.local", 120));
+
+        first.merge(second);
+        assert_eq!(first.answers.len(), 2);
+        assert_eq!(first.answers[0].ttl(), 120, "the existing record is kept");
+        assert!(first.answers[1].as_ptr().is_some());
+        // De-duplication is per section.
+        assert_eq!(first.additionals.len(), 1);
+    }
+
+    #[test]
+    fn test_svcb_record_round_trips() {
+        use mdns::{DnsName, DnsRecord, DnsType, SvcParam};
+
+        let record = DnsRecord::SVCB {
+            name: DnsName::new("web._http._tcp.local").unwrap(),
+            ttl: 120,
+            priority: 1,
+            target: DnsName::new("node.local").unwrap(),
+            // Written in key order regardless of the order given here.
+            params: vec![
+                SvcParam::Port(8443),
+                SvcParam::Alpn(vec!["h2".to_string(), "http/1.1".to_string()]),
+            ],
+        };
+        let mut packet = DnsPacket::new();
+        packet.answers.push(record.clone());
+        let bytes = packet.serialize();
+        assert_eq!(bytes.len(), packet.estimated_size());
+
+        let parsed = DnsPacket::parse_strict(&bytes).expect("SVCB packet failed to parse");
+        assert_eq!(parsed.answers.len(), 1);
+        let answer = &parsed.answers[0];
+        assert_eq!(answer.dns_type(), DnsType::SVCB);
+        assert_eq!(
+            answer.as_svcb().unwrap(),
+            [
+                SvcParam::Alpn(vec!["h2".to_string(), "http/1.1".to_string()]),
+                SvcParam::Port(8443),
+            ]
+        );
+        assert!(answer.same_record(&record));
+        assert_eq!(parsed.serialize(), bytes);
+    }
+
+    #[test]
+    fn test_name_hierarchy() {
+        use mdns::DnsName;
+
+        let instance = DnsName::new("Web._HTTP._tcp.local.").unwrap();
+        let service_type = DnsName::new("_http._tcp.local").unwrap();
+        let root = DnsName::new("").unwrap();
+
+        assert_eq!(instance.labels_count(), 4);
+        assert!(instance.is_subdomain_of(&service_type), "comparison ignores case");
+        assert!(service_type.is_subdomain_of(&service_type));
+        assert!(!service_type.is_subdomain_of(&instance));
+        // Suffix matching is per label, not per character.
+        assert!(!DnsName::new("x_http._tcp.local").unwrap().is_subdomain_of(&service_type));
+
+        let parent = instance.parent().unwrap();
+        assert!(parent.eq_ignore_case(&service_type));
+        assert_eq!(parent.labels_count(), 3);
+
+        assert_eq!(root.labels_count(), 0);
+        assert!(root.parent().is_none());
+        assert!(instance.is_subdomain_of(&root));
+        assert!(root.is_subdomain_of(&root));
+        assert!(!root.is_subdomain_of(&service_type));
+        assert_eq!(DnsName::new("local").unwrap().parent().unwrap(), root);
+    }
+}
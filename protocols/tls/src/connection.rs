@@ -1,13 +1,20 @@
 use tokio::net::TcpStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
 use async_trait::async_trait;
 use tokio::sync::Mutex;   // <-- Use tokio's Mutex for async
 use std::sync::Arc;
 
 use crate::record::{TlsRecord, RecordType, RecordError};
 use crate::tls_state::TlsState;
+use data_encryption::Aead;
 use handshake::Handshake;
 use nautilus_core::connection::Connection;
+use zeroize::Zeroizing;
+
+/// The AEAD for `state`'s record cipher, keyed with its session key.
+async fn record_aead_for(state: &Mutex<TlsState>) -> Result<Box<dyn Aead>, RecordError> {
+    state.lock().await.record_aead()
+}
 
 #[derive(Clone)]
 pub struct TlsConnection {
@@ -24,17 +31,11 @@ pub struct TlsReader {
 
 impl TlsReader {
     pub async fn receive(&mut self) -> Result<Vec<u8>, RecordError> {
-        let session_key = {
-            let st = self.state.lock().await;
-            st.session_key().to_vec()
-        };
+        let aead = record_aead_for(&self.state).await?;
 
         let mut locked_stream = self.inner.lock().await;
-        let mut buf = vec![0u8; 4096];
-        let n = locked_stream.read(&mut buf).await.map_err(|_| RecordError::ReadError)?;
-
-        let mut record = TlsRecord::deserialize(&buf[..n])?;
-        let payload = record.decrypt(&session_key)?;
+        let mut record = TlsRecord::read_from(&mut *locked_stream).await?;
+        let payload = record.open(aead.as_ref())?;
         Ok(payload)
     }
 }
@@ -46,16 +47,13 @@ pub struct TlsWriter {
 
 impl TlsWriter {
     pub async fn send(&mut self, data: &[u8]) -> Result<(), RecordError> {
-        let session_key = {
-            let st = self.state.lock().await;
-            st.session_key().to_vec()
-        };
+        let aead = record_aead_for(&self.state).await?;
 
         let mut record = TlsRecord::new(RecordType::ApplicationData, data.to_vec());
-        record.encrypt(&session_key)?;
+        record.seal(aead.as_ref())?;
 
         let mut locked_stream = self.inner.lock().await;
-        locked_stream.write_all(&record.serialize()).await.map_err(|_| RecordError::WriteError)?;
+        record.write_to(&mut *locked_stream).await?;
         Ok(())
     }
 }
@@ -74,7 +72,7 @@ impl TlsConnection {
         {
             // tokio::sync::Mutex never returns a poison error, so just .await:
             let mut st = state.lock().await;
-            st.set_handshake_complete(true);
+            st.mark_handshake_complete();
         }
 
         // 3. Wrap the final stream in Arc<Mutex<...>>
@@ -84,9 +82,9 @@ impl TlsConnection {
         };
         Ok(connection)
     }
-    pub async fn get_session_key(&self) -> Vec<u8> {
-        let st = self.state.lock().await;
-        st.session_key().to_vec()
+    /// A copy of the session key, wiped on drop; see `TlsState::export_session_key`.
+    pub async fn get_session_key(&self) -> Zeroizing<Vec<u8>> {
+        self.state.lock().await.export_session_key()
     }
     pub async fn split(&self) -> (TlsReader, TlsWriter) {
         let inner_clone = self.inner.clone();
@@ -128,43 +126,29 @@ impl Connection for TlsConnection {
     }
 
     async fn send(&mut self, data: &[u8]) -> Result<(), Self::Error> {
-        // 1. Get session key
-        let session_key = {
-            let st = self.state.lock().await;
-            st.session_key().to_vec()
-        };
+        // 1. Build the record AEAD from the session key
+        let aead = record_aead_for(&self.state).await?;
 
         // 2. Encrypt into TlsRecord
         let mut record = TlsRecord::new(RecordType::ApplicationData, data.to_vec());
-        record.encrypt(&session_key)?;
+        record.seal(aead.as_ref())?;
 
         // 3. Lock stream and write
         let mut locked_stream = self.inner.lock().await;
-        locked_stream
-            .write_all(&record.serialize())
-            .await
-            .map_err(|_| RecordError::WriteError)?;
+        record.write_to(&mut *locked_stream).await?;
         Ok(())
     }
 
     async fn receive(&mut self) -> Result<Vec<u8>, Self::Error> {
-        // 1. Get session key
-        let session_key = {
-            let st = self.state.lock().await;
-            st.session_key().to_vec()
-        };
+        // 1. Build the record AEAD from the session key
+        let aead = record_aead_for(&self.state).await?;
 
         // 2. Lock stream and read
         let mut locked_stream = self.inner.lock().await;
-        let mut buf = vec![0u8; 4096];
-        let n = locked_stream
-            .read(&mut buf)
-            .await
-            .map_err(|_| RecordError::ReadError)?;
 
-        // 3. Deserialize & decrypt
-        let mut record = TlsRecord::deserialize(&buf[..n])?;
-        let payload = record.decrypt(&session_key)?;
+        // 3. Read one framed record & decrypt
+        let mut record = TlsRecord::read_from(&mut *locked_stream).await?;
+        let payload = record.open(aead.as_ref())?;
         Ok(payload)
     }
 
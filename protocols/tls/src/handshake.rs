@@ -1,19 +1,25 @@
 // protocols\tls\src\handshake.rs
 use async_trait::async_trait;
 use futures::future::BoxFuture;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
 // ----- Import Handshake traits -----
-use handshake::{HandshakeStream, HandshakeError, HandshakeStep};
+use handshake::{Handshake, HandshakeStream, HandshakeError, HandshakeStep};
+use handshake::{read_frame, write_frame, DEFAULT_MAX_HANDSHAKE_MESSAGE};
 
 // ----- Add Mutex + Arc if needed -----
 use std::sync::Arc;
+use std::time::Duration;
 
 // ----- FIPS203 imports -----
 use fips203::ml_kem_1024::{EncapsKey, /*DecapsKey,*/ KG, CipherText};
 // Import SerDes to get `into_bytes()` and `try_from_bytes()`
 use fips203::traits::{SerDes, KeyGen, Decaps, Encaps};
 
-use crate::tls_state::TlsState;
+use crate::tls_state::{TlsState, TRANSCRIPT_MAC_LEN};
+use crate::key_schedule::{derive_keys, RECORD_KEY_LABEL, TRANSCRIPT_MAC_LABEL};
+use crate::record::record_cipher_for;
+use crate::replay::{HelloNonceCache, HELLO_NONCE_LEN};
+use identity::{CipherSuite, CipherSuitePolicy};
+use rand::Rng;
 use tokio::sync::Mutex; 
 // --------------------------------------------------------
 // If you don’t actually use `DecapsKey`, remove or comment:
@@ -28,9 +34,95 @@ pub enum HandshakeRole {
     Initiator,
     Responder,
 }
+/// Derives the 32-byte AES-256-GCM record key from the KEM shared secret.
+fn record_key(shared_secret: &[u8]) -> Vec<u8> {
+    derive_keys(shared_secret, &[RECORD_KEY_LABEL], &[32]).remove(0)
+}
+
+/// Derives the 32-byte transcript MAC key from the KEM shared secret.
+fn transcript_mac_key(shared_secret: &[u8]) -> Vec<u8> {
+    derive_keys(shared_secret, &[TRANSCRIPT_MAC_LABEL], &[32]).remove(0)
+}
+
+/// Adds `message` to the transcript and returns the frame to send: the message
+/// itself, followed by the transcript MAC if one is enabled and keyed.
+fn authenticate(state: &mut TlsState, message: &[u8]) -> Vec<u8> {
+    state.absorb_transcript(message);
+    let mut frame = message.to_vec();
+    if let Some(tag) = state.transcript_tag() {
+        frame.extend_from_slice(&tag);
+    }
+    frame
+}
+
+/// Splits the transcript MAC off a received frame when the MAC is enabled.
+fn split_transcript_tag(state: &TlsState, mut frame: Vec<u8>) -> Result<(Vec<u8>, Option<Vec<u8>>), HandshakeError> {
+    if !state.transcript_mac_enabled() {
+        return Ok((frame, None));
+    }
+    if frame.len() < TRANSCRIPT_MAC_LEN {
+        return Err(HandshakeError::Generic("transcript MAC missing".to_string()));
+    }
+    let tag = frame.split_off(frame.len() - TRANSCRIPT_MAC_LEN);
+    Ok((frame, Some(tag)))
+}
+
+/// Adds a received `message` to the transcript and checks the tag that came with it.
+fn check_transcript_tag(state: &mut TlsState, message: &[u8], tag: Option<&[u8]>) -> Result<(), HandshakeError> {
+    state.absorb_transcript(message);
+    match tag {
+        Some(tag) if !state.verify_transcript_tag(tag) => {
+            Err(HandshakeError::Generic("transcript MAC mismatch".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Rejects input handed to a step that does not consume any, which usually means
+/// the pipeline was assembled in the wrong order.
+fn expect_empty_input(step: &str, input: &[u8]) -> Result<(), HandshakeError> {
+    if input.is_empty() {
+        Ok(())
+    } else {
+        Err(HandshakeError::StepError(format!(
+            "{step} expects no input but received {} bytes from the previous step",
+            input.len()
+        )))
+    }
+}
+
+/// Accepts no input or the suite chosen by a preceding `CipherSuiteStep`; anything
+/// else still means the pipeline was assembled in the wrong order.
+fn expect_suite_or_empty_input(step: &str, input: &[u8]) -> Result<(), HandshakeError> {
+    if input.is_empty() || serde_json::from_slice::<CipherSuite>(input).is_ok() {
+        Ok(())
+    } else {
+        Err(HandshakeError::StepError(format!(
+            "{step} expects no input or a negotiated cipher suite but received {} bytes from the previous step",
+            input.len()
+        )))
+    }
+}
+
+/// Adds the messages of a completed exchange to the transcript of `state`, if any.
+async fn absorb_exchange(state: &Option<Arc<Mutex<TlsState>>>, messages: &[&[u8]]) {
+    if let Some(state) = state {
+        let mut guard = state.lock().await;
+        for message in messages {
+            guard.absorb_transcript(message);
+        }
+    }
+}
+
+/// Exchanges `HELLO || nonce` / `HELLO_ACK`. The initiator picks a fresh random
+/// nonce per handshake; a responder configured with a [`HelloNonceCache`] rejects
+/// any nonce it has already seen within the cache window.
 pub struct HelloStep {
     protocol_id: String,
     role: HandshakeRole,
+    nonce_cache: Option<Arc<HelloNonceCache>>,
+    state: Option<Arc<Mutex<TlsState>>>,
+    max_message_size: usize,
 }
 
 impl HelloStep {
@@ -38,8 +130,24 @@ impl HelloStep {
         Self {
             protocol_id: protocol_id.to_string(),
             role,
+            nonce_cache: None,
+            state: None,
+            max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE,
         }
     }
+
+    /// Enables replay detection on the responder side using a shared cache.
+    pub fn with_nonce_cache(mut self, cache: Arc<HelloNonceCache>) -> Self {
+        self.nonce_cache = Some(cache);
+        self
+    }
+
+    /// Records the HELLO exchange in the transcript of `state`, so the transcript
+    /// MAC checked by `FinishStep` covers the nonce as well.
+    pub fn with_state(mut self, state: Arc<Mutex<TlsState>>) -> Self {
+        self.state = Some(state);
+        self
+    }
 }
 
 #[async_trait]
@@ -52,54 +160,61 @@ impl HandshakeStep for HelloStep {
         self.protocol_id = protocol_id.to_string();
     }
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
-        _input: Vec<u8>,
+        input: Vec<u8>,
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
+            expect_empty_input("HelloStep", &input)?;
             match self.role {
                 HandshakeRole::Initiator => {
-                    // 1) Initiator: send "HELLO"
+                    // 1) Initiator: send "HELLO" followed by a fresh nonce
                     println!("[Initiator] Sending HELLO");
-                    stream.write_all(b"HELLO").await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to send HELLO: {e}"))
-                    })?;
+                    let nonce: [u8; HELLO_NONCE_LEN] = rand::thread_rng().gen();
+                    let mut hello = b"HELLO".to_vec();
+                    hello.extend_from_slice(&nonce);
+                    write_frame(stream, &hello).await?;
 
                     // 2) Read "HELLO_ACK"
                     println!("[Initiator] Waiting for HELLO_ACK");
-                    let mut buf = [0u8; 9];
-                    stream.read_exact(&mut buf).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to read HELLO_ACK: {e}"))
-                    })?;
+                    let buf = read_frame(stream, self.max_message_size).await?;
 
-                    if &buf != b"HELLO_ACK" {
+                    if buf != b"HELLO_ACK" {
                         return Err(HandshakeError::Generic(
                             "Invalid HELLO_ACK response".to_string(),
                         ));
                     }
                     println!("[Initiator] Received HELLO_ACK");
+                    absorb_exchange(&self.state, &[&hello, &buf]).await;
                 }
 
                 HandshakeRole::Responder => {
                     // 1) Responder: read "HELLO"
                     println!("[Responder] Waiting for HELLO");
-                    let mut buf = [0u8; 5];
-                    stream.read_exact(&mut buf).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to read HELLO: {e}"))
-                    })?;
-                    if &buf != b"HELLO" {
+                    let buf = read_frame(stream, self.max_message_size).await?;
+                    if buf.len() != 5 + HELLO_NONCE_LEN || &buf[..5] != b"HELLO" {
                         return Err(HandshakeError::Generic(
                             "Invalid HELLO from Initiator".to_string(),
                         ));
                     }
+                    if let Some(cache) = &self.nonce_cache {
+                        let mut nonce = [0u8; HELLO_NONCE_LEN];
+                        nonce.copy_from_slice(&buf[5..]);
+                        if !cache.check_and_insert(nonce) {
+                            return Err(HandshakeError::Generic("replayed hello".to_string()));
+                        }
+                    }
                     println!("[Responder] Received HELLO");
 
                     // 2) Send "HELLO_ACK"
                     println!("[Responder] Sending HELLO_ACK");
-                    stream.write_all(b"HELLO_ACK").await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to send HELLO_ACK: {e}"))
-                    })?;
+                    write_frame(stream, b"HELLO_ACK").await?;
+                    absorb_exchange(&self.state, &[&buf, b"HELLO_ACK"]).await;
                 }
 
                 HandshakeRole::Unknown => {
@@ -116,12 +231,94 @@ impl HandshakeStep for HelloStep {
 }
 pub struct CipherSuiteStep {
     protocol_id: String,
+    max_message_size: usize,
+    negotiation: Option<SuiteNegotiation>,
+    state: Option<Arc<Mutex<TlsState>>>,
+}
+
+/// Suites and preference order used when `CipherSuiteStep` negotiates.
+struct SuiteNegotiation {
+    role: HandshakeRole,
+    local: Vec<CipherSuite>,
+    policy: CipherSuitePolicy,
 }
 
 impl CipherSuiteStep {
     pub fn new(protocol_id: &str) -> Self {
         Self {
             protocol_id: protocol_id.to_string(),
+            max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE,
+            negotiation: None,
+            state: None,
+        }
+    }
+
+    /// Records the offer and the reply in the transcript of `state`, so a suite list
+    /// altered in transit fails the transcript MAC checked by `FinishStep`. After a
+    /// negotiation, the chosen suite is also stored in `state` and selects the record
+    /// cipher when it names one (see [`record_cipher_for`]).
+    pub fn with_state(mut self, state: Arc<Mutex<TlsState>>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Negotiates one suite from `local` instead of exchanging raw input.
+    ///
+    /// The initiator sends its suites and accepts the responder's pick if it is one
+    /// of them; the responder picks with `policy` (server preference) and sends the
+    /// choice back. Both sides return the chosen suite as JSON. The initiator's
+    /// `policy` is unused. Suites naming a record cipher this build cannot run are
+    /// neither offered nor accepted.
+    pub fn with_policy(mut self, role: HandshakeRole, local: Vec<CipherSuite>, policy: CipherSuitePolicy) -> Self {
+        self.negotiation = Some(SuiteNegotiation { role, local, policy });
+        self
+    }
+
+    /// Runs the negotiation configured by `with_policy` and returns the offer and the
+    /// chosen suite, both as sent on the wire.
+    async fn negotiate(
+        negotiation: &SuiteNegotiation,
+        stream: &mut dyn HandshakeStream,
+        max_message_size: usize,
+    ) -> Result<(Vec<u8>, Vec<u8>), HandshakeError> {
+        let local: Vec<CipherSuite> = negotiation
+            .local
+            .iter()
+            .filter(|suite| record_cipher_for(suite).is_ok())
+            .cloned()
+            .collect();
+        match negotiation.role {
+            HandshakeRole::Initiator => {
+                let offer = serde_json::to_vec(&local)
+                    .map_err(|e| HandshakeError::Generic(format!("Failed to encode cipher suites: {}", e)))?;
+                write_frame(stream, &offer).await?;
+                let buf = read_frame(stream, max_message_size).await?;
+                let chosen: CipherSuite = serde_json::from_slice(&buf)
+                    .map_err(|e| HandshakeError::Generic(format!("Invalid cipher suite response: {}", e)))?;
+                if !local.iter().any(|suite| suite.name() == chosen.name()) {
+                    return Err(HandshakeError::Generic(format!(
+                        "Peer chose unoffered cipher suite {}",
+                        chosen.name()
+                    )));
+                }
+                Ok((offer, buf))
+            }
+            HandshakeRole::Responder => {
+                let buf = read_frame(stream, max_message_size).await?;
+                let remote: Vec<CipherSuite> = serde_json::from_slice(&buf)
+                    .map_err(|e| HandshakeError::Generic(format!("Invalid cipher suite list: {}", e)))?;
+                let chosen = negotiation
+                    .policy
+                    .select(&local, &remote)
+                    .ok_or_else(|| HandshakeError::Generic("No common cipher suite".into()))?;
+                let reply = serde_json::to_vec(&chosen)
+                    .map_err(|e| HandshakeError::Generic(format!("Failed to encode cipher suite: {}", e)))?;
+                write_frame(stream, &reply).await?;
+                Ok((buf, reply))
+            }
+            HandshakeRole::Unknown => Err(HandshakeError::StepError(
+                "CipherSuiteStep cannot negotiate with Unknown role".into(),
+            )),
         }
     }
 }
@@ -136,25 +333,46 @@ impl HandshakeStep for CipherSuiteStep {
         self.protocol_id = protocol_id.to_string();
     }
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
         input: Vec<u8>,
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
+            if let Some(negotiation) = &self.negotiation {
+                let (offer, chosen) = Self::negotiate(negotiation, stream, self.max_message_size).await?;
+                absorb_exchange(&self.state, &[&offer, &chosen]).await;
+                if let Some(state) = &self.state {
+                    let suite: CipherSuite = serde_json::from_slice(&chosen)
+                        .map_err(|e| HandshakeError::Generic(format!("Invalid cipher suite: {}", e)))?;
+                    let cipher = record_cipher_for(&suite).map_err(|e| {
+                        HandshakeError::NegotiationFailed(format!("{} cannot protect records: {}", suite.name(), e))
+                    })?;
+                    let mut guard = state.lock().await;
+                    guard.set_negotiated_cipher_suite(chosen.clone());
+                    if let Some(cipher) = cipher {
+                        guard.set_record_cipher(cipher);
+                    }
+                }
+                return Ok(chosen);
+            }
+
             // Send supported cipher suites
-            stream.write_all(&input).await.map_err(|e| {
-                HandshakeError::Generic(format!("Failed to send cipher suites: {}", e))
-            })?;
+            write_frame(stream, &input).await?;
 
             // Read the negotiated cipher suite
-            let mut buf = vec![0; 1024];
-            let n = stream.read(&mut buf).await.map_err(|e| {
-                HandshakeError::Generic(format!("Failed to read cipher suite response: {}", e))
-            })?;
+            let buf = read_frame(stream, self.max_message_size).await?;
+
+            // Both peers send first here, so record the pair in an order both agree on.
+            let (first, second) = if input <= buf { (&input, &buf) } else { (&buf, &input) };
+            absorb_exchange(&self.state, &[first, second]).await;
 
             // Return the negotiated cipher suite
-            Ok(buf[..n].to_vec())
+            Ok(buf)
         })
     }
 }
@@ -166,11 +384,43 @@ pub struct KyberExchangeStep {
     role: HandshakeRole,
     /// Arc<Mutex<TlsState>> is used so we can .lock() TlsState
     state: Arc<Mutex<TlsState>>,
+    max_message_size: usize,
+    read_timeout: Duration,
 }
 
+/// Default time `KyberExchangeStep` waits for the whole public key or ciphertext.
+pub const DEFAULT_KYBER_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl KyberExchangeStep {
     pub fn new(role: HandshakeRole, state: Arc<Mutex<TlsState>>) -> Self {
-        Self { role, state }
+        Self {
+            role,
+            state,
+            max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE,
+            read_timeout: DEFAULT_KYBER_READ_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for the peer's key material. A message split across many
+    /// segments is accumulated until complete, but only within this overall limit.
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Reads one frame, however many segments it arrives in, within `read_timeout`.
+    async fn read_key_material(
+        &self,
+        stream: &mut dyn HandshakeStream,
+        what: &str,
+    ) -> Result<Vec<u8>, HandshakeError> {
+        match tokio::time::timeout(self.read_timeout, read_frame(stream, self.max_message_size)).await {
+            Ok(frame) => Ok(frame?),
+            Err(_) => Err(HandshakeError::Generic(format!(
+                "Timed out after {:?} waiting for {}",
+                self.read_timeout, what
+            ))),
+        }
     }
 }
 
@@ -182,12 +432,22 @@ impl HandshakeStep for KyberExchangeStep {
 
     fn set_protocol_id(&mut self, _protocol_id: &str) {}
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
-        _input: Vec<u8>,
+        input: Vec<u8>,
     ) -> BoxFuture<'a, Result<Vec<u8>, HandshakeError>> {
         Box::pin(async move {
+            expect_suite_or_empty_input("KyberExchangeStep", &input)?;
+            self.state
+                .lock()
+                .await
+                .ensure_fresh()
+                .map_err(|e| HandshakeError::StepError(format!("KyberExchangeStep: {e}")))?;
             match self.role {
                 HandshakeRole::Initiator => {
                     // Generate key pair
@@ -201,26 +461,22 @@ impl HandshakeStep for KyberExchangeStep {
 
                     // Send public key
                     println!("[Initiator] Sending public key");
-                    stream.write_all(&pk_bytes).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to send public key: {}", e))
-                    })?;
+                    let frame = authenticate(&mut *self.state.lock().await, &pk_bytes);
+                    write_frame(stream, &frame).await?;
 
                     // Receive ciphertext
                     println!("[Initiator] Waiting for ciphertext");
-                    let mut buf = vec![0u8; 1600];
-                    let n = stream.read(&mut buf).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to read ciphertext: {}", e))
-                    })?;
+                    let frame = self.read_key_material(stream, "ciphertext").await?;
+                    let (buf, tag) = split_transcript_tag(&*self.state.lock().await, frame)?;
 
-                    // Extract the ciphertext from the buffer
-                    if n < 1568 {
+                    if buf.len() < 1568 {
                         return Err(HandshakeError::Generic(
                             "Ciphertext too small".to_string(),
                         ));
                     }
 
                     // Reconstruct the ciphertext using SerDes::try_from_bytes()
-                    let ct_bytes: [u8; 1568] = buf[..1568].try_into().map_err(|_| {
+                    let ct_bytes: [u8; 1568] = buf[..].try_into().map_err(|_| {
                         HandshakeError::Generic("Invalid ciphertext size".to_string())
                     })?;
                     let ciphertext = CipherText::try_from_bytes(ct_bytes).map_err(|_| {
@@ -234,15 +490,18 @@ impl HandshakeStep for KyberExchangeStep {
 
                     // Convert shared key to bytes
                     let sk_bytes = shared_key.into_bytes();
-                    println!("Client Secret : {:?}",sk_bytes.to_vec());
-                    // Update session key in TlsState
+                    // Check the responder's transcript MAC, then update session key in TlsState
                     {
                         let mut guard = self.state.lock().await;
-                        guard.set_session_key(sk_bytes.to_vec());
+                        guard.set_transcript_mac_key(transcript_mac_key(&sk_bytes));
+                        check_transcript_tag(&mut guard, &buf, tag.as_deref())?;
+                        guard
+                            .set_session_key(record_key(&sk_bytes))
+                            .map_err(|e| HandshakeError::StepError(format!("KyberExchangeStep: {e}")))?;
                     }
 
                     println!("[Initiator] Kyber Shared key established");
-                    Ok(vec![]) 
+                    Ok(input)
                 }
 
                 HandshakeRole::Responder => {
@@ -250,10 +509,8 @@ impl HandshakeStep for KyberExchangeStep {
 
                     // Receive public key
                     println!("[Responder] Waiting for public key");
-                    let mut buf = vec![0u8; 1568]; // Expected public key size for Kyber
-                    stream.read_exact(&mut buf).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to read public key: {}", e))
-                    })?;
+                    let buf = self.read_key_material(stream, "public key").await?;
+                    self.state.lock().await.absorb_transcript(&buf);
 
                     // Rebuild the public key using SerDes::try_from_bytes()
                     let pk_array: [u8; 1568] = buf.try_into().map_err(|_| {
@@ -268,28 +525,30 @@ impl HandshakeStep for KyberExchangeStep {
                         HandshakeError::Generic(format!("Encapsulation failed: {}", e))
                     })?;
 
-                    // Convert ciphertext to bytes
+                    // Convert ciphertext and shared key to bytes
                     let ct_bytes = ciphertext.into_bytes();
+                    let sk_bytes = shared_key.into_bytes();
 
                     // Send ciphertext
                     println!("[Responder] Sending ciphertext");
-                    stream.write_all(&ct_bytes).await.map_err(|e| {
-                        HandshakeError::Generic(format!("Failed to send ciphertext: {}", e))
-                    })?;
+                    let frame = {
+                        let mut guard = self.state.lock().await;
+                        guard.set_transcript_mac_key(transcript_mac_key(&sk_bytes));
+                        authenticate(&mut guard, &ct_bytes)
+                    };
+                    write_frame(stream, &frame).await?;
 
-                    // Convert shared key to bytes
-                    let sk_bytes = shared_key.into_bytes();
-                    println!("Server Secret : {:?}",sk_bytes.to_vec());
-                    println!("Key Length : {:?}",sk_bytes.to_vec().len());
                     // Update session key in TlsState
                     {
                         let mut guard = self.state.lock().await;
-                        guard.set_session_key(sk_bytes.to_vec());
+                        guard
+                            .set_session_key(record_key(&sk_bytes))
+                            .map_err(|e| HandshakeError::StepError(format!("KyberExchangeStep: {e}")))?;
                     }
 
                     println!("\x1b[35m[Responder] Kyber Completed - Shared key established\x1b[0m");
 
-                    Ok(vec![])
+                    Ok(input)
                 }
                 HandshakeRole::Unknown => {
                     return Err(HandshakeError::Generic("Handshake role not set correctly".to_string()));
@@ -300,8 +559,25 @@ impl HandshakeStep for KyberExchangeStep {
 }
 
 
+/// Confirms the handshake with `HANDSHAKE_DONE` / `OK` and marks `state` complete.
 pub struct FinishStep {
     pub role: HandshakeRole,
+    state: Arc<Mutex<TlsState>>,
+    max_message_size: usize,
+}
+
+impl FinishStep {
+    pub fn new(role: HandshakeRole, state: Arc<Mutex<TlsState>>) -> Self {
+        Self { role, state, max_message_size: DEFAULT_MAX_HANDSHAKE_MESSAGE }
+    }
+
+    /// Verifies and strips the transcript MAC of a received frame, if enabled.
+    async fn check_frame(&self, frame: Vec<u8>) -> Result<Vec<u8>, HandshakeError> {
+        let mut guard = self.state.lock().await;
+        let (message, tag) = split_transcript_tag(&guard, frame)?;
+        check_transcript_tag(&mut guard, &message, tag.as_deref())?;
+        Ok(message)
+    }
 }
 
 #[async_trait]
@@ -311,6 +587,10 @@ impl HandshakeStep for FinishStep {
     }
     fn set_protocol_id(&mut self, _: &str) {}
 
+    fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
     fn execute<'a>(
         &'a mut self,
         stream: &'a mut dyn HandshakeStream,
@@ -320,34 +600,94 @@ impl HandshakeStep for FinishStep {
             match self.role {
                 HandshakeRole::Initiator => {
                     // Send "HANDSHAKE_DONE"
-                    stream.write_all(b"HANDSHAKE_DONE").await
-                        .map_err(|e| HandshakeError::Generic(format!("FinishStep write: {e}")))?;
+                    let frame = authenticate(&mut *self.state.lock().await, b"HANDSHAKE_DONE");
+                    write_frame(stream, &frame).await?;
                     // Read "OK"
-                    let mut buf = [0u8; 2];
-                    stream.read_exact(&mut buf).await
-                        .map_err(|e| HandshakeError::Generic(format!("FinishStep read: {e}")))?;
-                    if &buf != b"OK" {
+                    let frame = read_frame(stream, self.max_message_size).await?;
+                    let buf = self.check_frame(frame).await?;
+                    if buf != b"OK" {
                         return Err(HandshakeError::Generic("FinishStep expected OK".into()));
                     }
                 }
                 HandshakeRole::Responder => {
                     // Responder reads "HANDSHAKE_DONE"
-                    let mut buf = [0u8; 14];
-                    stream.read_exact(&mut buf).await
-                        .map_err(|e| HandshakeError::Generic(format!("FinishStep read: {e}")))?;
-                    if &buf != b"HANDSHAKE_DONE" {
+                    let frame = read_frame(stream, self.max_message_size).await?;
+                    let buf = self.check_frame(frame).await?;
+                    if buf != b"HANDSHAKE_DONE" {
                         return Err(HandshakeError::Generic("FinishStep expected HANDSHAKE_DONE".into()));
                     }
                     // Writes "OK"
-                    stream.write_all(b"OK").await
-                        .map_err(|e| HandshakeError::Generic(format!("FinishStep write: {e}")))?;
+                    let frame = authenticate(&mut *self.state.lock().await, b"OK");
+                    write_frame(stream, &frame).await?;
                 }
                 HandshakeRole::Unknown => {
                     return Err(HandshakeError::Generic("FinishStep cannot proceed with Unknown role".to_string()));
                 }
             }
+            self.state.lock().await.mark_handshake_complete();
             // Return the same input for consistency
             Ok(input)
         })
     }
-}
\ No newline at end of file
+}
+
+// ---------------
+// Standard sequences
+// ---------------
+
+/// Suites the standard handshake offers: the Kyber key exchange it always runs.
+fn default_cipher_suites() -> Vec<CipherSuite> {
+    vec![CipherSuite::Kyber { priority: 0 }]
+}
+
+/// Builds the standard TLS handshake for the connecting side:
+/// `HelloStep` → `CipherSuiteStep` → `KyberExchangeStep` → `FinishStep`, all in the
+/// `Initiator` role. The session key is written into `state` once the Kyber exchange
+/// completes, and the handshake returns the negotiated suite as JSON.
+pub fn build_initiator_handshake(state: Arc<Mutex<TlsState>>) -> Handshake {
+    build_standard_handshake(HandshakeRole::Initiator, state, default_cipher_suites(), CipherSuitePolicy::default())
+}
+
+/// Builds the standard TLS handshake for the accepting side, mirroring
+/// [`build_initiator_handshake`] with every step in the `Responder` role.
+pub fn build_responder_handshake(state: Arc<Mutex<TlsState>>) -> Handshake {
+    build_standard_handshake(HandshakeRole::Responder, state, default_cipher_suites(), CipherSuitePolicy::default())
+}
+
+/// Like [`build_initiator_handshake`], but offers `suites` in the `CipherSuiteStep`.
+/// The responder decides, so `policy` is unused on this side.
+pub fn build_initiator_handshake_with_policy(
+    state: Arc<Mutex<TlsState>>,
+    suites: Vec<CipherSuite>,
+    policy: CipherSuitePolicy,
+) -> Handshake {
+    build_standard_handshake(HandshakeRole::Initiator, state, suites, policy)
+}
+
+/// Like [`build_responder_handshake`], but accepts only `suites` and picks among the
+/// ones the initiator also offers with `policy`. The handshake fails if none match.
+pub fn build_responder_handshake_with_policy(
+    state: Arc<Mutex<TlsState>>,
+    suites: Vec<CipherSuite>,
+    policy: CipherSuitePolicy,
+) -> Handshake {
+    build_standard_handshake(HandshakeRole::Responder, state, suites, policy)
+}
+
+fn build_standard_handshake(
+    role: HandshakeRole,
+    state: Arc<Mutex<TlsState>>,
+    suites: Vec<CipherSuite>,
+    policy: CipherSuitePolicy,
+) -> Handshake {
+    let mut handshake = Handshake::new("TLS_HANDSHAKE");
+    handshake.add_step(Box::new(HelloStep::new("TLS_HANDSHAKE", role).with_state(state.clone())));
+    handshake.add_step(Box::new(
+        CipherSuiteStep::new("TLS_HANDSHAKE")
+            .with_policy(role, suites, policy)
+            .with_state(state.clone()),
+    ));
+    handshake.add_step(Box::new(KyberExchangeStep::new(role, state.clone())));
+    handshake.add_step(Box::new(FinishStep::new(role, state)));
+    handshake
+}
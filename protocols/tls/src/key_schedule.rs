@@ -0,0 +1,39 @@
+// protocols\tls\src\key_schedule.rs
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+/// Label used to derive the record-layer key from the KEM shared secret.
+pub(crate) const RECORD_KEY_LABEL: &[u8] = b"nautilus tls record key";
+/// Label used to derive the handshake transcript MAC key from the KEM shared secret.
+pub(crate) const TRANSCRIPT_MAC_LABEL: &[u8] = b"nautilus tls transcript mac";
+
+/// Derives one independent byte string per label from `secret` using SHAKE256.
+///
+/// Each output is `SHAKE256(len(secret) || secret || len(label) || label)` read to
+/// the matching entry in `lengths`, so outputs may be longer than 32 bytes.
+///
+/// # Panics
+/// Panics if `labels` and `lengths` differ in length.
+pub fn derive_keys(secret: &[u8], labels: &[&[u8]], lengths: &[usize]) -> Vec<Vec<u8>> {
+    assert_eq!(
+        labels.len(),
+        lengths.len(),
+        "derive_keys needs one output length per label"
+    );
+
+    labels
+        .iter()
+        .zip(lengths)
+        .map(|(label, &length)| {
+            let mut hasher = Shake256::default();
+            hasher.update(&(secret.len() as u32).to_be_bytes());
+            hasher.update(secret);
+            hasher.update(&(label.len() as u32).to_be_bytes());
+            hasher.update(label);
+
+            let mut output = vec![0u8; length];
+            hasher.finalize_xof().read(&mut output);
+            output
+        })
+        .collect()
+}
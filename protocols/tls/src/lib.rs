@@ -4,9 +4,15 @@ mod connection;
 mod record;
 mod handshake;
 mod tls_session;
+mod key_schedule;
+mod replay;
+mod tls_endpoint;
 
 pub use connection::TlsConnection;
-pub use record::{TlsRecord, RecordType, RecordError};
-pub use tls_state::TlsState;
-pub use handshake::{HelloStep,CipherSuiteStep,HandshakeRole,KyberExchangeStep,FinishStep};
-pub use tls_session::{TlsSession,adaptive_session};
\ No newline at end of file
+pub use record::{record_aead, record_cipher_for, TlsRecord, RecordType, RecordError, MAX_RECORD_PAYLOAD, MAX_RECORD_LEN};
+pub use tls_state::{TlsState, TlsStateError};
+pub use handshake::{HelloStep,CipherSuiteStep,HandshakeRole,KyberExchangeStep,DEFAULT_KYBER_READ_TIMEOUT,FinishStep,build_initiator_handshake,build_responder_handshake,build_initiator_handshake_with_policy,build_responder_handshake_with_policy};
+pub use tls_session::{TlsSession,adaptive_session,accept_with_limit};
+pub use tls_endpoint::{TlsServer, TlsClient, DEFAULT_HANDSHAKE_TIMEOUT};
+pub use key_schedule::derive_keys;
+pub use replay::{HelloNonceCache, DEFAULT_NONCE_CACHE_CAPACITY, HELLO_NONCE_LEN};
\ No newline at end of file
@@ -1,8 +1,65 @@
 // protocols\tls\src\record.rs
-use aes_gcm::{Aes256Gcm, Key, Nonce};
-use aes_gcm::aead::{Aead, KeyInit};
+use data_encryption::{Aead, Aes256GcmEncryption, SymmetricCipherSuite};
+use identity::CipherSuite;
 use std::error::Error;
 use rand::Rng;
+use handshake::{read_frame, write_frame, FrameError};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Largest plaintext payload carried by a single record (64 KiB).
+pub const MAX_RECORD_PAYLOAD: usize = 64 * 1024;
+
+/// Largest serialized record accepted from the wire: type byte, nonce,
+/// [`MAX_RECORD_PAYLOAD`] of ciphertext and the AEAD tag.
+pub const MAX_RECORD_LEN: usize = 1 + 12 + MAX_RECORD_PAYLOAD + 16;
+
+/// Builds the record-layer AEAD for `suite` keyed with `key`.
+///
+/// ChaCha20-Poly1305 needs this crate's `chacha20` feature; other suites are
+/// refused with `RecordError::UnsupportedCipher`.
+pub fn record_aead(suite: &SymmetricCipherSuite, key: &[u8]) -> Result<Box<dyn Aead>, RecordError> {
+    // The instance nonce is never used: records carry their own.
+    let unused_nonce = vec![0u8; 12];
+    match suite {
+        SymmetricCipherSuite::AES256GCM { .. } => Aes256GcmEncryption::new(key.to_vec(), unused_nonce)
+            .map(|aead| Box::new(aead) as Box<dyn Aead>)
+            .map_err(|_| RecordError::EncryptionError),
+        #[cfg(feature = "chacha20")]
+        SymmetricCipherSuite::ChaCha20 { .. } => data_encryption::ChaCha20Encryption::new(key.to_vec(), unused_nonce)
+            .map(|aead| Box::new(aead) as Box<dyn Aead>)
+            .map_err(|_| RecordError::EncryptionError),
+        #[allow(unreachable_patterns)]
+        _ => Err(RecordError::UnsupportedCipher),
+    }
+}
+
+/// Names of the symmetric ciphers a negotiated suite can select, whether or not this
+/// build can run them (see [`SymmetricCipherSuite::name`]).
+const RECORD_CIPHER_NAMES: [&str; 4] = ["AES256-GCM", "ChaCha20", "Blowfish", "TripleDES"];
+
+/// The record-layer AEAD named by a negotiated `suite`, matched against
+/// [`SymmetricCipherSuite::name`] (e.g. a `CipherSuite::Custom` named `"ChaCha20"`).
+///
+/// Returns `Ok(None)` when `suite` names no cipher, in which case the record layer
+/// uses AES-256-GCM. A cipher [`record_aead`] cannot build in this build (ChaCha20
+/// without the `chacha20` feature, or a non-AEAD cipher) is refused with
+/// `RecordError::UnsupportedCipher` rather than replaced, so both peers either use
+/// the same cipher or fail.
+pub fn record_cipher_for(suite: &CipherSuite) -> Result<Option<SymmetricCipherSuite>, RecordError> {
+    let name = suite.name();
+    let supported = [
+        SymmetricCipherSuite::AES256GCM { priority: 0 },
+        #[cfg(feature = "chacha20")]
+        SymmetricCipherSuite::ChaCha20 { priority: 0 },
+    ]
+    .into_iter()
+    .find(|cipher| cipher.name() == name);
+    match supported {
+        Some(cipher) => Ok(Some(cipher)),
+        None if RECORD_CIPHER_NAMES.contains(&name.as_str()) => Err(RecordError::UnsupportedCipher),
+        None => Ok(None),
+    }
+}
 
 #[derive(Debug)]
 pub enum RecordType {
@@ -20,36 +77,53 @@ impl TlsRecord {
         Self { record_type, payload }
     }
 
+    /// Encrypts the payload with AES-256-GCM under `key`; see [`TlsRecord::seal`].
     pub fn encrypt(&mut self, key: &[u8]) -> Result<(), RecordError> {
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
-        let nonce_bytes: [u8; 12] = rand::thread_rng().gen(); // Generate random 12-byte nonce
-        let nonce = Nonce::from_slice(&nonce_bytes);
-    
-        self.payload = cipher
-            .encrypt(nonce, self.payload.as_ref())
+        let aead = record_aead(&SymmetricCipherSuite::AES256GCM { priority: 0 }, key)?;
+        self.seal(aead.as_ref())
+    }
+
+    /// Decrypts an AES-256-GCM payload under `key`; see [`TlsRecord::open`].
+    pub fn decrypt(&mut self, key: &[u8]) -> Result<Vec<u8>, RecordError> {
+        let aead = record_aead(&SymmetricCipherSuite::AES256GCM { priority: 0 }, key)?;
+        self.open(aead.as_ref())
+    }
+
+    /// Encrypts the payload in place as `nonce || ciphertext || tag` with a random
+    /// nonce. The record type byte is authenticated as associated data, so the framing
+    /// is the same whichever AEAD is used.
+    pub fn seal(&mut self, aead: &dyn Aead) -> Result<(), RecordError> {
+        let mut nonce = vec![0u8; aead.nonce_len()];
+        rand::thread_rng().fill(nonce.as_mut_slice());
+
+        let ciphertext = aead
+            .seal(&nonce, &[self.type_byte()], &self.payload)
             .map_err(|_| RecordError::EncryptionError)?;
-        self.payload.splice(0..0, nonce_bytes.iter().cloned()); // Prepend nonce to payload
+        nonce.extend_from_slice(&ciphertext);
+        self.payload = nonce;
         Ok(())
     }
-    pub fn decrypt(&mut self, key: &[u8]) -> Result<Vec<u8>, RecordError> {
-        if self.payload.len() < 12 {
+
+    /// Decrypts a payload produced by [`TlsRecord::seal`] with the same AEAD and key.
+    pub fn open(&mut self, aead: &dyn Aead) -> Result<Vec<u8>, RecordError> {
+        if self.payload.len() < aead.nonce_len() {
             return Err(RecordError::DecryptionError); // Not enough data for nonce
         }
-    
-        let (nonce_bytes, ciphertext) = self.payload.split_at(12); // Extract nonce
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
-        let nonce = Nonce::from_slice(nonce_bytes);
-    
-        cipher
-            .decrypt(nonce, ciphertext)
+
+        let (nonce, ciphertext) = self.payload.split_at(aead.nonce_len());
+        aead.open(nonce, &[self.type_byte()], ciphertext)
             .map_err(|_| RecordError::DecryptionError)
     }
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut data = Vec::new();
-        data.push(match self.record_type {
+
+    fn type_byte(&self) -> u8 {
+        match self.record_type {
             RecordType::Handshake => 0x01,
             RecordType::ApplicationData => 0x02,
-        });
+        }
+    }
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.push(self.type_byte());
         data.extend(&self.payload);
         data
     }
@@ -73,6 +147,31 @@ impl TlsRecord {
         
         Ok(Self { record_type, payload })
     }
+
+    /// Writes the serialized record to `stream` as a single length-prefixed frame.
+    pub async fn write_to<S>(&self, stream: &mut S) -> Result<(), RecordError>
+    where
+        S: AsyncWrite + Unpin + ?Sized,
+    {
+        let data = self.serialize();
+        if data.len() > MAX_RECORD_LEN {
+            return Err(RecordError::TooLarge);
+        }
+        write_frame(stream, &data).await.map_err(|_| RecordError::WriteError)
+    }
+
+    /// Reads one record written by [`TlsRecord::write_to`], however the bytes are
+    /// split across reads. Frames longer than [`MAX_RECORD_LEN`] are rejected.
+    pub async fn read_from<S>(stream: &mut S) -> Result<Self, RecordError>
+    where
+        S: AsyncRead + Unpin + ?Sized,
+    {
+        let data = read_frame(stream, MAX_RECORD_LEN).await.map_err(|e| match e {
+            FrameError::TooLarge { .. } => RecordError::TooLarge,
+            FrameError::Io(_) => RecordError::ReadError,
+        })?;
+        Self::deserialize(&data)
+    }
 }
 
 #[derive(Debug)]
@@ -82,6 +181,9 @@ pub enum RecordError {
     InvalidRecord,
     WriteError,
     ReadError,
+    TooLarge,
+    /// The selected record cipher is not available in this build.
+    UnsupportedCipher,
 }
 
 impl std::fmt::Display for RecordError {
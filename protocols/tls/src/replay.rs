@@ -0,0 +1,93 @@
+// protocols\tls\src\replay.rs
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Size of the random nonce carried in every HELLO.
+pub const HELLO_NONCE_LEN: usize = 16;
+
+/// Default number of nonces a [`HelloNonceCache`] holds before evicting the oldest.
+pub const DEFAULT_NONCE_CACHE_CAPACITY: usize = 65_536;
+
+/// Nonces in the order they were first seen, plus a set for lookups.
+#[derive(Default)]
+struct SeenNonces {
+    order: VecDeque<([u8; HELLO_NONCE_LEN], Instant)>,
+    nonces: HashSet<[u8; HELLO_NONCE_LEN]>,
+}
+
+impl SeenNonces {
+    fn pop_oldest(&mut self) {
+        if let Some((nonce, _)) = self.order.pop_front() {
+            self.nonces.remove(&nonce);
+        }
+    }
+}
+
+/// Remembers HELLO nonces seen by a responder for a bounded time window so a
+/// captured HELLO cannot be replayed to start another handshake.
+///
+/// Share one cache (behind an `Arc`) across every responder `HelloStep` that
+/// should reject each other's replays. At most `capacity` nonces are kept; past
+/// that the oldest is forgotten early, so size it for the expected HELLO rate
+/// over one window.
+pub struct HelloNonceCache {
+    window: Duration,
+    capacity: usize,
+    seen: Mutex<SeenNonces>,
+}
+
+impl HelloNonceCache {
+    pub fn new(window: Duration) -> Self {
+        Self::with_capacity(window, DEFAULT_NONCE_CACHE_CAPACITY)
+    }
+
+    /// Creates a cache holding at most `capacity` (at least one) nonces.
+    pub fn with_capacity(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity: capacity.max(1),
+            seen: Mutex::new(SeenNonces::default()),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records `nonce` and returns `true` if it is fresh, or `false` if it was
+    /// already seen within the window. Entries are expired oldest first, so each
+    /// call only touches the nonces that actually left the window.
+    pub fn check_and_insert(&self, nonce: [u8; HELLO_NONCE_LEN]) -> bool {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap();
+        while seen
+            .order
+            .front()
+            .is_some_and(|(_, at)| now.duration_since(*at) >= self.window)
+        {
+            seen.pop_oldest();
+        }
+        if seen.nonces.contains(&nonce) {
+            return false;
+        }
+        while seen.order.len() >= self.capacity {
+            seen.pop_oldest();
+        }
+        seen.order.push_back((nonce, now));
+        seen.nonces.insert(nonce);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap().order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
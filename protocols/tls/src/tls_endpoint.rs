@@ -0,0 +1,87 @@
+// protocols\tls\src\tls_endpoint.rs
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::Mutex;
+
+use crate::{TlsConnection, TlsState};
+use handshake::Handshake;
+
+/// Listens for TCP connections and turns each accepted one into a `TlsConnection`.
+///
+/// `handshake_builder` is called with a fresh `TlsState` for every connection, e.g.
+/// `build_responder_handshake`.
+pub struct TlsServer<F> {
+    listener: TcpListener,
+    handshake_builder: F,
+    handshake_timeout: Duration,
+}
+
+/// Default time `TlsServer::accept` allows a peer to complete the handshake.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl<F> TlsServer<F>
+where
+    F: Fn(Arc<Mutex<TlsState>>) -> Handshake,
+{
+    /// Binds a listener on `addr`. No handshake runs until `accept` is called.
+    pub async fn bind(addr: impl ToSocketAddrs, handshake_builder: F) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(Self { listener, handshake_builder, handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT })
+    }
+
+    /// Sets how long `accept` waits for a peer to finish the handshake before
+    /// dropping the connection, so a silent client cannot stall the server.
+    pub fn with_handshake_timeout(mut self, handshake_timeout: Duration) -> Self {
+        self.handshake_timeout = handshake_timeout;
+        self
+    }
+
+    /// The address the listener is bound to, useful after binding port 0.
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts the next connection and runs the handshake on it.
+    ///
+    /// A failed handshake, or one not finished within the handshake timeout, is
+    /// returned as an error; the listener stays usable.
+    pub async fn accept(&self) -> Result<(TlsConnection, SocketAddr), Box<dyn std::error::Error + Send + Sync>> {
+        let (socket, peer) = self.listener.accept().await?;
+        let state = Arc::new(Mutex::new(TlsState::new()));
+        let handshake = (self.handshake_builder)(state.clone());
+        let connection = tokio::time::timeout(self.handshake_timeout, TlsConnection::new(socket, handshake, state))
+            .await
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("handshake with {} timed out after {:?}", peer, self.handshake_timeout),
+                )
+            })??;
+        println!("[Server] Secure connection established with {}", peer);
+        Ok((connection, peer))
+    }
+}
+
+/// Opens TCP connections and runs the client side of the handshake on them.
+pub struct TlsClient;
+
+impl TlsClient {
+    /// Connects to `addr` and runs the handshake returned by `handshake_builder`
+    /// (e.g. `build_initiator_handshake`) with a fresh `TlsState`.
+    pub async fn connect<F>(
+        addr: impl ToSocketAddrs,
+        handshake_builder: F,
+    ) -> Result<TlsConnection, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: FnOnce(Arc<Mutex<TlsState>>) -> Handshake,
+    {
+        let socket = TcpStream::connect(addr).await?;
+        let state = Arc::new(Mutex::new(TlsState::new()));
+        let handshake = handshake_builder(state.clone());
+        let connection = TlsConnection::new(socket, handshake, state).await?;
+        println!("[Client] Secure connection established");
+        Ok(connection)
+    }
+}
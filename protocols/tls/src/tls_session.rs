@@ -1,18 +1,19 @@
 use tokio::net::{TcpStream, TcpListener};
 use tokio::sync::Mutex; // <-- Use tokio's Mutex for TlsState
+use tokio::sync::{mpsc, Semaphore};
+use std::future::Future;
 use std::sync::Arc;
 
 use crate::{
     TlsConnection, 
     TlsState, 
-    HelloStep, 
     HandshakeRole, 
-    KyberExchangeStep, 
-    FinishStep
+    build_initiator_handshake,
+    build_responder_handshake
 };
-use handshake::Handshake;
 use nautilus_core::connection::Connection;
 use std::time::Duration;
+use zeroize::Zeroizing;
 use tokio::time::timeout;
 
 #[derive(Clone)]
@@ -27,14 +28,15 @@ impl TlsSession {
         role: HandshakeRole,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         // Make sure we also use tokio::sync::Mutex for TlsState
-        let state = Arc::new(Mutex::new(TlsState::default()));
+        let state = Arc::new(Mutex::new(TlsState::new()));
 
-        let mut handshake = Handshake::new("TLS_HANDSHAKE");
-        let hello_step = HelloStep::new("TLS_HANDSHAKE", role);
-        let kyber_step = KyberExchangeStep::new(role, state.clone());
-        handshake.add_step(Box::new(hello_step));
-        handshake.add_step(Box::new(kyber_step));
-        handshake.add_step(Box::new(FinishStep { role }));
+        let handshake = match role {
+            HandshakeRole::Initiator => build_initiator_handshake(state.clone()),
+            HandshakeRole::Responder => build_responder_handshake(state.clone()),
+            HandshakeRole::Unknown => {
+                return Err("TlsSession cannot proceed with Unknown role".into());
+            }
+        };
 
         // Build TlsConnection, which does the handshake
         let connection = TlsConnection::new(socket, handshake, state).await?;
@@ -51,7 +53,7 @@ impl TlsSession {
     pub async fn receive(&mut self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         self.connection.receive().await.map_err(Into::into)
     }
-    pub async fn get_session_key(&self) -> Vec<u8> {
+    pub async fn get_session_key(&self) -> Zeroizing<Vec<u8>> {
         self.connection.get_session_key().await
     }
     pub async fn split(&self) -> (crate::connection::TlsReader, crate::connection::TlsWriter) {
@@ -88,3 +90,78 @@ pub async fn adaptive_session(
         }
     }
 }
+
+/// Wait before `accept_with_limit` retries after its first failed accept.
+const ACCEPT_RETRY_MIN: Duration = Duration::from_millis(10);
+/// Longest wait between retries while accepts keep failing.
+const ACCEPT_RETRY_MAX: Duration = Duration::from_secs(1);
+
+/// Accepts connections on `listener` in a background task, running `handshake` on each
+/// with at most `max_concurrent` (at least one) handshakes in progress at once.
+///
+/// Beyond the limit, connections are not accepted until a slot frees, so they queue
+/// in the listener's backlog instead of each costing a task and a key generation.
+/// A slot is held only for the handshake itself; its result is delivered on the
+/// returned channel, and dropping the receiver stops the acceptor. Failed accepts
+/// (e.g. out of file descriptors) are retried after a delay that doubles up to one
+/// second, so a persistent error does not spin the task.
+///
+/// ```ignore
+/// let mut sessions = accept_with_limit(listener, 64, |socket| {
+///     TlsSession::new(socket, HandshakeRole::Responder)
+/// });
+/// ```
+pub fn accept_with_limit<F, Fut>(
+    listener: TcpListener,
+    max_concurrent: usize,
+    handshake: F,
+) -> mpsc::Receiver<Fut::Output>
+where
+    F: Fn(TcpStream) -> Fut + Send + Sync + 'static,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    let max_concurrent = max_concurrent.max(1);
+    let (sender, receiver) = mpsc::channel(max_concurrent);
+    let slots = Arc::new(Semaphore::new(max_concurrent));
+    let handshake = Arc::new(handshake);
+
+    tokio::spawn(async move {
+        let mut retry_delay = ACCEPT_RETRY_MIN;
+        loop {
+            let permit = tokio::select! {
+                permit = slots.clone().acquire_owned() => permit.expect("acceptor semaphore is never closed"),
+                _ = sender.closed() => break,
+            };
+            let socket = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((socket, _)) => {
+                        retry_delay = ACCEPT_RETRY_MIN;
+                        socket
+                    }
+                    Err(e) => {
+                        println!("[Acceptor] => accept error: {}, retrying in {:?}", e, retry_delay);
+                        drop(permit);
+                        tokio::select! {
+                            _ = tokio::time::sleep(retry_delay) => {}
+                            _ = sender.closed() => break,
+                        }
+                        retry_delay = (retry_delay * 2).min(ACCEPT_RETRY_MAX);
+                        continue;
+                    }
+                },
+                _ = sender.closed() => break,
+            };
+
+            let handshake = handshake.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let result = handshake(socket).await;
+                drop(permit);
+                let _ = sender.send(result).await;
+            });
+        }
+    });
+
+    receiver
+}
@@ -1,27 +1,84 @@
 // protocols\tls\src\tls_state.rs
+use crate::record::{record_aead, RecordError};
+use data_encryption::{Aead, SymmetricCipherSuite};
+use hmac::{Hmac, Mac};
+use sha3::Sha3_256;
+use std::error::Error;
+use zeroize::Zeroizing;
+
+/// Length of the transcript MAC tag appended to authenticated handshake messages.
+pub(crate) const TRANSCRIPT_MAC_LEN: usize = 32;
+
+/// Every handshake message seen so far, and the MAC key once the key exchange has
+/// produced one.
+#[derive(Default)]
+struct Transcript {
+    messages: Vec<u8>,
+    key: Option<Zeroizing<Vec<u8>>>,
+}
+
 #[derive(Default)]
 pub struct TlsState {
+    /// Set by `FinishStep`; a completed state must not take part in another handshake.
     handshake_complete: bool,
-    session_key: Option<Vec<u8>>,
+    /// Wiped when replaced or dropped.
+    session_key: Option<Zeroizing<Vec<u8>>>,
     negotiated_cipher_suite: Option<Vec<u8>>,
     supported_cipher_suites: Vec<u8>,
+    /// Present when the transcript MAC is enabled.
+    transcript: Option<Transcript>,
+    /// AEAD protecting application records; AES-256-GCM when unset.
+    record_cipher: Option<SymmetricCipherSuite>,
 }
 
 impl TlsState {
-    pub fn set_handshake_complete(&mut self, complete: bool) {
-        self.handshake_complete = complete;
+    /// Creates a clean state for a single handshake.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails if this state already completed a handshake.
+    pub fn ensure_fresh(&self) -> Result<(), TlsStateError> {
+        if self.handshake_complete {
+            Err(TlsStateError::AlreadyCompleted)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Marks the handshake as complete. There is no way back: a completed state
+    /// refuses further key material, so it cannot be reused for another handshake.
+    pub fn mark_handshake_complete(&mut self) {
+        self.handshake_complete = true;
     }
 
     pub fn handshake_complete(&self) -> bool {
         self.handshake_complete
     }
 
-    pub fn set_session_key(&mut self, key: Vec<u8>) {
-        self.session_key = Some(key);
+    /// Stores the session key. Refused once the handshake has completed, so a state
+    /// reused for a second connection cannot mix secrets.
+    pub fn set_session_key(&mut self, key: Vec<u8>) -> Result<(), TlsStateError> {
+        self.ensure_fresh()?;
+        self.session_key = Some(Zeroizing::new(key));
+        Ok(())
     }
 
-    pub fn session_key(&self) -> &[u8] {
-        self.session_key.as_deref().unwrap_or_default()
+    /// The live session key. Kept inside the crate; callers outside use
+    /// `export_session_key` or `record_aead`.
+    pub(crate) fn session_key(&self) -> &[u8] {
+        self.session_key.as_ref().map(|key| key.as_slice()).unwrap_or_default()
+    }
+
+    /// Exports a copy of the negotiated session key for use outside this crate,
+    /// e.g. to key a QUIC connection or a custom record layer.
+    ///
+    /// **Sensitive:** this is the live record key. Anyone holding it can read and
+    /// forge traffic on this connection. The copy is wiped when the returned value
+    /// is dropped; do not clone it into plain buffers or log it. Returns an empty key
+    /// before the key exchange has run.
+    pub fn export_session_key(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.session_key().to_vec())
     }
 
     pub fn set_negotiated_cipher_suite(&mut self, suite: Vec<u8>) {
@@ -39,4 +96,82 @@ impl TlsState {
     pub fn supported_cipher_suites(&self) -> &[u8] {
         &self.supported_cipher_suites
     }
+
+    /// Selects the AEAD the record layer seals application data with. `CipherSuiteStep`
+    /// calls this with the negotiated suite; both peers must select the same one.
+    pub fn set_record_cipher(&mut self, suite: SymmetricCipherSuite) {
+        self.record_cipher = Some(suite);
+    }
+
+    /// The record-layer AEAD, AES-256-GCM unless `set_record_cipher` chose another.
+    pub fn record_cipher(&self) -> SymmetricCipherSuite {
+        self.record_cipher
+            .clone()
+            .unwrap_or(SymmetricCipherSuite::AES256GCM { priority: 0 })
+    }
+
+    /// Builds the record-layer AEAD for `record_cipher`, keyed with the session key.
+    /// Use it with `TlsRecord::seal`/`open` so records follow the negotiated cipher.
+    pub fn record_aead(&self) -> Result<Box<dyn Aead>, RecordError> {
+        record_aead(&self.record_cipher(), self.session_key())
+    }
+
+    /// Authenticates each handshake message once the key exchange has produced a
+    /// secret: `KyberExchangeStep` and `FinishStep` append an HMAC over the transcript
+    /// so far, and the peer checks it before going on. `HelloStep` and `CipherSuiteStep`
+    /// record their messages too when given the state, so those tags cover the whole
+    /// handshake. Both peers must enable it.
+    pub fn enable_transcript_mac(&mut self) {
+        self.transcript.get_or_insert_with(Transcript::default);
+    }
+
+    pub fn transcript_mac_enabled(&self) -> bool {
+        self.transcript.is_some()
+    }
+
+    /// Appends a handshake message to the transcript. Does nothing when disabled.
+    pub(crate) fn absorb_transcript(&mut self, message: &[u8]) {
+        if let Some(transcript) = &mut self.transcript {
+            transcript.messages.extend_from_slice(&(message.len() as u32).to_be_bytes());
+            transcript.messages.extend_from_slice(message);
+        }
+    }
+
+    pub(crate) fn set_transcript_mac_key(&mut self, key: Vec<u8>) {
+        if let Some(transcript) = &mut self.transcript {
+            transcript.key = Some(Zeroizing::new(key));
+        }
+    }
+
+    fn transcript_mac(&self) -> Option<Hmac<Sha3_256>> {
+        let transcript = self.transcript.as_ref()?;
+        let mut mac = Hmac::<Sha3_256>::new_from_slice(transcript.key.as_ref()?).ok()?;
+        mac.update(&transcript.messages);
+        Some(mac)
+    }
+
+    /// The MAC over the transcript so far, or `None` before a MAC key is set.
+    pub(crate) fn transcript_tag(&self) -> Option<Vec<u8>> {
+        self.transcript_mac().map(|mac| mac.finalize().into_bytes().to_vec())
+    }
+
+    /// Checks `tag` against the transcript so far in constant time.
+    pub(crate) fn verify_transcript_tag(&self, tag: &[u8]) -> bool {
+        self.transcript_mac().is_some_and(|mac| mac.verify_slice(tag).is_ok())
+    }
+}
+
+#[derive(Debug)]
+pub enum TlsStateError {
+    AlreadyCompleted,
 }
+
+impl std::fmt::Display for TlsStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsStateError::AlreadyCompleted => write!(f, "TLS state already completed a handshake"),
+        }
+    }
+}
+
+impl Error for TlsStateError {}
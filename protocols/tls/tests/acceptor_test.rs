@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tls::accept_with_limit;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::Semaphore;
+
+    #[tokio::test]
+    async fn test_third_handshake_waits_for_a_free_slot() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // Each "handshake" runs until the test lets it finish.
+        let started = Arc::new(AtomicUsize::new(0));
+        let finish = Arc::new(Semaphore::new(0));
+        let mut results = {
+            let started = started.clone();
+            let finish = finish.clone();
+            accept_with_limit(listener, 2, move |_socket| {
+                let started = started.clone();
+                let finish = finish.clone();
+                async move {
+                    let index = started.fetch_add(1, Ordering::SeqCst);
+                    finish.acquire().await.unwrap().forget();
+                    index
+                }
+            })
+        };
+
+        let _clients = [
+            TcpStream::connect(address).await.unwrap(),
+            TcpStream::connect(address).await.unwrap(),
+            TcpStream::connect(address).await.unwrap(),
+        ];
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(started.load(Ordering::SeqCst), 2, "only two handshakes may run at once");
+
+        finish.add_permits(1);
+        let first = tokio::time::timeout(Duration::from_secs(1), results.recv()).await.unwrap();
+        assert!(first.is_some());
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while started.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("the third handshake should start once a slot frees");
+    }
+}
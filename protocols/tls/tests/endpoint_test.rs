@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use nautilus_core::connection::Connection;
+    use std::time::Duration;
+    use tls::{build_initiator_handshake, build_responder_handshake, TlsClient, TlsServer};
+    use tokio::net::TcpStream;
+
+    #[tokio::test]
+    async fn test_encrypted_echo_over_loopback() {
+        let server = TlsServer::bind("127.0.0.1:0", build_responder_handshake).await.unwrap();
+        let address = server.local_addr().unwrap();
+
+        let echo = tokio::spawn(async move {
+            let (mut connection, _peer) = server.accept().await.expect("server handshake failed");
+            let message = connection.receive().await.expect("server receive failed");
+            connection.send(&message).await.expect("server send failed");
+            connection.get_session_key().await
+        });
+
+        let mut client = TlsClient::connect(address, build_initiator_handshake)
+            .await
+            .expect("client handshake failed");
+        client.send(b"hello over tls").await.unwrap();
+        let reply = client.receive().await.unwrap();
+        assert_eq!(reply, b"hello over tls");
+
+        let server_key = echo.await.unwrap();
+        assert_eq!(client.get_session_key().await, server_key);
+        assert_eq!(server_key.len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_accept_times_out_on_silent_client() {
+        let server = TlsServer::bind("127.0.0.1:0", build_responder_handshake)
+            .await
+            .unwrap()
+            .with_handshake_timeout(Duration::from_millis(100));
+        let address = server.local_addr().unwrap();
+
+        // Connects but never sends a HELLO.
+        let _silent = TcpStream::connect(address).await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), server.accept())
+            .await
+            .expect("accept should give up on its own");
+        let error = result.err().expect("a silent client must not complete a handshake");
+        let error = error.downcast_ref::<std::io::Error>().expect("expected an io::Error");
+        assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,287 @@
+#[cfg(test)]
+mod tests {
+    use handshake::{read_frame, write_frame, HandshakeError, HandshakeStep};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tls::{FinishStep, HandshakeRole, HelloNonceCache, HelloStep, KyberExchangeStep, TlsState, HELLO_NONCE_LEN};
+    use tokio::sync::Mutex;
+
+    /// Passes at most `segment` bytes through per read or write, like a slow link.
+    struct Segmented<S> {
+        inner: S,
+        segment: usize,
+    }
+
+    impl<S: tokio::io::AsyncRead + Unpin> tokio::io::AsyncRead for Segmented<S> {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            let mut chunk = vec![0u8; self.segment.min(buf.remaining())];
+            let mut limited = tokio::io::ReadBuf::new(&mut chunk);
+            let poll = std::pin::Pin::new(&mut self.inner).poll_read(cx, &mut limited);
+            if let std::task::Poll::Ready(Ok(())) = poll {
+                buf.put_slice(limited.filled());
+            }
+            poll
+        }
+    }
+
+    impl<S: tokio::io::AsyncWrite + Unpin> tokio::io::AsyncWrite for Segmented<S> {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            let len = buf.len().min(self.segment);
+            std::pin::Pin::new(&mut self.inner).poll_write(cx, &buf[..len])
+        }
+
+        fn poll_flush(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hello_step_rejects_unexpected_input() {
+        let (mut stream, _peer) = tokio::io::duplex(1024);
+        let mut step = HelloStep::new("TLS_HANDSHAKE", HandshakeRole::Initiator);
+
+        let result = step.execute(&mut stream, b"CIPHERSUITES".to_vec()).await;
+        match result {
+            Err(HandshakeError::StepError(msg)) => assert!(msg.contains("HelloStep")),
+            other => panic!("Expected StepError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kyber_step_rejects_unexpected_input() {
+        let (mut stream, _peer) = tokio::io::duplex(1024);
+        let state = Arc::new(Mutex::new(TlsState::new()));
+        let mut step = KyberExchangeStep::new(HandshakeRole::Responder, state);
+
+        let result = step.execute(&mut stream, vec![1, 2, 3]).await;
+        assert!(matches!(result, Err(HandshakeError::StepError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_responder_rejects_replayed_hello() {
+        let cache = Arc::new(HelloNonceCache::new(Duration::from_secs(60)));
+        let mut hello = b"HELLO".to_vec();
+        hello.extend_from_slice(&[7u8; HELLO_NONCE_LEN]);
+
+        // First delivery is accepted and acknowledged.
+        let (mut stream, mut peer) = tokio::io::duplex(1024);
+        write_frame(&mut peer, &hello).await.unwrap();
+        let mut step = HelloStep::new("TLS_HANDSHAKE", HandshakeRole::Responder)
+            .with_nonce_cache(cache.clone());
+        step.execute(&mut stream, vec![]).await.expect("fresh HELLO rejected");
+        let ack = read_frame(&mut peer, 64).await.unwrap();
+        assert_eq!(ack, b"HELLO_ACK");
+
+        // Replaying the captured bytes on a new connection is refused.
+        let (mut stream, mut peer) = tokio::io::duplex(1024);
+        write_frame(&mut peer, &hello).await.unwrap();
+        let mut step = HelloStep::new("TLS_HANDSHAKE", HandshakeRole::Responder)
+            .with_nonce_cache(cache.clone());
+        match step.execute(&mut stream, vec![]).await {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "replayed hello"),
+            other => panic!("Expected replayed hello error, got {:?}", other),
+        }
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_nonce_cache_forgets_after_window() {
+        let cache = HelloNonceCache::new(Duration::from_millis(10));
+        assert!(cache.check_and_insert([1u8; HELLO_NONCE_LEN]));
+        assert!(!cache.check_and_insert([1u8; HELLO_NONCE_LEN]));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(cache.check_and_insert([1u8; HELLO_NONCE_LEN]));
+    }
+
+    #[test]
+    fn test_nonce_cache_evicts_oldest_at_capacity() {
+        let cache = HelloNonceCache::with_capacity(Duration::from_secs(60), 2);
+        assert!(cache.check_and_insert([1u8; HELLO_NONCE_LEN]));
+        assert!(cache.check_and_insert([2u8; HELLO_NONCE_LEN]));
+        assert!(cache.check_and_insert([3u8; HELLO_NONCE_LEN]));
+        assert_eq!(cache.len(), 2);
+
+        // The oldest nonce made room; the newer ones are still remembered.
+        assert!(!cache.check_and_insert([2u8; HELLO_NONCE_LEN]));
+        assert!(!cache.check_and_insert([3u8; HELLO_NONCE_LEN]));
+        assert!(cache.check_and_insert([1u8; HELLO_NONCE_LEN]));
+    }
+
+    #[tokio::test]
+    async fn test_kyber_exchange_over_segmented_stream() {
+        let (initiator_io, responder_io) = tokio::io::duplex(64);
+        let mut initiator_io = Segmented { inner: initiator_io, segment: 500 };
+        let mut responder_io = Segmented { inner: responder_io, segment: 500 };
+
+        let initiator_state = Arc::new(Mutex::new(TlsState::new()));
+        let responder_state = Arc::new(Mutex::new(TlsState::new()));
+        let mut initiator = KyberExchangeStep::new(HandshakeRole::Initiator, initiator_state.clone())
+            .with_read_timeout(Duration::from_secs(5));
+        let mut responder = KyberExchangeStep::new(HandshakeRole::Responder, responder_state.clone());
+
+        let (initiated, responded) = tokio::join!(
+            initiator.execute(&mut initiator_io, vec![]),
+            responder.execute(&mut responder_io, vec![])
+        );
+        initiated.expect("initiator failed on a segmented ciphertext");
+        responded.expect("responder failed on a segmented public key");
+
+        let initiator_key = initiator_state.lock().await.export_session_key();
+        let responder_key = responder_state.lock().await.export_session_key();
+        assert_eq!(*initiator_key, *responder_key);
+    }
+
+    #[tokio::test]
+    async fn test_kyber_exchange_times_out_on_silent_peer() {
+        let (mut stream, _peer) = tokio::io::duplex(4096);
+        let state = Arc::new(Mutex::new(TlsState::new()));
+        let mut step = KyberExchangeStep::new(HandshakeRole::Responder, state)
+            .with_read_timeout(Duration::from_millis(50));
+
+        match step.execute(&mut stream, vec![]).await {
+            Err(HandshakeError::Generic(msg)) => assert!(msg.contains("Timed out")),
+            other => panic!("Expected a timeout, got {:?}", other),
+        }
+    }
+
+    fn mac_state() -> Arc<Mutex<TlsState>> {
+        let mut state = TlsState::new();
+        state.enable_transcript_mac();
+        Arc::new(Mutex::new(state))
+    }
+
+    #[tokio::test]
+    async fn test_transcript_mac_handshake_completes() {
+        let (mut initiator_io, mut responder_io) = tokio::io::duplex(4096);
+        let (initiator_state, responder_state) = (mac_state(), mac_state());
+
+        let initiator = async {
+            KyberExchangeStep::new(HandshakeRole::Initiator, initiator_state.clone())
+                .execute(&mut initiator_io, vec![])
+                .await?;
+            FinishStep::new(HandshakeRole::Initiator, initiator_state.clone())
+                .execute(&mut initiator_io, vec![])
+                .await
+        };
+        let responder = async {
+            KyberExchangeStep::new(HandshakeRole::Responder, responder_state.clone())
+                .execute(&mut responder_io, vec![])
+                .await?;
+            FinishStep::new(HandshakeRole::Responder, responder_state.clone())
+                .execute(&mut responder_io, vec![])
+                .await
+        };
+        let (initiated, responded) = tokio::join!(initiator, responder);
+        initiated.expect("initiator failed");
+        responded.expect("responder failed");
+
+        assert!(initiator_state.lock().await.handshake_complete());
+        assert_eq!(
+            *initiator_state.lock().await.export_session_key(),
+            *responder_state.lock().await.export_session_key()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tampered_ciphertext_fails_transcript_mac() {
+        let (mut initiator_io, mut initiator_peer) = tokio::io::duplex(4096);
+        let (mut responder_peer, mut responder_io) = tokio::io::duplex(4096);
+
+        // Relays the public key untouched and flips one ciphertext byte on the way back.
+        let relay = tokio::spawn(async move {
+            let public_key = read_frame(&mut initiator_peer, 1 << 16).await.unwrap();
+            write_frame(&mut responder_peer, &public_key).await.unwrap();
+            let mut ciphertext = read_frame(&mut responder_peer, 1 << 16).await.unwrap();
+            ciphertext[100] ^= 0x01;
+            write_frame(&mut initiator_peer, &ciphertext).await.unwrap();
+            (initiator_peer, responder_peer)
+        });
+
+        let initiator_state = mac_state();
+        let mut initiator = KyberExchangeStep::new(HandshakeRole::Initiator, initiator_state.clone());
+        let mut responder = KyberExchangeStep::new(HandshakeRole::Responder, mac_state());
+        let (initiated, responded) = tokio::join!(
+            initiator.execute(&mut initiator_io, vec![]),
+            responder.execute(&mut responder_io, vec![])
+        );
+        responded.expect("responder does not see the tampering");
+        match initiated {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "transcript MAC mismatch"),
+            other => panic!("Expected a transcript MAC mismatch, got {:?}", other),
+        }
+        assert!(initiator_state.lock().await.export_session_key().is_empty());
+        let _ = relay.await;
+    }
+
+    #[tokio::test]
+    async fn test_cipher_suite_step_rejects_oversized_frame() {
+        use identity::{CipherSuite, CipherSuitePolicy};
+        use tls::CipherSuiteStep;
+        use tokio::io::AsyncWriteExt;
+
+        // Declare a ~4 GB suite list but never send it.
+        let (mut stream, mut peer) = tokio::io::duplex(64);
+        peer.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+
+        let mut responder = CipherSuiteStep::new("TLS_HANDSHAKE").with_policy(
+            HandshakeRole::Responder,
+            vec![CipherSuite::Kyber { priority: 0 }],
+            CipherSuitePolicy::default(),
+        );
+        match responder.execute(&mut stream, vec![]).await {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "message too large"),
+            other => panic!("Expected message too large error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cipher_suite_negotiation_uses_server_preference() {
+        use identity::{CipherSuite, CipherSuitePolicy};
+        use tls::CipherSuiteStep;
+
+        let suite = |name: &str, priority| CipherSuite::Custom { name: name.to_string(), priority };
+        let (mut initiator_io, mut responder_io) = tokio::io::duplex(4096);
+
+        // The client lists A first; the server supports both but prefers B.
+        let mut initiator = CipherSuiteStep::new("TLS_HANDSHAKE").with_policy(
+            HandshakeRole::Initiator,
+            vec![suite("A", 1), suite("B", 2)],
+            CipherSuitePolicy::default(),
+        );
+        let mut responder = CipherSuiteStep::new("TLS_HANDSHAKE").with_policy(
+            HandshakeRole::Responder,
+            vec![suite("A", 1), suite("B", 1)],
+            CipherSuitePolicy::new(&[suite("B", 0), suite("A", 0)]),
+        );
+        let (initiated, responded) = tokio::join!(
+            initiator.execute(&mut initiator_io, vec![]),
+            responder.execute(&mut responder_io, vec![])
+        );
+        let initiated = initiated.expect("initiator failed");
+        assert_eq!(initiated, responded.expect("responder failed"));
+        let chosen: CipherSuite = serde_json::from_slice(&initiated).unwrap();
+        assert_eq!(chosen.name(), "B");
+
+        let policy = CipherSuitePolicy::new(&[suite("C", 0)]);
+        assert_eq!(policy.select(&[suite("A", 1)], &[suite("A", 1)]).unwrap().name(), "A");
+        assert!(policy.select(&[suite("A", 1)], &[suite("C", 1)]).is_none());
+    }
+}
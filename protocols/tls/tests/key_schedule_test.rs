@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod key_schedule_testing {
+    use tls::derive_keys;
+
+    #[test]
+    fn test_labels_produce_independent_reproducible_keys() {
+        let secret = [7u8; 32];
+        let keys = derive_keys(&secret, &[b"send", b"recv"], &[32, 32]);
+        let again = derive_keys(&secret, &[b"send", b"recv"], &[32, 32]);
+
+        assert_eq!(keys, again);
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(keys[0], secret.to_vec());
+    }
+
+    #[test]
+    fn test_xof_output_longer_than_hash() {
+        let secret = [1u8; 32];
+        let keys = derive_keys(&secret, &[b"key", b"nonce"], &[100, 12]);
+
+        assert_eq!(keys[0].len(), 100);
+        assert_eq!(keys[1].len(), 12);
+        // A shorter read of the same label is a prefix of the longer one.
+        let short = derive_keys(&secret, &[b"key"], &[32]);
+        assert_eq!(short[0], keys[0][..32]);
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use data_encryption::{Aead, SymmetricCipherSuite};
+    use identity::CipherSuite;
+    use tls::{record_aead, record_cipher_for, RecordError, RecordType, TlsRecord};
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    /// Seals a record with `aead`, sends it through a stream and opens it again,
+    /// checking the wire layout is type byte, 12-byte nonce, ciphertext, 16-byte tag.
+    async fn round_trip(aead: &dyn Aead) {
+        let payload = b"same framing for every AEAD".to_vec();
+        let mut record = TlsRecord::new(RecordType::ApplicationData, payload.clone());
+        record.seal(aead).expect("seal failed");
+
+        let wire = record.serialize();
+        assert_eq!(wire.len(), 1 + 12 + payload.len() + 16);
+        assert_eq!(wire[0], 0x02);
+
+        let (mut writer, mut reader) = tokio::io::duplex(4096);
+        record.write_to(&mut writer).await.unwrap();
+        let mut received = TlsRecord::read_from(&mut reader).await.unwrap();
+        assert_eq!(received.open(aead).expect("open failed"), payload);
+
+        // The type byte is authenticated: relabelling the record breaks it.
+        let mut relabelled = wire.clone();
+        relabelled[0] = 0x01;
+        let mut relabelled = TlsRecord::deserialize(&relabelled).unwrap();
+        assert!(matches!(relabelled.open(aead), Err(RecordError::DecryptionError)));
+    }
+
+    #[tokio::test]
+    async fn test_record_layer_over_aes_gcm() {
+        let aes = record_aead(&SymmetricCipherSuite::AES256GCM { priority: 0 }, &KEY).unwrap();
+        round_trip(aes.as_ref()).await;
+
+        // `encrypt`/`decrypt` are the AES-256-GCM case of `seal`/`open`.
+        let mut record = TlsRecord::new(RecordType::ApplicationData, b"legacy".to_vec());
+        record.encrypt(&KEY).unwrap();
+        assert_eq!(record.open(aes.as_ref()).unwrap(), b"legacy");
+    }
+
+    #[cfg(feature = "chacha20")]
+    #[tokio::test]
+    async fn test_record_layer_over_chacha20_poly1305() {
+        let chacha = record_aead(&SymmetricCipherSuite::ChaCha20 { priority: 0 }, &KEY).unwrap();
+        round_trip(chacha.as_ref()).await;
+
+        // Same key and framing, different cipher: the records do not interoperate.
+        let aes = record_aead(&SymmetricCipherSuite::AES256GCM { priority: 0 }, &KEY).unwrap();
+        let mut record = TlsRecord::new(RecordType::ApplicationData, b"cipher bound".to_vec());
+        record.seal(chacha.as_ref()).unwrap();
+        assert!(record.open(aes.as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_record_cipher_follows_negotiated_suite_name() {
+        let aes = CipherSuite::Custom { name: "AES256-GCM".to_string(), priority: 3 };
+        assert_eq!(record_cipher_for(&aes).unwrap(), Some(SymmetricCipherSuite::AES256GCM { priority: 0 }));
+        assert_eq!(record_cipher_for(&CipherSuite::Kyber { priority: 0 }).unwrap(), None);
+
+        // A known cipher the record layer cannot run is refused, not downgraded.
+        let blowfish = CipherSuite::Custom { name: "Blowfish".to_string(), priority: 0 };
+        assert!(matches!(record_cipher_for(&blowfish), Err(RecordError::UnsupportedCipher)));
+        let chacha = CipherSuite::Custom { name: "ChaCha20".to_string(), priority: 0 };
+        assert_eq!(record_cipher_for(&chacha).is_ok(), cfg!(feature = "chacha20"));
+    }
+
+    #[test]
+    fn test_unknown_record_cipher_is_refused() {
+        let custom = SymmetricCipherSuite::Custom { name: "ROT13".to_string(), priority: 0 };
+        assert!(matches!(record_aead(&custom, &KEY), Err(RecordError::UnsupportedCipher)));
+    }
+}
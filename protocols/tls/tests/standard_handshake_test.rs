@@ -0,0 +1,248 @@
+#[cfg(test)]
+mod tests {
+    use handshake::{read_frame, write_frame, HandshakeError};
+    use identity::{CipherSuite, CipherSuitePolicy};
+    use std::sync::Arc;
+    use tls::{
+        build_initiator_handshake, build_initiator_handshake_with_policy, build_responder_handshake,
+        build_responder_handshake_with_policy, TlsState,
+    };
+    use tokio::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_standard_handshake_derives_matching_session_keys() {
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let server_state = Arc::new(Mutex::new(TlsState::new()));
+
+        let mut initiator = build_initiator_handshake(client_state.clone());
+        let mut responder = build_responder_handshake(server_state.clone());
+
+        let (client_result, server_result) = tokio::join!(
+            initiator.execute(&mut client_stream),
+            responder.execute(&mut server_stream),
+        );
+        let client_suite = client_result.expect("initiator handshake failed");
+        let server_suite = server_result.expect("responder handshake failed");
+        assert_eq!(client_suite, server_suite);
+        let chosen: CipherSuite = serde_json::from_slice(&client_suite).unwrap();
+        assert_eq!(chosen.name(), "Kyber");
+
+        let client_key = client_state.lock().await.export_session_key().to_vec();
+        let server_key = server_state.lock().await.export_session_key().to_vec();
+        assert_eq!(client_key.len(), 32);
+        assert_eq!(client_key, server_key);
+        assert!(client_state.lock().await.handshake_complete());
+        assert!(server_state.lock().await.handshake_complete());
+    }
+
+    #[tokio::test]
+    async fn test_exported_session_keys_match() {
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let server_state = Arc::new(Mutex::new(TlsState::new()));
+        assert!(client_state.lock().await.export_session_key().is_empty());
+
+        let mut initiator = build_initiator_handshake(client_state.clone());
+        let mut responder = build_responder_handshake(server_state.clone());
+        let (client_result, server_result) = tokio::join!(
+            initiator.execute(&mut client_stream),
+            responder.execute(&mut server_stream),
+        );
+        client_result.expect("initiator handshake failed");
+        server_result.expect("responder handshake failed");
+
+        let client_key = client_state.lock().await.export_session_key();
+        let server_key = server_state.lock().await.export_session_key();
+        assert_eq!(client_key.len(), 32);
+        assert_eq!(*client_key, *server_key);
+        assert_eq!(client_key, client_state.lock().await.export_session_key());
+    }
+
+    #[tokio::test]
+    async fn test_completed_state_rejects_second_handshake() {
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let mut initiator = build_initiator_handshake(client_state.clone());
+        let mut responder = build_responder_handshake(Arc::new(Mutex::new(TlsState::new())));
+        let (first, _) = tokio::join!(
+            initiator.execute(&mut client_stream),
+            responder.execute(&mut server_stream),
+        );
+        first.expect("first handshake failed");
+        let first_key = client_state.lock().await.export_session_key().to_vec();
+
+        // Reuse the completed state against a fresh responder.
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let mut responder = build_responder_handshake(Arc::new(Mutex::new(TlsState::new())));
+        tokio::spawn(async move {
+            let _ = responder.execute(&mut server_stream).await;
+        });
+        let mut initiator = build_initiator_handshake(client_state.clone());
+        let result = initiator.execute(&mut client_stream).await;
+
+        assert!(matches!(result, Err(HandshakeError::StepError(_))), "got {:?}", result);
+        assert_eq!(client_state.lock().await.export_session_key().as_slice(), first_key.as_slice());
+        assert!(client_state.lock().await.set_session_key(vec![0; 32]).is_err());
+    }
+
+    fn mac_state() -> Arc<Mutex<TlsState>> {
+        let mut state = TlsState::new();
+        state.enable_transcript_mac();
+        Arc::new(Mutex::new(state))
+    }
+
+    #[tokio::test]
+    async fn test_tampered_hello_fails_transcript_mac() {
+        let (mut client_stream, mut client_peer) = tokio::io::duplex(8192);
+        let (mut server_peer, mut server_stream) = tokio::io::duplex(8192);
+
+        // Flips one nonce byte of the HELLO, then relays everything else untouched.
+        tokio::spawn(async move {
+            let mut hello = read_frame(&mut client_peer, 1 << 16).await.unwrap();
+            hello[6] ^= 0x01;
+            write_frame(&mut server_peer, &hello).await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut client_peer, &mut server_peer).await;
+        });
+
+        // The responder's first tag already covers the altered HELLO, so the initiator
+        // rejects the ciphertext and closes its stream.
+        let client_state = mac_state();
+        let mut initiator = build_initiator_handshake(client_state.clone());
+        let mut responder = build_responder_handshake(mac_state());
+        let server = tokio::spawn(async move { responder.execute(&mut server_stream).await });
+        let client_result = initiator.execute(&mut client_stream).await;
+        drop(client_stream);
+
+        match client_result {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "transcript MAC mismatch"),
+            other => panic!("Expected a transcript MAC mismatch, got {:?}", other),
+        }
+        assert!(client_state.lock().await.export_session_key().is_empty());
+        assert!(server.await.unwrap().is_err());
+    }
+
+    fn suite(name: &str) -> CipherSuite {
+        CipherSuite::Custom { name: name.to_string(), priority: 0 }
+    }
+
+    #[tokio::test]
+    async fn test_standard_handshake_applies_responder_policy() {
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let mut initiator = build_initiator_handshake_with_policy(
+            client_state.clone(),
+            vec![suite("A"), suite("B")],
+            CipherSuitePolicy::default(),
+        );
+        let mut responder = build_responder_handshake_with_policy(
+            Arc::new(Mutex::new(TlsState::new())),
+            vec![suite("A"), suite("B")],
+            CipherSuitePolicy::new(&[suite("B"), suite("A")]),
+        );
+
+        let (client_result, server_result) = tokio::join!(
+            initiator.execute(&mut client_stream),
+            responder.execute(&mut server_stream),
+        );
+        let client_suite = client_result.expect("initiator handshake failed");
+        server_result.expect("responder handshake failed");
+        let chosen: CipherSuite = serde_json::from_slice(&client_suite).unwrap();
+        assert_eq!(chosen.name(), "B");
+        assert_eq!(client_state.lock().await.negotiated_cipher_suite(), client_suite.as_slice());
+    }
+
+    #[cfg(feature = "chacha20")]
+    #[tokio::test]
+    async fn test_negotiated_suite_selects_record_cipher() {
+        use data_encryption::SymmetricCipherSuite;
+
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let server_state = Arc::new(Mutex::new(TlsState::new()));
+        let mut initiator = build_initiator_handshake_with_policy(
+            client_state.clone(),
+            vec![suite("ChaCha20")],
+            CipherSuitePolicy::default(),
+        );
+        let mut responder = build_responder_handshake_with_policy(
+            server_state.clone(),
+            vec![suite("AES256-GCM"), suite("ChaCha20")],
+            CipherSuitePolicy::default(),
+        );
+        let (client_result, server_result) = tokio::join!(
+            initiator.execute(&mut client_stream),
+            responder.execute(&mut server_stream),
+        );
+        client_result.expect("initiator handshake failed");
+        server_result.expect("responder handshake failed");
+
+        let chacha = SymmetricCipherSuite::ChaCha20 { priority: 0 };
+        assert_eq!(client_state.lock().await.record_cipher(), chacha);
+        assert_eq!(server_state.lock().await.record_cipher(), chacha);
+    }
+
+    #[tokio::test]
+    async fn test_standard_handshake_fails_when_responder_rejects_every_suite() {
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let server_state = Arc::new(Mutex::new(TlsState::new()));
+        let mut initiator = build_initiator_handshake_with_policy(
+            client_state.clone(),
+            vec![suite("LEGACY")],
+            CipherSuitePolicy::default(),
+        );
+        let mut responder = build_responder_handshake_with_policy(
+            server_state.clone(),
+            vec![CipherSuite::Kyber { priority: 0 }],
+            CipherSuitePolicy::new(&[CipherSuite::Kyber { priority: 0 }]),
+        );
+
+        // The responder owns its stream so its failure closes it and unblocks the initiator.
+        let server = tokio::spawn(async move { responder.execute(&mut server_stream).await });
+        let client_result = initiator.execute(&mut client_stream).await;
+
+        match server.await.unwrap() {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "No common cipher suite"),
+            other => panic!("Expected the responder to reject the offer, got {:?}", other),
+        }
+        assert!(client_result.is_err());
+        assert!(client_state.lock().await.export_session_key().is_empty());
+        assert!(!server_state.lock().await.handshake_complete());
+    }
+
+    #[cfg(not(feature = "chacha20"))]
+    #[tokio::test]
+    async fn test_unsupported_record_cipher_is_not_negotiated() {
+        let (mut client_stream, mut server_stream) = tokio::io::duplex(8192);
+        let client_state = Arc::new(Mutex::new(TlsState::new()));
+        let mut initiator = build_initiator_handshake_with_policy(
+            client_state.clone(),
+            vec![suite("ChaCha20")],
+            CipherSuitePolicy::default(),
+        );
+        let mut responder = build_responder_handshake_with_policy(
+            Arc::new(Mutex::new(TlsState::new())),
+            vec![suite("ChaCha20")],
+            CipherSuitePolicy::default(),
+        );
+
+        // Without the feature, neither side may fall back to AES-256-GCM.
+        let server = tokio::spawn(async move { responder.execute(&mut server_stream).await });
+        let client_result = initiator.execute(&mut client_stream).await;
+        match server.await.unwrap() {
+            Err(HandshakeError::Generic(msg)) => assert_eq!(msg, "No common cipher suite"),
+            other => panic!("Expected the responder to refuse ChaCha20, got {:?}", other),
+        }
+        assert!(client_result.is_err());
+        assert!(client_state.lock().await.export_session_key().is_empty());
+    }
+
+    #[test]
+    fn test_standard_handshake_step_order() {
+        let state = Arc::new(Mutex::new(TlsState::new()));
+        let handshake = build_initiator_handshake(state);
+        assert_eq!(handshake.protocol_id(), "TLS_HANDSHAKE");
+        assert_eq!(handshake.list_steps().len(), 4);
+    }
+}
@@ -0,0 +1,18 @@
+// security\data_encryption\src\aead_trait.rs
+use crate::EncryptionError;
+
+/// Authenticated encryption with associated data, with the nonce supplied per call.
+///
+/// Unlike `SymmetricEncryption`, which encrypts under the nonce an instance was
+/// created with, callers such as a record layer pass a fresh nonce for every message
+/// and bind unencrypted header bytes through `aad`.
+pub trait Aead: Send + Sync {
+  /// Nonce length in bytes that `seal` and `open` expect.
+  fn nonce_len(&self) -> usize;
+
+  /// Encrypts `plaintext` and returns the ciphertext followed by the tag.
+  fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+
+  /// Verifies and decrypts output of `seal` made with the same nonce and `aad`.
+  fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+}
@@ -1,18 +1,48 @@
 // ================================ Data Encryption Module =======================
 // security\data_encryption\src\encryption\aes_symmetric.rs
-use crate::{SymmetricEncryption, StreamEncryption};
+use crate::{Aead as NonceAead, EncryptionError, SymmetricEncryption, StreamEncryption};
 use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce,
+    aead::{
+        consts::{U12, U13, U14, U15, U16},
+        Aead, KeyInit, Payload,
+    },
+    aes::Aes256,
+    AesGcm, Nonce, TagSize,
 };
+use hmac::{Hmac, Mac};
+#[cfg(feature = "compression")]
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+use sha2::Sha256;
 use std::io::{Read, Write};
 use zeroize::Zeroize;
 
+/// Label the key-commitment tag is computed over.
+const KEY_COMMITMENT_LABEL: &[u8] = b"nautilus-aes256gcm-key-commitment";
+/// Length of the key-commitment tag (HMAC-SHA256 output).
+const KEY_COMMITMENT_LEN: usize = 32;
+/// Default GCM tag length in bytes.
+const DEFAULT_TAG_LEN: usize = 16;
+/// Plaintext bytes read per stream chunk.
+const STREAM_CHUNK_LEN: usize = 1024;
+/// Chunk header byte: the payload is stored as-is.
+#[cfg(feature = "compression")]
+const CHUNK_STORED: u8 = 0;
+/// Chunk header byte: the payload is deflated.
+#[cfg(feature = "compression")]
+const CHUNK_DEFLATED: u8 = 1;
+/// Deflate level used for compressed streams.
+#[cfg(feature = "compression")]
+const DEFLATE_LEVEL: u8 = 6;
+
 // ========================= Aes256GcmEncryption Struct =========================
 #[derive(Clone,Debug)]
 pub struct Aes256GcmEncryption {
     key: Vec<u8>,
     nonce: Vec<u8>,
+    key_commitment: bool,
+    tag_len: usize,
+    #[cfg(feature = "compression")]
+    compress: bool,
 }
 
 impl Drop for Aes256GcmEncryption {
@@ -33,7 +63,176 @@ impl Aes256GcmEncryption {
             return Err("Invalid nonce length: expected 12 bytes.".to_string());
         }
 
-        Ok(Self { key, nonce })
+        Ok(Self {
+            key,
+            nonce,
+            key_commitment: false,
+            tag_len: DEFAULT_TAG_LEN,
+            #[cfg(feature = "compression")]
+            compress: false,
+        })
+    }
+
+    /// Enables key commitment for `encrypt`/`decrypt` and the `*_with_key` variants.
+    ///
+    /// GCM alone is not key-committing: a crafted ciphertext can decrypt under two keys,
+    /// which enables partitioning-oracle attacks when several keys are tried. With this
+    /// enabled, ciphertexts are prefixed with `HMAC-SHA256(key, label)` and decryption
+    /// rejects any ciphertext whose tag does not match the key before running GCM.
+    /// Both sides must agree on the mode; stream encryption is unaffected.
+    pub fn with_key_commitment(mut self, enabled: bool) -> Self {
+        self.key_commitment = enabled;
+        self
+    }
+
+    /// Sets the GCM authentication tag length, from 12 to 16 bytes (default 16).
+    ///
+    /// Applies to every encrypt/decrypt path, including streams; both sides must use
+    /// the same length. Shorter tags save bandwidth but weaken integrity: a forgery
+    /// succeeds with probability about 2^-(8 * tag_len) per attempt, so only shorten
+    /// the tag when the protocol limits how many forgeries can be tried.
+    pub fn with_tag_length(mut self, tag_len: usize) -> Result<Self, String> {
+        if !(12..=16).contains(&tag_len) {
+            return Err(format!("Invalid tag length: expected 12 to 16 bytes, got {}", tag_len));
+        }
+        self.tag_len = tag_len;
+        Ok(self)
+    }
+
+    /// The configured GCM tag length in bytes.
+    pub fn tag_length(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Enables deflate compression of each chunk in `encrypt_stream`/`decrypt_stream`.
+    ///
+    /// Every chunk's plaintext then starts with a header byte saying whether the
+    /// payload was deflated or stored (when deflating would not shrink it); the header
+    /// is encrypted with the chunk, so it is authenticated. Both sides must agree on
+    /// the mode. Compressed sizes leak how much of a chunk repeats itself, so only
+    /// enable this for content an attacker cannot partially choose (CRIME/BREACH-style
+    /// oracles); secrets mixed with attacker input must not be compressed.
+    ///
+    /// Requires the `compression` feature.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compress = enabled;
+        self
+    }
+
+    /// Whether stream chunks are compressed before encryption.
+    #[cfg(feature = "compression")]
+    pub fn compression(&self) -> bool {
+        self.compress
+    }
+
+    /// Frames one plaintext chunk for encryption: as-is, or behind a header byte
+    /// when compression is enabled.
+    fn pack_chunk(&self, chunk: &[u8]) -> Vec<u8> {
+        #[cfg(feature = "compression")]
+        if self.compress {
+            return Self::deflate_chunk(chunk);
+        }
+        chunk.to_vec()
+    }
+
+    /// Deflates `chunk` behind a header byte, or stores it if that is not smaller.
+    #[cfg(feature = "compression")]
+    fn deflate_chunk(chunk: &[u8]) -> Vec<u8> {
+        let deflated = compress_to_vec(chunk, DEFLATE_LEVEL);
+        let (header, payload) = if deflated.len() < chunk.len() {
+            (CHUNK_DEFLATED, deflated.as_slice())
+        } else {
+            (CHUNK_STORED, chunk)
+        };
+        let mut packed = Vec::with_capacity(payload.len() + 1);
+        packed.push(header);
+        packed.extend_from_slice(payload);
+        packed
+    }
+
+    /// Reverses `pack_chunk` on a decrypted chunk.
+    fn unpack_chunk(&self, packed: Vec<u8>) -> Result<Vec<u8>, String> {
+        #[cfg(feature = "compression")]
+        if self.compress {
+            return Self::inflate_chunk(packed);
+        }
+        Ok(packed)
+    }
+
+    /// Reverses `deflate_chunk`.
+    #[cfg(feature = "compression")]
+    fn inflate_chunk(mut packed: Vec<u8>) -> Result<Vec<u8>, String> {
+        let Some((&header, payload)) = packed.split_first() else {
+            return Err("Empty stream chunk".to_string());
+        };
+        let chunk = match header {
+            CHUNK_STORED => payload.to_vec(),
+            // A chunk never inflates past the size it was read at.
+            CHUNK_DEFLATED => decompress_to_vec_with_limit(payload, STREAM_CHUNK_LEN)
+                .map_err(|e| format!("Failed to inflate stream chunk: {:?}", e.status))?,
+            other => return Err(format!("Unknown stream chunk header: {}", other)),
+        };
+        packed.zeroize();
+        Ok(chunk)
+    }
+
+    /// Runs AES-256-GCM with the configured tag length.
+    fn gcm(&self, key: &[u8], nonce: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+        self.gcm_with_aad(key, nonce, &[], data, encrypt)
+    }
+
+    /// Runs AES-256-GCM with the configured tag length over `data` and `aad`.
+    fn gcm_with_aad(&self, key: &[u8], nonce: &[u8], aad: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+        if nonce.len() != 12 {
+            return Err(format!("Invalid nonce length: expected 12 bytes, got {}", nonce.len()));
+        }
+        let payload = Payload { msg: data, aad };
+        match self.tag_len {
+            12 => gcm_with_tag::<U12>(key, nonce, payload, encrypt),
+            13 => gcm_with_tag::<U13>(key, nonce, payload, encrypt),
+            14 => gcm_with_tag::<U14>(key, nonce, payload, encrypt),
+            15 => gcm_with_tag::<U15>(key, nonce, payload, encrypt),
+            _ => gcm_with_tag::<U16>(key, nonce, payload, encrypt),
+        }
+    }
+
+    /// Computes the commitment tag for `key`.
+    fn commitment_tag(key: &[u8]) -> Result<Vec<u8>, String> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).map_err(|e| e.to_string())?;
+        mac.update(KEY_COMMITMENT_LABEL);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+
+    /// Prepends the commitment tag when commitment is enabled.
+    fn seal_committed(&self, key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let ciphertext = self.gcm(key, &self.nonce, plaintext, true)?;
+        if !self.key_commitment {
+            return Ok(ciphertext);
+        }
+
+        let mut committed = Self::commitment_tag(key)?;
+        committed.extend_from_slice(&ciphertext);
+        Ok(committed)
+    }
+
+    /// Checks the commitment tag (when enabled) before decrypting.
+    fn open_committed(&self, key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+        let ciphertext = if self.key_commitment {
+            if ciphertext.len() < KEY_COMMITMENT_LEN {
+                return Err("Ciphertext too short for key commitment".to_string());
+            }
+            let (tag, rest) = ciphertext.split_at(KEY_COMMITMENT_LEN);
+            let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).map_err(|e| e.to_string())?;
+            mac.update(KEY_COMMITMENT_LABEL);
+            mac.verify_slice(tag)
+                .map_err(|_| "Key commitment mismatch".to_string())?;
+            rest
+        } else {
+            ciphertext
+        };
+
+        self.gcm(key, &self.nonce, ciphertext, false)
     }
 
     fn increment_nonce(nonce: &mut [u8; 12]) {
@@ -46,20 +245,46 @@ impl Aes256GcmEncryption {
     }
 }
 
+/// One AES-256-GCM operation with a `T`-byte tag.
+fn gcm_with_tag<T: TagSize>(key: &[u8], nonce: &[u8], data: Payload, encrypt: bool) -> Result<Vec<u8>, String> {
+    let cipher = AesGcm::<Aes256, U12, T>::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce = Nonce::from_slice(nonce);
+    if encrypt {
+        cipher.encrypt(nonce, data).map_err(|e| e.to_string())
+    } else {
+        cipher.decrypt(nonce, data).map_err(|e| e.to_string())
+    }
+}
+
 // ========================= SymmetricEncryption Trait =========================
 impl SymmetricEncryption for Aes256GcmEncryption {
     type Error = String;
 
     fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Self::Error> {
-        let nonce = Nonce::from_slice(&self.nonce);
-        let cipher = Aes256Gcm::new_from_slice(&self.key).map_err(|e| e.to_string())?;
-        cipher.encrypt(nonce, plaintext).map_err(|e| e.to_string())
+        self.seal_committed(&self.key, plaintext)
     }
 
     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Self::Error> {
-        let nonce = Nonce::from_slice(&self.nonce);
-        let cipher = Aes256Gcm::new_from_slice(&self.key).map_err(|e| e.to_string())?;
-        cipher.decrypt(nonce, ciphertext).map_err(|e| e.to_string())
+        self.open_committed(&self.key, ciphertext)
+    }
+}
+
+// ========================= Aead Trait =========================
+/// Uses the instance key and tag length; the nonce given at construction and key
+/// commitment do not apply.
+impl NonceAead for Aes256GcmEncryption {
+    fn nonce_len(&self) -> usize {
+        12
+    }
+
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        self.gcm_with_aad(&self.key, nonce, aad, plaintext, true)
+            .map_err(EncryptionError::EncryptionFailed)
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        self.gcm_with_aad(&self.key, nonce, aad, ciphertext, false)
+            .map_err(EncryptionError::DecryptionFailed)
     }
 }
 
@@ -78,11 +303,10 @@ impl StreamEncryption for Aes256GcmEncryption {
         // Convert the nonce slice to a [u8; 12] so we can increment it
         let mut nonce_array = *<&[u8; 12]>::try_from(nonce)
             .map_err(|_| "Invalid nonce length (must be 12 bytes)".to_string())?;
-        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
 
-        let mut buffer = vec![0u8; 1024];
+        let mut buffer = vec![0u8; STREAM_CHUNK_LEN];
         loop {
-            // 1) Read up to 1024 bytes from plaintext
+            // 1) Read up to STREAM_CHUNK_LEN bytes from plaintext
             let bytes_read = input.read(&mut buffer).map_err(|e| e.to_string())?;
             if bytes_read == 0 {
                 // Reached EOF. Write a 0-length prefix to signal "done".
@@ -92,10 +316,10 @@ impl StreamEncryption for Aes256GcmEncryption {
                 break;
             }
 
-            // 2) Encrypt this chunk with the current nonce
-            let encrypted_chunk = cipher
-                .encrypt(Nonce::from_slice(&nonce_array), &buffer[..bytes_read])
-                .map_err(|e| e.to_string())?;
+            // 2) Compress if enabled, then encrypt this chunk with the current nonce
+            let mut packed = self.pack_chunk(&buffer[..bytes_read]);
+            let encrypted_chunk = self.gcm(key, &nonce_array, &packed, true)?;
+            packed.zeroize();
 
             // 3) Write the length prefix, then the ciphertext
             let chunk_len = encrypted_chunk.len() as u32;
@@ -124,7 +348,6 @@ impl StreamEncryption for Aes256GcmEncryption {
     ) -> Result<(), Self::Error> {
         let mut nonce_array = *<&[u8; 12]>::try_from(nonce)
             .map_err(|_| "Invalid nonce length (must be 12 bytes)".to_string())?;
-        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
 
         loop {
             // 1) Read the 4-byte length prefix
@@ -147,10 +370,8 @@ impl StreamEncryption for Aes256GcmEncryption {
             let mut enc_buf = vec![0u8; chunk_len as usize];
             input.read_exact(&mut enc_buf).map_err(|e| e.to_string())?;
 
-            // 3) Decrypt with the current nonce
-            let decrypted_chunk = cipher
-            .decrypt(Nonce::from_slice(&nonce_array), &enc_buf[..])
-                .map_err(|e| e.to_string())?;
+            // 3) Decrypt with the current nonce, inflating if compression is enabled
+            let decrypted_chunk = self.unpack_chunk(self.gcm(key, &nonce_array, &enc_buf, false)?)?;
 
             // 4) Write the decrypted plaintext
             output.write_all(&decrypted_chunk).map_err(|e| e.to_string())?;
@@ -168,16 +389,12 @@ impl Aes256GcmEncryption {
     // Encrypt the given plaintext using the provided session key
     pub fn encrypt_with_key(&self, plaintext: &[u8], session_key: &[u8]) -> Result<Vec<u8>, String> {
         // Use the provided session key for encryption
-        let cipher = Aes256Gcm::new_from_slice(session_key).map_err(|e| e.to_string())?;
-        let nonce = Nonce::from_slice(&self.nonce);
-        cipher.encrypt(nonce, plaintext).map_err(|e| e.to_string())
+        self.seal_committed(session_key, plaintext)
     }
 
     // Decrypt the given ciphertext using the provided session key
     pub fn decrypt_with_key(&self, ciphertext: &[u8], session_key: &[u8]) -> Result<Vec<u8>, String> {
         // Use the provided session key for decryption
-        let cipher = Aes256Gcm::new_from_slice(session_key).map_err(|e| e.to_string())?;
-        let nonce = Nonce::from_slice(&self.nonce);
-        cipher.decrypt(nonce, ciphertext).map_err(|e| e.to_string())
+        self.open_committed(session_key, ciphertext)
     }
 }
\ No newline at end of file
@@ -2,11 +2,11 @@
 // security\data_encryption\src\encryption\chacha20_symmetric.rs
 #[cfg(feature = "chacha20")]
 use chacha20poly1305::{
-    aead::{Aead, KeyInit},
+    aead::{Aead, KeyInit, Payload},
     ChaCha20Poly1305,
 };
 #[cfg(feature = "chacha20")]
-use crate::{SymmetricEncryption, StreamEncryption};
+use crate::{Aead as NonceAead, EncryptionError, SymmetricEncryption, StreamEncryption};
 #[cfg(feature = "chacha20")]
 use std::io::{Read, Write};
 
@@ -59,6 +59,37 @@ impl SymmetricEncryption for ChaCha20Encryption {
     }
 }
 
+// ========================= Aead Trait =========================
+/// Uses the instance key; the nonce given at construction does not apply.
+#[cfg(feature = "chacha20")]
+impl NonceAead for ChaCha20Encryption {
+    fn nonce_len(&self) -> usize {
+        12
+    }
+
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if nonce.len() != 12 {
+            return Err(EncryptionError::EncryptionFailed("Invalid nonce length".to_string()));
+        }
+        let cipher = ChaCha20Poly1305::new_from_slice(&self.key)
+            .map_err(|e| EncryptionError::InvalidKey(e.to_string()))?;
+        cipher
+            .encrypt(chacha20poly1305::Nonce::from_slice(nonce), Payload { msg: plaintext, aad })
+            .map_err(|e| EncryptionError::EncryptionFailed(e.to_string()))
+    }
+
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if nonce.len() != 12 {
+            return Err(EncryptionError::DecryptionFailed("Invalid nonce length".to_string()));
+        }
+        let cipher = ChaCha20Poly1305::new_from_slice(&self.key)
+            .map_err(|e| EncryptionError::InvalidKey(e.to_string()))?;
+        cipher
+            .decrypt(chacha20poly1305::Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+            .map_err(|e| EncryptionError::DecryptionFailed(e.to_string()))
+    }
+}
+
 // ========================= StreamEncryption Trait =========================
 #[cfg(feature = "chacha20")]
 impl StreamEncryption for ChaCha20Encryption {
@@ -34,38 +34,11 @@ impl KeyDerivation for Argon2KeyDerivation {
         password: &[u8],
         output_length: usize,
     ) -> Result<Vec<u8>, Self::Error> {
-        if output_length > 1024 * 1024 {
-            return Err("Output length exceeds maximum allowed size (1MB).".to_string());
-        }
-
-        let argon2 = Argon2::new(
-            argon2::Algorithm::Argon2id,
-            Version::V0x13,
-            Params::new(
-                self.memory_size_kb,
-                self.iterations,
-                self.parallelism,
-                Some(output_length),
-            )
-            .map_err(|e| format!("Argon2 parameter setup failed: {}", e))?,
-        );
-
         // Generate a secure random salt
         let mut salt = generate_secure_salt(16);
-
-        // Initialize the output buffer for the derived key
-        let mut derived_key = vec![0u8; output_length];
-
-        // Perform the key derivation
-        let result = argon2.hash_password_into(password, &salt, &mut derived_key);
+        let result = self.derive_key_with_salt(password, &salt, output_length);
         salt.zeroize(); // Zeroize salt after use
-
-        if let Err(err) = result {
-            derived_key.zeroize(); // Zero out the derived key on failure
-            return Err(format!("Argon2 key derivation failed: {}", err));
-        }
-
-        Ok(derived_key)
+        result
     }
 }
 
@@ -89,6 +62,44 @@ impl Argon2KeyDerivation {
             parallelism,
         })
     }
+
+    /// Derives a key from `password` and a caller-supplied `salt`.
+    ///
+    /// Unlike [`KeyDerivation::derive_key`], the result is reproducible: the same
+    /// password, salt and parameters always yield the same key.
+    pub fn derive_key_with_salt(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        output_length: usize,
+    ) -> Result<Vec<u8>, String> {
+        if output_length > 1024 * 1024 {
+            return Err("Output length exceeds maximum allowed size (1MB).".to_string());
+        }
+
+        let argon2 = Argon2::new(
+            argon2::Algorithm::Argon2id,
+            Version::V0x13,
+            Params::new(
+                self.memory_size_kb,
+                self.iterations,
+                self.parallelism,
+                Some(output_length),
+            )
+            .map_err(|e| format!("Argon2 parameter setup failed: {}", e))?,
+        );
+
+        // Initialize the output buffer for the derived key
+        let mut derived_key = vec![0u8; output_length];
+
+        // Perform the key derivation
+        if let Err(err) = argon2.hash_password_into(password, salt, &mut derived_key) {
+            derived_key.zeroize(); // Zero out the derived key on failure
+            return Err(format!("Argon2 key derivation failed: {}", err));
+        }
+
+        Ok(derived_key)
+    }
 }
 
 // ============================================================================
@@ -10,4 +10,8 @@ pub use argon2_key_derive::Argon2KeyDerivation;
 #[cfg(feature = "scrypt_derive")]
 mod scrypt_key_derive;
 #[cfg(feature = "scrypt_derive")]
-pub use scrypt_key_derive::Scrypt;
\ No newline at end of file
+pub use scrypt_key_derive::Scrypt;
+#[cfg(any(feature = "argon", feature = "scrypt_derive"))]
+mod passphrase_key_derive;
+#[cfg(any(feature = "argon", feature = "scrypt_derive"))]
+pub use passphrase_key_derive::{derive_key_from_passphrase, DerivedKey, PassphraseKdf, PASSPHRASE_SALT_LEN};
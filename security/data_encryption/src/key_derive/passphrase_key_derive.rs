@@ -0,0 +1,120 @@
+// ================================ Data Encryption Module =======================
+// security\data_encryption\src\key_derive\passphrase_key_derive.rs
+#[cfg(feature = "argon")]
+use crate::Argon2KeyDerivation;
+#[cfg(feature = "scrypt_derive")]
+use crate::Scrypt;
+use crate::utils::generate_secure_salt;
+use zeroize::Zeroize;
+
+/// Length of the salt generated by [`PassphraseKdf::new_salt`].
+pub const PASSPHRASE_SALT_LEN: usize = 16;
+
+// ========================= PassphraseKdf Enum =========================
+/// Password hashing function and cost parameters used to turn a passphrase into
+/// a 32-byte key. Store these next to the salt so the key can be re-derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassphraseKdf {
+    /// Argon2id with memory cost in KiB, iteration count and lane count.
+    #[cfg(feature = "argon")]
+    Argon2id {
+        memory_size_kb: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+    /// scrypt with `N = 2^log_n`, block size `r` and parallelism `p`.
+    #[cfg(feature = "scrypt_derive")]
+    Scrypt { log_n: u8, r: u32, p: u32 },
+}
+
+impl PassphraseKdf {
+    /// Generates a fresh random salt suitable for [`derive_key_from_passphrase`].
+    pub fn new_salt() -> Vec<u8> {
+        generate_secure_salt(PASSPHRASE_SALT_LEN)
+    }
+}
+
+impl Default for PassphraseKdf {
+    /// Argon2id with 64 MiB, 3 passes and 1 lane when available, otherwise
+    /// scrypt with `N = 2^15, r = 8, p = 1`.
+    fn default() -> Self {
+        #[cfg(feature = "argon")]
+        {
+            PassphraseKdf::Argon2id {
+                memory_size_kb: 64 * 1024,
+                iterations: 3,
+                parallelism: 1,
+            }
+        }
+        #[cfg(not(feature = "argon"))]
+        {
+            PassphraseKdf::Scrypt { log_n: 15, r: 8, p: 1 }
+        }
+    }
+}
+
+// ========================= DerivedKey Struct =========================
+/// A 32-byte key derived from a passphrase, together with the salt it was
+/// derived with. The key is zeroized on drop.
+pub struct DerivedKey {
+    key: [u8; 32],
+    salt: Vec<u8>,
+}
+
+impl DerivedKey {
+    /// The derived key, sized for `Aes256GcmEncryption`.
+    pub fn key(&self) -> &[u8; 32] {
+        &self.key
+    }
+
+    /// The salt used for the derivation; store it to re-derive the key later.
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+}
+
+impl Drop for DerivedKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+// ========================= Passphrase Derivation =========================
+/// Derives a 32-byte key from `passphrase` and `salt` with the function chosen
+/// by `params`. The same inputs always produce the same key.
+pub fn derive_key_from_passphrase(
+    passphrase: &str,
+    salt: &[u8],
+    params: &PassphraseKdf,
+) -> Result<DerivedKey, String> {
+    if salt.len() < 8 {
+        return Err("Salt must be at least 8 bytes long.".to_string());
+    }
+
+    let mut derived = match *params {
+        #[cfg(feature = "argon")]
+        PassphraseKdf::Argon2id {
+            memory_size_kb,
+            iterations,
+            parallelism,
+        } => Argon2KeyDerivation::new(memory_size_kb, iterations, parallelism)?
+            .derive_key_with_salt(passphrase.as_bytes(), salt, 32)?,
+        #[cfg(feature = "scrypt_derive")]
+        PassphraseKdf::Scrypt { log_n, r, p } => {
+            let params = scrypt::Params::new(log_n, r, p, 32)
+                .map_err(|e| format!("Scrypt parameter setup failed: {}", e))?;
+            Scrypt { params }.derive_key_with_salt(passphrase.as_bytes(), salt, 32)?
+        }
+    };
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&derived);
+    derived.zeroize();
+
+    Ok(DerivedKey {
+        key,
+        salt: salt.to_vec(),
+    })
+}
+
+// ============================================================================
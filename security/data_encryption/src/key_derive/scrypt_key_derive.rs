@@ -21,11 +21,26 @@ impl KeyDerivation for Scrypt {
         password: &[u8],
         output_length: usize,
     ) -> Result<Vec<u8>, Self::Error> {
+        let salt = &generate_secure_salt(16);
+        self.derive_key_with_salt(password, salt, output_length)
+    }
+}
+
+// ========================= Scrypt Implementation =========================
+#[cfg(feature = "scrypt_derive")]
+impl Scrypt {
+    /// Derives a key from `password` and a caller-supplied `salt`, so the same
+    /// inputs can be used again later to recover the key.
+    pub fn derive_key_with_salt(
+        &self,
+        password: &[u8],
+        salt: &[u8],
+        output_length: usize,
+    ) -> Result<Vec<u8>, String> {
         if output_length > 1024 * 1024 {
             return Err("Output length exceeds the maximum allowed size (1MB).".to_string());
         }
 
-        let salt = &generate_secure_salt(16);
         let mut key = vec![0u8; output_length];
         scrypt(password, salt, &self.params, &mut key).map_err(|e| e.to_string())?;
         Ok(key)
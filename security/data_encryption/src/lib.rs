@@ -11,6 +11,9 @@ pub use key_derivation_trait::KeyDerivation;
 
 mod stream_encryption_trait;
 pub use stream_encryption_trait::StreamEncryption;
+
+mod aead_trait;
+pub use aead_trait::Aead;
 // ================================================= Encryption Public API Interface =========================================
 mod encryption;
 pub use encryption::*;
@@ -88,6 +88,61 @@ mod tests {
   }
   
 
+  #[test]
+  fn test_key_commitment_rejects_other_key() {
+      let key_a = vec![0xAA; 32];
+      let key_b = vec![0xBB; 32];
+      let aes_a = Aes256GcmEncryption::new(key_a, NONCE.to_vec())
+          .unwrap()
+          .with_key_commitment(true);
+      let aes_b = Aes256GcmEncryption::new(key_b, NONCE.to_vec())
+          .unwrap()
+          .with_key_commitment(true);
+
+      let ciphertext = aes_a.encrypt(b"committed to key A").expect("Encryption failed");
+      assert_eq!(aes_a.decrypt(&ciphertext).unwrap(), b"committed to key A");
+
+      // Key B is rejected by the commitment check itself, before GCM ever runs.
+      let err = aes_b.decrypt(&ciphertext).expect_err("Key B must not open key A's ciphertext");
+      assert_eq!(err, "Key commitment mismatch");
+
+      // Swap in key B's commitment block: the GCM part still verifies under key A,
+      // so only the commitment check can reject it.
+      const COMMITMENT_LEN: usize = 32;
+      let other = aes_b.encrypt(b"committed to key B").expect("Encryption failed");
+      let mut spliced = other[..COMMITMENT_LEN].to_vec();
+      spliced.extend_from_slice(&ciphertext[COMMITMENT_LEN..]);
+      let uncommitted_a = Aes256GcmEncryption::new(vec![0xAA; 32], NONCE.to_vec()).unwrap();
+      assert_eq!(uncommitted_a.decrypt(&spliced[COMMITMENT_LEN..]).unwrap(), b"committed to key A");
+      let err = aes_a.decrypt(&spliced).expect_err("A foreign commitment block must be rejected");
+      assert_eq!(err, "Key commitment mismatch");
+  }
+
+  #[test]
+  fn test_short_tag_length() {
+      let plaintext = b"twelve byte tag";
+      let short = setup_aes().with_tag_length(12).expect("12 is a valid tag length");
+      let full = setup_aes();
+      assert_eq!(full.tag_length(), 16);
+
+      let ciphertext = short.encrypt(plaintext).expect("Encryption failed");
+      assert_eq!(ciphertext.len(), plaintext.len() + 12);
+      assert_eq!(short.decrypt(&ciphertext).unwrap(), plaintext);
+      assert!(full.decrypt(&ciphertext).is_err());
+
+      let mut encrypted_output = Vec::new();
+      short.encrypt_stream(Cursor::new(plaintext.to_vec()), &mut encrypted_output, &KEY, &NONCE)
+          .expect("Encryption failed");
+      let mut decrypted_output = Vec::new();
+      short.decrypt_stream(Cursor::new(encrypted_output.clone()), &mut decrypted_output, &KEY, &NONCE)
+          .expect("Decryption failed");
+      assert_eq!(decrypted_output, plaintext);
+      assert!(full.decrypt_stream(Cursor::new(encrypted_output), Vec::new(), &KEY, &NONCE).is_err());
+
+      assert!(setup_aes().with_tag_length(11).is_err());
+      assert!(setup_aes().with_tag_length(17).is_err());
+  }
+
   #[test]
   fn test_encrypt_empty_data() {
       let key = KEY.to_vec();
@@ -201,4 +256,36 @@ mod tests {
 
       assert!(decrypted_output.is_empty(), "Decrypted output should be empty");
   }
+
+  #[cfg(feature = "compression")]
+  #[test]
+  fn test_compressed_stream_round_trip() {
+      let plaintext = b"2026-10-15 INFO request served in 3ms\n".repeat(200);
+      let plain = setup_aes();
+      let compressed = setup_aes().with_compression(true);
+      assert!(compressed.compression());
+
+      let mut uncompressed_output = Vec::new();
+      plain.encrypt_stream(Cursor::new(plaintext.clone()), &mut uncompressed_output, &KEY, &NONCE)
+          .expect("Encryption failed");
+      let mut compressed_output = Vec::new();
+      compressed.encrypt_stream(Cursor::new(plaintext.clone()), &mut compressed_output, &KEY, &NONCE)
+          .expect("Encryption failed");
+      assert!(compressed_output.len() < uncompressed_output.len() / 4);
+
+      let mut decrypted_output = Vec::new();
+      compressed.decrypt_stream(Cursor::new(compressed_output), &mut decrypted_output, &KEY, &NONCE)
+          .expect("Decryption failed");
+      assert_eq!(decrypted_output, plaintext);
+
+      // Incompressible chunks are stored behind the header byte and still round trip.
+      let random = data_encryption::utils::generate_random_key(3000);
+      let mut encrypted_output = Vec::new();
+      compressed.encrypt_stream(Cursor::new(random.clone()), &mut encrypted_output, &KEY, &NONCE)
+          .expect("Encryption failed");
+      let mut decrypted_output = Vec::new();
+      compressed.decrypt_stream(Cursor::new(encrypted_output), &mut decrypted_output, &KEY, &NONCE)
+          .expect("Decryption failed");
+      assert_eq!(decrypted_output, random);
+  }
 }
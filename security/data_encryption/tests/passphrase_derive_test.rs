@@ -0,0 +1,54 @@
+#[cfg(test)]
+#[cfg(feature = "argon")]
+mod tests {
+    use data_encryption::{derive_key_from_passphrase, PassphraseKdf};
+
+    fn params() -> PassphraseKdf {
+        PassphraseKdf::Argon2id {
+            memory_size_kb: 4096,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn test_same_passphrase_and_salt_yield_same_key() {
+        let salt = PassphraseKdf::new_salt();
+        let first = derive_key_from_passphrase("correct horse", &salt, &params()).expect("Key derivation failed");
+        let second = derive_key_from_passphrase("correct horse", &salt, &params()).expect("Key derivation failed");
+
+        assert_eq!(first.key(), second.key());
+        assert_eq!(first.salt(), salt.as_slice());
+    }
+
+    #[test]
+    fn test_different_salts_yield_different_keys() {
+        let first = derive_key_from_passphrase("correct horse", &[1u8; 16], &params()).expect("Key derivation failed");
+        let second = derive_key_from_passphrase("correct horse", &[2u8; 16], &params()).expect("Key derivation failed");
+
+        assert_ne!(first.key(), second.key());
+    }
+
+    #[test]
+    fn test_short_salt_is_rejected() {
+        assert!(derive_key_from_passphrase("correct horse", &[0u8; 4], &params()).is_err());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "scrypt_derive")]
+mod scrypt_tests {
+    use data_encryption::{derive_key_from_passphrase, PassphraseKdf};
+
+    #[test]
+    fn test_scrypt_passphrase_is_deterministic() {
+        let params = PassphraseKdf::Scrypt { log_n: 10, r: 8, p: 1 };
+        let salt = PassphraseKdf::new_salt();
+        let first = derive_key_from_passphrase("correct horse", &salt, &params).expect("Key derivation failed");
+        let second = derive_key_from_passphrase("correct horse", &salt, &params).expect("Key derivation failed");
+        assert_eq!(first.key(), second.key());
+
+        let other = derive_key_from_passphrase("correct horse", &[9u8; 16], &params).expect("Key derivation failed");
+        assert_ne!(first.key(), other.key());
+    }
+}
@@ -174,7 +174,7 @@ impl TcpConnection {
                 let mut st = state.lock().map_err(|_| {
                     ConnectionError::Generic("StdMutex Poisoned".into())
                 })?;
-                st.set_handshake_complete(true);
+                st.mark_handshake_complete();
             }
 
             // handshake done
@@ -188,17 +188,17 @@ impl TcpConnection {
 
     /// Send data over TLS
     pub async fn tls_send(&self, data: &[u8], state: Arc<StdMutex<TlsState>>) -> Result<(), ConnectionError> {
-        let session_key = {
+        let aead = {
             let st = state.lock().map_err(|_| ConnectionError::SendFailed("Mutex Poisoned".into()))?;
-            st.session_key().to_vec()
+            st.record_aead().map_err(|e| ConnectionError::SendFailed(e.to_string()))?
         };
         let mut guard = self.stream.lock().await;
         if let Some(ref mut stream) = *guard {
             let mut record = TlsRecord::new(RecordType::ApplicationData, data.to_vec());
-            record.encrypt(&session_key).map_err(|e| ConnectionError::SendFailed(e.to_string()))?;
+            record.seal(aead.as_ref()).map_err(|e| ConnectionError::SendFailed(e.to_string()))?;
 
-            stream
-                .write_all(&record.serialize())
+            record
+                .write_to(stream)
                 .await
                 .map_err(|e| ConnectionError::SendFailed(e.to_string()))?;
 
@@ -212,23 +212,18 @@ impl TcpConnection {
 
     /// Receive data over TLS
     pub async fn tls_receive(&self, state: Arc<StdMutex<TlsState>>) -> Result<Vec<u8>, ConnectionError> {
-        let session_key = {
+        let aead = {
             let st = state.lock().map_err(|_| ConnectionError::ReceiveFailed("Mutex Poisoned".into()))?;
-            st.session_key().to_vec()
+            st.record_aead().map_err(|e| ConnectionError::ReceiveFailed(e.to_string()))?
         };
 
         let mut guard = self.stream.lock().await;
         if let Some(ref mut stream) = *guard {
-            let mut buf = vec![0u8; 4096];
-            let n = stream
-                .read(&mut buf)
+            let mut record = TlsRecord::read_from(stream)
                 .await
                 .map_err(|e| ConnectionError::ReceiveFailed(e.to_string()))?;
-
-            let mut record = TlsRecord::deserialize(&buf[..n])
-                .map_err(|e| ConnectionError::ReceiveFailed(e.to_string()))?;
             let payload = record
-                .decrypt(&session_key)
+                .open(aead.as_ref())
                 .map_err(|e| ConnectionError::ReceiveFailed(e.to_string()))?;
             Ok(payload)
         } else {
@@ -0,0 +1,120 @@
+// utilities\registry\benches\concurrency_benchmark.rs
+/// Purpose : Compare the sharded `InMemoryRegistry` against a single-lock map
+/// under concurrent readers and writers.
+use criterion::{criterion_group, criterion_main, Criterion};
+use registry::{InMemoryRegistry, Record, Registry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tokio::runtime::Runtime;
+
+const TASKS: usize = 8;
+const OPS_PER_TASK: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BenchRecord {
+    id: String,
+    port: u16,
+}
+
+impl Record for BenchRecord {
+    fn identifier(&self) -> String {
+        self.id.clone()
+    }
+
+    fn expires_at(&self) -> Option<SystemTime> {
+        None
+    }
+}
+
+/// Baseline mirroring the registry before sharding: one lock guards both the map and
+/// the expiration heap, and every read first takes the write lock to purge expired entries.
+type SingleLockInner = (HashMap<String, BenchRecord>, BinaryHeap<String>);
+
+#[derive(Clone, Default)]
+struct SingleLockMap {
+    inner: Arc<RwLock<SingleLockInner>>,
+}
+
+impl SingleLockMap {
+    fn add(&self, record: BenchRecord) {
+        let mut guard = self.inner.write().unwrap();
+        guard.1.push(record.id.clone());
+        guard.0.insert(record.id.clone(), record);
+    }
+
+    fn get(&self, id: &str) -> Option<BenchRecord> {
+        {
+            let guard = self.inner.write().unwrap();
+            let _ = guard.1.peek();
+        }
+        self.inner.read().unwrap().0.get(id).cloned()
+    }
+}
+
+fn record(task: usize, op: usize) -> BenchRecord {
+    BenchRecord {
+        id: format!("service-{}-{}", task, op),
+        port: op as u16,
+    }
+}
+
+async fn run_sharded(registry: InMemoryRegistry<BenchRecord>) {
+    let mut handles = Vec::with_capacity(TASKS);
+    for task in 0..TASKS {
+        let registry = registry.clone();
+        handles.push(tokio::spawn(async move {
+            for op in 0..OPS_PER_TASK {
+                if task % 2 == 0 {
+                    registry.add(record(task, op)).await.unwrap();
+                } else {
+                    let _ = registry.get(&format!("service-{}-{}", task - 1, op)).await;
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+async fn run_single_lock(map: SingleLockMap) {
+    let mut handles = Vec::with_capacity(TASKS);
+    for task in 0..TASKS {
+        let map = map.clone();
+        handles.push(tokio::spawn(async move {
+            for op in 0..OPS_PER_TASK {
+                if task % 2 == 0 {
+                    map.add(record(task, op));
+                } else {
+                    let _ = map.get(&format!("service-{}-{}", task - 1, op));
+                }
+            }
+        }));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+}
+
+fn concurrency_benchmarks(c: &mut Criterion) {
+    let rt = Runtime::new().expect("Failed to build Tokio runtime");
+    let capacity = TASKS * OPS_PER_TASK;
+
+    c.bench_function("sharded_registry_mixed_read_write", |b| {
+        b.iter(|| {
+            rt.block_on(async {
+                let registry = InMemoryRegistry::new(capacity);
+                run_sharded(registry).await;
+            })
+        })
+    });
+
+    c.bench_function("single_lock_mixed_read_write", |b| {
+        b.iter(|| rt.block_on(run_single_lock(SingleLockMap::default())))
+    });
+}
+
+criterion_group!(benches, concurrency_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,181 @@
+// utilities\registry\benches\registry_benchmark.rs
+/// Purpose : Compare `InMemoryRegistry` with `RedisRegistry` for add, get, list and
+/// remove at several registry sizes, one record at a time and in bulk. Results are
+/// appended to `benches/registry_benchmark.csv` at the repository root.
+///
+/// The Redis backend needs the `redis_registry` feature and a server at `REDIS_URL`
+/// (default `redis://127.0.0.1:6379`); without either, only the in-memory rows are
+/// written. The benchmark writes `record:bench-*` keys and removes them afterwards.
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::future::join_all;
+use registry::{InMemoryRegistry, Record, RecordStore};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::runtime::Runtime;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+const CSV_FILE: &str = "registry_benchmark.csv";
+const CSV_HEADERS: &str = "Backend,Records,Operation,Total_ms,Mean_us_per_op,OpsPerSec";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BenchRecord {
+    id: String,
+    service_type: String,
+    port: u16,
+}
+
+impl Record for BenchRecord {
+    fn identifier(&self) -> String {
+        self.id.clone()
+    }
+
+    fn expires_at(&self) -> Option<SystemTime> {
+        None
+    }
+}
+
+fn record(index: usize) -> BenchRecord {
+    BenchRecord {
+        id: format!("bench-{}", index),
+        service_type: "_bench._tcp.local.".to_string(),
+        port: index as u16,
+    }
+}
+
+/// Get benchmark output directory as "Nautilus/benches"
+fn get_benchmark_path() -> PathBuf {
+    let mut path = env::current_dir().expect("Failed to get current directory");
+    path.pop(); // Go from 'registry' to 'utilities'
+    path.pop(); // Go from 'utilities' to 'Nautilus'
+    path.push("benches");
+    path
+}
+
+/// Writes the CSV headers unless the file already has content.
+fn ensure_headers(file_name: &str, headers: &str) {
+    let file_path = get_benchmark_path().join(file_name);
+    let has_content = OpenOptions::new()
+        .read(true)
+        .open(&file_path)
+        .map(|file| BufReader::new(file).lines().next().is_some())
+        .unwrap_or(false);
+    if !has_content {
+        append_to_csv(file_name, headers);
+    }
+}
+
+/// Append benchmark results to CSV
+fn append_to_csv(file_name: &str, content: &str) {
+    let file_path = get_benchmark_path().join(file_name);
+    let mut file = OpenOptions::new().create(true).append(true).open(file_path).expect("Failed to open CSV file");
+    writeln!(file, "{}", content).expect("Failed to write to CSV");
+}
+
+/// Records one measurement of `ops` operations that took `elapsed` in total.
+fn report(backend: &str, records: usize, operation: &str, ops: usize, elapsed: Duration) {
+    let total_ms = elapsed.as_secs_f64() * 1_000.0;
+    let mean_us = elapsed.as_secs_f64() * 1_000_000.0 / ops as f64;
+    let ops_per_sec = ops as f64 / elapsed.as_secs_f64();
+    append_to_csv(
+        CSV_FILE,
+        &format!("{},{},{},{:.3},{:.3},{:.2}", backend, records, operation, total_ms, mean_us, ops_per_sec),
+    );
+    println!("{:<10} {:>6} {:<11} {:>12.3} us/op", backend, records, operation, mean_us);
+}
+
+/// Measures every operation against `store` at one size, leaving it empty again.
+async fn benchmark_store(backend: &str, store: Arc<dyn RecordStore<BenchRecord>>, records: usize) {
+    // One record at a time.
+    let start = Instant::now();
+    for index in 0..records {
+        store.add(record(index)).await.expect("add failed");
+    }
+    report(backend, records, "add", records, start.elapsed());
+
+    let start = Instant::now();
+    for index in 0..records {
+        store.get(&format!("bench-{}", index)).await.expect("get failed");
+    }
+    report(backend, records, "get", records, start.elapsed());
+
+    let start = Instant::now();
+    let listed = store.list().await.expect("list failed");
+    report(backend, records, "list", 1, start.elapsed());
+    assert!(listed.len() >= records, "{} lost records", backend);
+
+    let start = Instant::now();
+    for index in 0..records {
+        store.remove(&format!("bench-{}", index)).await.expect("remove failed");
+    }
+    report(backend, records, "remove", records, start.elapsed());
+
+    // Bulk: every operation of a kind in flight at once.
+    let start = Instant::now();
+    for result in join_all((0..records).map(|index| store.add(record(index)))).await {
+        result.expect("bulk add failed");
+    }
+    report(backend, records, "bulk_add", records, start.elapsed());
+
+    let ids: Vec<String> = (0..records).map(|index| format!("bench-{}", index)).collect();
+    let start = Instant::now();
+    for result in join_all(ids.iter().map(|id| store.get(id))).await {
+        result.expect("bulk get failed");
+    }
+    report(backend, records, "bulk_get", records, start.elapsed());
+
+    let start = Instant::now();
+    for result in join_all(ids.iter().map(|id| store.remove(id))).await {
+        result.expect("bulk remove failed");
+    }
+    report(backend, records, "bulk_remove", records, start.elapsed());
+}
+
+#[cfg(feature = "redis_registry")]
+async fn redis_store(capacity: usize) -> Option<Arc<dyn RecordStore<BenchRecord>>> {
+    let url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    match registry::RedisRegistry::<BenchRecord>::new(&url, capacity, "bench:expirations").await {
+        Ok(store) => Some(Arc::new(store)),
+        Err(e) => {
+            println!("Skipping Redis registry benchmark: {}", e);
+            None
+        }
+    }
+}
+
+/// Criterion benchmark function running every backend at every size
+fn registry_benchmarks(_c: &mut Criterion) {
+    let rt = Runtime::new().expect("Failed to build Tokio runtime");
+    ensure_headers(CSV_FILE, CSV_HEADERS);
+
+    for records in SIZES {
+        rt.block_on(async {
+            let in_memory: Arc<dyn RecordStore<BenchRecord>> = Arc::new(InMemoryRegistry::new(records));
+            benchmark_store("InMemory", in_memory, records).await;
+
+            #[cfg(feature = "redis_registry")]
+            if let Some(redis) = redis_store(records).await {
+                benchmark_store("Redis", redis, records).await;
+            }
+        });
+    }
+}
+
+/// Criterion configuration with limited samples and measurement time
+fn configure_criterion() -> Criterion {
+    Criterion::default()
+        .sample_size(10)
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(2))
+}
+
+criterion_group! {
+    name = benches;
+    config = configure_criterion();
+    targets = registry_benchmarks
+}
+criterion_main!(benches);
@@ -4,7 +4,7 @@ mod registry_traits;
 
 pub use record_trait::{Record,RecordType};
 pub use registry_record_error::RegistryError;
-pub use registry_traits::Registry;
+pub use registry_traits::{Registry,RecordStore};
 
 // ======================================================================================================================================
 
@@ -1,14 +1,17 @@
 // src/registry/in_memory_registry.rs
 /// The `InMemoryRegistry` provides a thread-safe, in-memory implementation of the `Registry` trait.
-/// It uses a hash map to store records and a binary heap to manage expiration times.
+/// It uses sharded hash maps to store records and a binary heap to manage expiration times.
 ///
 /// # Generic Parameters
 /// * `R` - A type that implements the `Record` trait, representing the type of records the registry will manage.
-use crate::{Record,Registry,RegistryError};
+use crate::{Record,RecordStore,Registry,RegistryError};
 use async_trait::async_trait;
 use std::collections::{HashMap, BinaryHeap};
-use std::sync::{Arc, RwLock};
-use std::time::SystemTime;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::cmp::Ordering;
 use tokio::time::interval;
 use std::time::Duration;
@@ -53,29 +56,78 @@ impl ExpirationEntry {
     }
 }
 
-/// Internal data structure for the in-memory registry.
+/// Converts a timestamp to nanoseconds since the Unix epoch, saturating at the bounds.
+fn to_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX))
+}
+
+/// Number of independently locked buckets the records are spread across.
+const SHARD_COUNT: usize = 16;
+
+/// A bucket of records, keyed by identifier.
+type Shard<R> = RwLock<HashMap<String, R>>;
+
+/// Expiration bookkeeping shared by all shards.
+///
+/// Re-adding a record pushes a new heap entry without removing the old one, so
+/// `current` holds the expiration each live record was last added with. Heap entries
+/// that no longer match it are stale and are discarded when they reach the top.
 #[derive(Debug)]
-struct Inner<R: Record> {
-    records: HashMap<String, R>, // Keyed by identifier
+struct Expirations {
     heap: BinaryHeap<ExpirationEntry>,
+    current: HashMap<String, Option<SystemTime>>,
     capacity: usize,
 }
 
+impl Expirations {
+    /// Pops stale entries until the top of the heap (if any) is current.
+    fn discard_stale(&mut self) {
+        while let Some(top) = self.heap.peek() {
+            if self.current.get(&top.identifier) == Some(&top.expires_at) {
+                break;
+            }
+            self.heap.pop();
+        }
+    }
+
+    /// Pops the current entry that expires first and stops tracking its record.
+    fn pop_current(&mut self) -> Option<ExpirationEntry> {
+        self.discard_stale();
+        let entry = self.heap.pop()?;
+        self.current.remove(&entry.identifier);
+        Some(entry)
+    }
+}
+
 /// A thread-safe, in-memory registry implementation.
+///
+/// Records are spread over `SHARD_COUNT` buckets by a hash of their identifier, so
+/// operations on different records do not block each other. Expiration and capacity
+/// bookkeeping live behind a separate lock that is only held briefly. Lock order is
+/// always expirations before shards.
 #[derive(Debug, Clone)]
 pub struct InMemoryRegistry<R: Record> {
-    inner: Arc<RwLock<Inner<R>>>,
+    shards: Arc<Vec<Shard<R>>>,
+    expirations: Arc<Mutex<Expirations>>,
+    size: Arc<AtomicUsize>,
+    /// Earliest pending expiration (nanoseconds since the Unix epoch), so readers can
+    /// skip the expiration lock until something is actually due.
+    next_expiry: Arc<AtomicU64>,
 }
 
 impl<R: Record + 'static> InMemoryRegistry<R> {
     pub fn new(capacity: usize) -> Self {
-        let inner = Inner {
-            records: HashMap::new(),
-            heap: BinaryHeap::new(),
-            capacity,
-        };
+        let shards = (0..SHARD_COUNT).map(|_| RwLock::new(HashMap::new())).collect();
         let registry = Self {
-            inner: Arc::new(RwLock::new(inner)),
+            shards: Arc::new(shards),
+            expirations: Arc::new(Mutex::new(Expirations {
+                heap: BinaryHeap::new(),
+                current: HashMap::new(),
+                capacity,
+            })),
+            size: Arc::new(AtomicUsize::new(0)),
+            next_expiry: Arc::new(AtomicU64::new(u64::MAX)),
         };
 
         // Spawn a background task to remove expired records periodically
@@ -91,38 +143,81 @@ impl<R: Record + 'static> InMemoryRegistry<R> {
         registry
     }
 
+    /// Returns the shard responsible for `identifier`.
+    fn shard(&self, identifier: &str) -> &Shard<R> {
+        let mut hasher = DefaultHasher::new();
+        identifier.hash(&mut hasher);
+        &self.shards[(hasher.finish() as usize) % SHARD_COUNT]
+    }
+
+    /// Total number of records across all shards.
+    fn len(&self) -> usize {
+        self.size.load(AtomicOrdering::SeqCst)
+    }
+
+    /// Removes a record from its shard. `size` is updated under the shard lock, like
+    /// in `add`, so it can never be decremented before the matching increment.
+    fn remove_record(&self, identifier: &str) -> bool {
+        let mut shard = self.shard(identifier).write().unwrap();
+        let removed = shard.remove(identifier).is_some();
+        if removed {
+            self.size.fetch_sub(1, AtomicOrdering::SeqCst);
+        }
+        removed
+    }
+
+    /// Records the earliest expiration left in the heap after it changed.
+    fn refresh_next_expiry(&self, expirations: &mut Expirations) {
+        expirations.discard_stale();
+        let next = expirations
+            .heap
+            .peek()
+            .and_then(|entry| entry.expires_at)
+            .map_or(u64::MAX, to_nanos);
+        self.next_expiry.store(next, AtomicOrdering::SeqCst);
+    }
+
     fn remove_expired(&self) {
-        let mut guard = self.inner.write().unwrap();
         let now = SystemTime::now();
+        if to_nanos(now) < self.next_expiry.load(AtomicOrdering::SeqCst) {
+            return; // Nothing is due yet
+        }
+
+        let mut expirations = self.expirations.lock().unwrap();
 
-        while let Some(top) = guard.heap.peek() {
+        expirations.discard_stale();
+        while let Some(top) = expirations.heap.peek() {
             let is_expired = match top.expires_at {
                 Some(exp) => exp <= now,
                 None => false,
             };
 
             if is_expired {
-                let expired = guard.heap.pop().unwrap();
-                guard.records.remove(&expired.identifier);
+                let expired = expirations.pop_current().unwrap();
+                self.remove_record(&expired.identifier);
+                expirations.discard_stale();
             } else {
                 break; // Stop if the earliest expiration is in the future
             }
         }
+        self.refresh_next_expiry(&mut expirations);
     }
 
     fn enforce_capacity(&self) {
-        let mut guard = self.inner.write().unwrap();
+        let mut expirations = self.expirations.lock().unwrap();
+        let mut size = self.len();
         println!(
             "Enforcing capacity: Current size = {}, Capacity = {}",
-            guard.records.len(),
-            guard.capacity
+            size,
+            expirations.capacity
         );
 
         // Evict oldest records if still exceeding capacity
-        while guard.records.len() > guard.capacity {
-            if let Some(top) = guard.heap.pop() {
-                if guard.records.remove(&top.identifier).is_some() {
+        while size > expirations.capacity {
+            if let Some(top) = expirations.pop_current() {
+                if self.remove_record(&top.identifier) {
                     println!("Evicting record due to capacity: {}", top.identifier);
+                    size -= 1;
                 } else {
                     println!(
                         "Warning: Tried to evict a record not found in records: {}",
@@ -135,47 +230,8 @@ impl<R: Record + 'static> InMemoryRegistry<R> {
             }
         }
 
-        println!("After enforcing capacity: Current size = {}", guard.records.len());
-    }
-    #[allow(dead_code)]
-    #[deprecated]
-    fn remove_expired_and_enforce_capacity(&self) {
-        let mut guard = self.inner.write().unwrap();
-        let now = SystemTime::now();
-
-        println!(
-            "Starting cleanup: Current size = {}, Capacity = {}",
-            guard.records.len(),
-            guard.capacity
-        );
-
-        // Remove expired records
-        while let Some(top) = guard.heap.peek() {
-            let is_expired = match top.expires_at {
-                Some(exp) => exp <= now,
-                None => false,
-            };
-
-            if is_expired {
-                let expired = guard.heap.pop().unwrap();
-                if guard.records.remove(&expired.identifier).is_some() {
-                    println!("Removed expired record: {}", expired.identifier);
-                }
-            } else {
-                break; // Stop if the earliest expiration is in the future
-            }
-        }
-
-        // Enforce capacity BEFORE adding new records
-        while guard.records.len() > guard.capacity {
-            if let Some(top) = guard.heap.pop() {
-                if guard.records.remove(&top.identifier).is_some() {
-                    println!("Evicting record due to capacity: {}", top.identifier);
-                }
-            }
-        }
-
-        println!("After cleanup: Current size = {}", guard.records.len());
+        self.refresh_next_expiry(&mut expirations);
+        println!("After enforcing capacity: Current size = {}", size);
     }
 }
 
@@ -194,17 +250,28 @@ impl<R: Record + Send + Sync + 'static> Registry<R> for InMemoryRegistry<R>{
         let identifier = record.identifier();
         let expires_at = record.expires_at();
 
-        let mut guard = self.inner.write().unwrap();
+        let capacity = {
+            // Held across the insert, so a concurrent sweep never sees the new record
+            // alongside the expiration of the one it replaces.
+            let mut expirations = self.expirations.lock().unwrap();
 
-        // Insert or update the record
-        guard.records.insert(identifier.clone(), record.clone());
+            // Insert or update the record, counting it before the shard lock is released
+            {
+                let mut shard = self.shard(&identifier).write().unwrap();
+                if shard.insert(identifier.clone(), record).is_none() {
+                    self.size.fetch_add(1, AtomicOrdering::SeqCst);
+                }
+            }
 
-        // Insert into the heap
-        guard.heap.push(ExpirationEntry { expires_at, identifier });
+            // Supersedes any earlier entry, so a re-added record gets its full TTL again.
+            expirations.current.insert(identifier.clone(), expires_at);
+            expirations.heap.push(ExpirationEntry { expires_at, identifier });
+            self.refresh_next_expiry(&mut expirations);
+            expirations.capacity
+        };
 
         // Only enforce capacity if the registry size exceeds the capacity
-        if guard.records.len() > guard.capacity {
-            drop(guard); // Release write lock before calling `enforce_capacity`
+        if self.len() > capacity {
             self.enforce_capacity();
         }
 
@@ -217,17 +284,22 @@ impl<R: Record + Send + Sync + 'static> Registry<R> for InMemoryRegistry<R>{
         // Remove expired records first
         self.remove_expired();
 
-        let guard = self.inner.read().unwrap();
-        guard.records.get(identifier).cloned()
+        let guard = self.shard(identifier).read().unwrap();
+        guard.get(identifier).cloned()
     }
 
     /// Lists all records in the registry.
+    ///
+    /// All shards are read-locked together so the result is a consistent snapshot,
+    /// ordered by identifier.
     async fn list(&self) -> Vec<R> {
         // Remove expired records first
         self.remove_expired();
 
-        let guard = self.inner.read().unwrap();
-        guard.records.values().cloned().collect()
+        let guards: Vec<_> = self.shards.iter().map(|shard| shard.read().unwrap()).collect();
+        let mut records: Vec<(&String, &R)> = guards.iter().flat_map(|guard| guard.iter()).collect();
+        records.sort_by(|a, b| a.0.cmp(b.0));
+        records.into_iter().map(|(_, record)| record.clone()).collect()
     }
 
     /// Removes a record from the registry.
@@ -239,38 +311,43 @@ impl<R: Record + Send + Sync + 'static> Registry<R> for InMemoryRegistry<R>{
     /// * `Ok(())` - If the record is removed successfully.
     /// * `Err(RegistryError)` - If an error occurs.
     async fn remove(&self, identifier: &str) -> Result<(), RegistryError> {
-        let mut guard = self.inner.write().unwrap();
-        guard.records.remove(identifier);
-        // Note: Removing from the heap is not straightforward. For simplicity, we can leave it as is.
-        // Alternatively, implement a more sophisticated heap structure that allows removal.
+        let mut expirations = self.expirations.lock().unwrap();
+        // The record's heap entries become stale and are dropped as they surface.
+        expirations.current.remove(identifier);
+        self.remove_record(identifier);
+        self.refresh_next_expiry(&mut expirations);
         Ok(())
     }
 
     /// Updates the capacity of the registry.
     async fn set_capacity(&self, capacity: usize) {
-        let mut guard = self.inner.write().unwrap();
-        guard.capacity = capacity;
+        let mut expirations = self.expirations.lock().unwrap();
+        expirations.capacity = capacity;
         // Enforce new capacity
-        while guard.records.len() > guard.capacity {
-            if let Some(top) = guard.heap.pop() {
-                guard.records.remove(&top.identifier);
+        let mut size = self.len();
+        while size > expirations.capacity {
+            if let Some(top) = expirations.pop_current() {
+                if self.remove_record(&top.identifier) {
+                    size -= 1;
+                }
             } else {
                 break;
             }
         }
+        self.refresh_next_expiry(&mut expirations);
     }
 
     /// Retrieves the current capacity of the registry.
     async fn get_capacity(&self) -> usize {
-        let guard = self.inner.read().unwrap();
-        guard.capacity
+        self.expirations.lock().unwrap().capacity
     }
 
     async fn remove_lru(&self) -> Result<(), RegistryError> {
-        let mut guard = self.inner.write().unwrap();
+        let mut expirations = self.expirations.lock().unwrap();
 
-        if let Some(oldest) = guard.heap.pop() {
-            if guard.records.remove(&oldest.identifier).is_some() {
+        if let Some(oldest) = expirations.pop_current() {
+            self.refresh_next_expiry(&mut expirations);
+            if self.remove_record(&oldest.identifier) {
                 println!("LRU Evicted: {}", oldest.identifier);
                 Ok(())
             } else {
@@ -280,4 +357,25 @@ impl<R: Record + Send + Sync + 'static> Registry<R> for InMemoryRegistry<R>{
             Err(RegistryError::GenericError("No records to remove".to_string()))
         }
     }
-}
\ No newline at end of file
+}
+
+/// The in-memory registry cannot fail on lookups, so `RecordStore` just wraps the
+/// `Registry` results.
+#[async_trait]
+impl<R: Record + Send + Sync + 'static> RecordStore<R> for InMemoryRegistry<R> {
+    async fn add(&self, record: R) -> Result<(), RegistryError> {
+        Registry::add(self, record).await
+    }
+
+    async fn get(&self, identifier: &str) -> Result<Option<R>, RegistryError> {
+        Ok(Registry::get(self, identifier).await)
+    }
+
+    async fn remove(&self, identifier: &str) -> Result<(), RegistryError> {
+        Registry::remove(self, identifier).await
+    }
+
+    async fn list(&self) -> Result<Vec<R>, RegistryError> {
+        Ok(Registry::list(self).await)
+    }
+}
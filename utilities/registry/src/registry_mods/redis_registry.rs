@@ -1,5 +1,5 @@
 #[cfg(feature = "redis_registry")]
-use crate::{Record, RegistryError,Registry};
+use crate::{Record, RecordStore, RegistryError,Registry};
 #[cfg(feature = "redis_registry")]
 use deadpool_redis::Pool;
 #[cfg(feature = "redis_registry")]
@@ -14,6 +14,10 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 #[cfg(feature = "redis_registry")]
 use tokio::sync::RwLock;
+/// Upper bound on the connection check performed by `RedisRegistry::new`.
+#[cfg(feature = "redis_registry")]
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[cfg(feature = "redis_registry")]
 #[derive(Debug, Clone)]
 pub struct RedisRegistry<R: Record> {
@@ -132,6 +136,52 @@ impl<R: Record + Send + Sync + 'static> Registry<R> for RedisRegistry<R> {
     }
 }
 
+#[cfg(feature = "redis_registry")]
+#[async_trait::async_trait]
+impl<R: Record + Send + Sync + 'static> RecordStore<R> for RedisRegistry<R> {
+    async fn add(&self, record: R) -> Result<(), RegistryError> {
+        Registry::add(self, record).await
+    }
+
+    async fn get(&self, identifier: &str) -> Result<Option<R>, RegistryError> {
+        let mut conn = self.connection().await?;
+        let key = format!("record:{}", identifier);
+        let serialized: Option<String> = conn.get(key).await.map_err(|e| {
+            RegistryError::BackendError(format!("Failed to get key from Redis: {}", e))
+        })?;
+
+        serialized
+            .map(|s| serde_json::from_str::<R>(&s))
+            .transpose()
+            .map_err(|e| RegistryError::SerializationError(e.to_string()))
+    }
+
+    async fn remove(&self, identifier: &str) -> Result<(), RegistryError> {
+        Registry::remove(self, identifier).await
+    }
+
+    async fn list(&self) -> Result<Vec<R>, RegistryError> {
+        let mut conn = self.connection().await?;
+        let keys: Vec<String> = conn.keys("record:*").await.map_err(|e| {
+            RegistryError::BackendError(format!("Failed to list keys in Redis: {}", e))
+        })?;
+
+        let mut records = Vec::with_capacity(keys.len());
+        for key in keys {
+            let serialized: Option<String> = conn.get(key).await.map_err(|e| {
+                RegistryError::BackendError(format!("Failed to get key from Redis: {}", e))
+            })?;
+            // A key can expire between KEYS and GET.
+            if let Some(serialized) = serialized {
+                let record = serde_json::from_str::<R>(&serialized)
+                    .map_err(|e| RegistryError::SerializationError(e.to_string()))?;
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
 #[cfg(feature = "redis_registry")]
 impl<R: Record + Send + Sync + 'static> RedisRegistry<R> {
     /// Creates a new RedisRegistry instance.
@@ -151,7 +201,11 @@ impl<R: Record + Send + Sync + 'static> RedisRegistry<R> {
         let config = deadpool_redis::Config::from_url(redis_url);
         let pool = config
             .create_pool(Some(deadpool_redis::Runtime::Tokio1))
-            .map_err(|e| RegistryError::Custom(format!("Failed to create Redis pool: {}", e)))?;
+            .map_err(|e| RegistryError::Connection(format!("Failed to create Redis pool: {}", e)))?;
+
+        // The pool connects lazily, so ping once to surface bad URLs and unreachable
+        // servers here rather than on the first registry operation.
+        Self::ping(&pool, redis_url).await?;
 
         Ok(Self {
             pool,
@@ -160,6 +214,30 @@ impl<R: Record + Send + Sync + 'static> RedisRegistry<R> {
             expiration_key: expiration_key.to_string(),
         })
     }
+    /// Checks out a connection and sends PING, bounded by `CONNECT_TIMEOUT`.
+    async fn ping(pool: &Pool, redis_url: &str) -> Result<(), RegistryError> {
+        let ping = async {
+            let mut conn = pool.get().await.map_err(|e| {
+                RegistryError::Connection(format!("Failed to connect to {}: {}", redis_url, e))
+            })?;
+            let _: String = redis::cmd("PING").query_async(&mut conn).await.map_err(|e| {
+                RegistryError::Connection(format!("PING to {} failed: {}", redis_url, e))
+            })?;
+            Ok(())
+        };
+
+        tokio::time::timeout(CONNECT_TIMEOUT, ping).await.map_err(|_| {
+            RegistryError::Connection(format!("Timed out connecting to {}", redis_url))
+        })?
+    }
+
+    /// Checks out a pooled connection.
+    async fn connection(&self) -> Result<deadpool_redis::Connection, RegistryError> {
+        self.pool.get().await.map_err(|e| {
+            RegistryError::Connection(format!("Failed to get Redis connection: {}", e))
+        })
+    }
+
     pub async fn get_pool(&self) -> Result<deadpool_redis::Pool, RegistryError> {
         Ok(self.pool.clone())
     }
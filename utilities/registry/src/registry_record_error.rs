@@ -17,6 +17,9 @@ pub enum RegistryError {
     /// The registry has reached its maximum capacity.
     CapacityExceeded,
 
+    /// The backend could not be reached or the connection settings are invalid.
+    Connection(String),
+
     /// A generic error occurred.
     GenericError(String),
 
@@ -30,6 +33,7 @@ impl fmt::Display for RegistryError {
             RegistryError::BackendError(msg) => write!(f, "Backend error: {}", msg),
             RegistryError::RecordNotFound(id) => write!(f, "Record not found: {}", id),
             RegistryError::CapacityExceeded => write!(f, "Registry capacity exceeded"),
+            RegistryError::Connection(msg) => write!(f, "Connection error: {}", msg),
             RegistryError::GenericError(msg) => write!(f, "Error: {}", msg),
             RegistryError::Custom(msg)=>write!(f,"User-Defined Error : {}",msg)
         }
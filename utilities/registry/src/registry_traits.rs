@@ -56,3 +56,26 @@ pub trait Registry<R: Record>: Send + Sync {
     /// Implements a LRU -> Last-Recent-Update Policy into the Registry
     async fn remove_lru(&self) -> Result<(), RegistryError>;
 }
+
+/// The storage operations a consumer such as the mDNS registry needs, with every
+/// backend failure reported as a `RegistryError`.
+///
+/// Unlike `Registry`, lookups do not fold backend errors into `None` or an empty list,
+/// so callers can tell a missing record from an unreachable store. Implemented for
+/// `InMemoryRegistry` and, with `redis_registry`, for `RedisRegistry`, so both can be
+/// used behind an `Arc<dyn RecordStore<R>>`. The `Send + Sync` bound lets such an
+/// `Arc` be cloned into spawned tasks, and the returned futures are `Send` as well.
+#[async_trait]
+pub trait RecordStore<R: Record>: Send + Sync {
+    /// Adds or updates a record.
+    async fn add(&self, record: R) -> Result<(), RegistryError>;
+
+    /// Retrieves a record by its identifier; `Ok(None)` if there is none.
+    async fn get(&self, identifier: &str) -> Result<Option<R>, RegistryError>;
+
+    /// Removes a record by its identifier.
+    async fn remove(&self, identifier: &str) -> Result<(), RegistryError>;
+
+    /// Lists all records.
+    async fn list(&self) -> Result<Vec<R>, RegistryError>;
+}
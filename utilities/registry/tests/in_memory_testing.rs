@@ -59,6 +59,22 @@ mod tests {
         assert!(retrieved.is_none(), "Record should have expired and been removed.");
     }
 
+    #[tokio::test]
+    async fn test_readvertised_record_stays_alive() {
+        let registry = InMemoryRegistry::new(10);
+
+        // Re-advertise a 2-second record every second, well past its original expiry.
+        for _ in 0..6 {
+            registry.add(ServiceRecord::new("refreshed", "http", 8080, Some(2))).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            assert!(registry.get("refreshed").await.is_some(), "Re-advertised record expired early.");
+        }
+
+        // Once re-advertisement stops, the last TTL runs out.
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(registry.get("refreshed").await.is_none(), "Record should expire after re-advertisement stops.");
+    }
+
     #[tokio::test]
     async fn test_capacity_enforcement() {
         let registry = InMemoryRegistry::new(2);
@@ -222,5 +238,66 @@ mod tests {
         assert!(records.iter().any(|r| r.identifier() == "service3"));
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_add_remove_keeps_other_records() {
+        let registry = InMemoryRegistry::new(100);
+        for i in 0..10 {
+            registry.add(ServiceRecord::new(&format!("stable{}", i), "http", 8000, None)).await.unwrap();
+        }
+
+        // Adds and removes of the same ids race each other across threads.
+        let mut tasks = Vec::new();
+        for worker in 0..8 {
+            let registry = registry.clone();
+            tasks.push(tokio::spawn(async move {
+                for round in 0..500 {
+                    let id = format!("churn{}", (worker + round) % 4);
+                    if worker % 2 == 0 {
+                        registry.add(ServiceRecord::new(&id, "http", 9000, None)).await.unwrap();
+                    } else {
+                        registry.remove(&id).await.unwrap();
+                    }
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
 
-}
\ No newline at end of file
+        // A miscounted size would have pushed the registry over capacity and evicted these.
+        let records = registry.list().await;
+        for i in 0..10 {
+            let id = format!("stable{}", i);
+            assert!(records.iter().any(|r| r.identifier() == id), "{} was evicted", id);
+        }
+        assert!(records.len() <= 14);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_readd_survives_concurrent_expiry_sweep() {
+        let registry = InMemoryRegistry::new(100);
+
+        for round in 0..2000 {
+            // Already expired, so the next sweep targets it.
+            registry.add(ServiceRecord::new("flapping", "http", 8000, Some(0))).await.unwrap();
+
+            let start = std::sync::Arc::new(tokio::sync::Barrier::new(2));
+            let (adder, adder_start) = (registry.clone(), start.clone());
+            let readd = tokio::spawn(async move {
+                adder_start.wait().await;
+                adder.add(ServiceRecord::new("flapping", "http", 8000, None)).await.unwrap();
+            });
+            let sweeper = registry.clone();
+            let sweep = tokio::spawn(async move {
+                start.wait().await;
+                for _ in 0..50 {
+                    sweeper.get("unrelated").await;
+                }
+            });
+            readd.await.unwrap();
+            sweep.await.unwrap();
+
+            assert!(registry.get("flapping").await.is_some(), "re-added record was swept in round {}", round);
+        }
+    }
+}
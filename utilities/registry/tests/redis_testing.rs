@@ -4,7 +4,7 @@
 mod tests {
     #[cfg(feature = "redis_registry")]
     use registry::RedisRegistry;
-    use registry::{Record, Registry};
+    use registry::{Record, Registry, RegistryError};
     use serde::{Deserialize, Serialize};
     use std::time::{SystemTime};
     use redis::AsyncCommands;
@@ -125,6 +125,17 @@ mod tests {
         );
     }
     #[tokio::test]
+    async fn test_redis_unreachable_host_fails_eagerly() {
+        let result =
+            RedisRegistry::<ServiceRecord>::new("redis://nonexistent-host:1/", 10, "expiration_key").await;
+
+        match result {
+            Err(RegistryError::Connection(msg)) => assert!(msg.contains("nonexistent-host")),
+            Err(other) => panic!("Expected a connection error, got {}", other),
+            Ok(_) => panic!("Registry initialization should fail for an unreachable host."),
+        }
+    }
+    #[tokio::test]
     async fn test_redis_serialization_error() {
         #[derive(Serialize, Deserialize, Clone)]
         struct InvalidRecord {
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod tests {
+    use registry::{InMemoryRegistry, Record, RecordStore};
+    use serde::{Deserialize, Serialize};
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct PeerRecord {
+        id: String,
+    }
+
+    impl Record for PeerRecord {
+        fn identifier(&self) -> String {
+            self.id.clone()
+        }
+
+        fn expires_at(&self) -> Option<SystemTime> {
+            None
+        }
+    }
+
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    #[tokio::test]
+    async fn test_record_store_trait_object_moves_into_task() {
+        assert_send_sync::<dyn RecordStore<PeerRecord>>();
+
+        let store: Arc<dyn RecordStore<PeerRecord> + Send + Sync> = Arc::new(InMemoryRegistry::new(4));
+        let writer = Arc::clone(&store);
+        tokio::spawn(async move {
+            writer.add(PeerRecord { id: "peer-1".to_string() }).await.unwrap();
+        })
+        .await
+        .unwrap();
+
+        assert!(store.get("peer-1").await.unwrap().is_some());
+    }
+}